@@ -11,20 +11,91 @@
 #import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
 #import bevy_render::globals::Globals
 
+// Must match `VideoGlitchUniform` in src/settings.rs, field for field. The
+// byte offset of each field is noted after it; the total size is pinned by
+// `WGSL_SETTINGS_SIZE` there, which fails to compile when the two drift apart,
+// and the tests there check every offset.
 struct VideoGlitchSettings {
-    intensity: f32, // 4
-    color_aberration: mat3x3<f32>, // 9 * 4
-#ifdef SIXTEEN_BYTE_ALIGNMENT
-    // WebGL2 structs must be 16 byte aligned.
-    _webgl2_padding: vec2<u32>
-#endif
-}
+    intensity: f32, // 0
+    speed: f32, // 4
+    displacement: f32, // 8
+    aberration_offset: f32, // 12
+    // The columns of the aberration matrix, with its cycle speed in the first
+    // column's w and its scope in the second's. The third's w is the full
+    // image U left of which the source is shown untouched, negative without a
+    // `GlitchComparisonSplit`.
+    color_aberration: array<vec4<f32>, 3>, // 16
+    interference: f32, // 64
+    scanlines: f32, // 68
+    // How far a `GlitchWipe` has swept, negative without one.
+    wipe_progress: f32, // 72
+    // The `*_FLAG` bits, the number of exclusion rects from
+    // `EXCLUSION_COUNT_SHIFT` and the aberration angle from
+    // `ABERRATION_ANGLE_SHIFT`. The bits between select shader defs instead.
+    flags: u32, // 76
+    // Full image UV rects as (min.x, min.y, max.x, max.y).
+    exclusion_rects: array<vec4<f32>, 4>, // 80
+    // The camera's viewport in texture UV as (origin.x, origin.y, size.x, size.y).
+    viewport: vec4<f32>, // 144
+    // The time from a `GlitchTimeOverride` or `GlitchClock`.
+    time: f32, // 160
+    // The frame rate the time is held to, 0 for continuous time.
+    effect_fps: f32, // 164
+    // Where the effect fades in with distance, in multiples of the near plane
+    // of the camera's perspective projection. Both 0 for other projections.
+    depth_near: f32, // 168
+    depth_far: f32, // 172
+    // The camera's viewport within the full image of a `SubCameraView` in
+    // full image UV as (origin.x, origin.y, size.x, size.y), the whole image
+    // without one.
+    sub_view: vec4<f32>, // 176
+    // The hotspots in view as (full image UV, radius as a fraction of the
+    // full image height, strength). Unused ones have no strength.
+    hotspots: array<vec4<f32>, 3>, // 192
+    // The fraction of its radius over which each hotspot fades out, packed as
+    // unorm8s.
+    hotspot_falloff: u32, // 240
+    // The direction a `GlitchWipe` sweeps in, in radians counterclockwise from
+    // the right.
+    wipe_angle: f32, // 244
+    // The width of a `GlitchWipe`'s edge, as a fraction of the full image
+    // height.
+    wipe_edge: f32, // 248
+    // The fill color of a `GlitchWipe` as packed sRGBA8, transparent without
+    // one.
+    wipe_fill: u32, // 252
+} // 256 bytes
+
+// Snap offsets to whole pixels.
+const PIXEL_SNAP_FLAG: u32 = 1u;
+// Use `time` instead of the globals' time.
+const USE_TIME_FLAG: u32 = 2u;
+// Fade the effect out with distance instead.
+const DEPTH_INVERT_FLAG: u32 = 4u;
+// Shift rows to the left instead of to the right.
+const TEAR_LEFT_FLAG: u32 = 8u;
+// Where `flags` holds the number of exclusion rects, in 4 bits.
+const EXCLUSION_COUNT_SHIFT: u32 = 4u;
+// Where `flags` holds the direction the secondary colors are shifted in, in
+// its top 13 bits as steps of a turn counterclockwise from the right.
+const ABERRATION_ANGLE_SHIFT: u32 = 19u;
+const ABERRATION_ANGLE_STEP: f32 = 6.2831855 / 8192.0;
+
+// In the order of the entries of `video_glitch_bind_group_layout` in src/lib.rs.
 
 @group(0) @binding(0) var screen_texture: texture_2d<f32>;
 @group(0) @binding(1) var texture_sampler: sampler;
+#ifdef PUSH_CONSTANTS
+var<push_constant> settings: VideoGlitchSettings;
+#else
 @group(0) @binding(2) var<uniform> settings: VideoGlitchSettings;
+#endif
 @group(0) @binding(3) var<uniform> globals: Globals;
+@group(0) @binding(4) var mask_texture: texture_2d<f32>;
+@group(0) @binding(5) var depth_texture: texture_depth_2d;
 
+// The noise below is mirrored on the CPU in `src/signal.rs` for
+// `GlitchSignal`. Keep the two in lockstep.
 fn mod289(x: vec3<f32>) -> vec3<f32> {
     return x - floor(x * (1.0 / 289.0)) * 289.0;
 }
@@ -79,44 +150,543 @@ fn snoise(v: vec2<f32>) -> f32 {
     return 130.0 * dot(m, g);
 }
 
+// The time in seconds, held to the effect's frame rate.
+fn held_time() -> f32 {
+    var time = select(globals.time, settings.time, (settings.flags & USE_TIME_FLAG) != 0u);
+    if (settings.effect_fps > 0.0) {
+        time = floor(time * settings.effect_fps) / settings.effect_fps;
+    }
+    return time;
+}
+
+// The time driving the effect, held to the effect's frame rate and scaled by
+// its speed.
+fn effect_time() -> f32 {
+    return held_time() * settings.speed;
+}
+
 fn rand(co: vec2<f32>) -> f32 {
     return fract(sin(dot(co.xy, vec2<f32>(12.9898, 78.233))) * 43758.5453);
 }
 
-@fragment
-fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32>
-{
-    let uv = in.uv;
-    let time: f32 = globals.time * 2.0;
+// Smoothly interpolated random values at whole `p`, the time and the row,
+// from -1 to 1.
+fn value_noise(p: vec2<f32>) -> f32 {
+    let i = floor(p);
+    let f = p - i;
+    let u = f * f * (3.0 - 2.0 * f);
+    let a = mix(rand(i), rand(i + vec2<f32>(1.0, 0.0)), u.x);
+    let b = mix(rand(i + vec2<f32>(0.0, 1.0)), rand(i + vec2<f32>(1.0, 1.0)), u.x);
+    return mix(a, b, u.y) * 2.0 - 1.0;
+}
+
+// The cells per unit of time and row of the noise `p`, and across the
+// viewport, for `GlitchNoiseMode::Cell`.
+const CELL_SIZE: vec3<f32> = vec3<f32>(4.0, 40.0, 8.0);
 
+// A random value per block of the viewport and step of time, from -1 to 1.
+// Two hashes are averaged, which spreads the values like the other modes'
+// instead of evenly, so as many blocks tear as rows do there.
+fn cell_noise(p: vec2<f32>, x: f32) -> f32 {
+    let cell = floor(vec3<f32>(p, x) * CELL_SIZE);
+    let co = cell.xy + cell.z * vec2<f32>(37.0, 17.0);
+    return rand(co) + rand(co + vec2<f32>(0.5, 0.25)) - 1.0;
+}
+
+// The noise the tears are made of at `p`, the time and the row, from -1 to
+// 1, for the camera's `GlitchNoiseMode`. `x` is the viewport U, which only
+// cell noise depends on.
+fn noise_field(p: vec2<f32>, x: f32) -> f32 {
+#ifdef NOISE_VALUE
+    return value_noise(p);
+#else
+#ifdef NOISE_CELL
+    return cell_noise(p, x);
+#else
+    return snoise(p);
+#endif
+#endif
+}
+
+// Large, incidental noise waves offset by smaller, constant noise waves, at a
+// viewport UV.
+fn row_noise(uv: vec2<f32>, time: f32) -> f32 {
     // Create large, incidental noise waves
-    var noise: f32 = max(0.0, snoise(vec2<f32>(time, uv.y * 0.3)) - 0.3) * (1.0 / 0.7);
+    var noise: f32 = max(0.0, noise_field(vec2<f32>(time, uv.y * 0.3), uv.x) - 0.3) * (1.0 / 0.7);
 
     // Offset by smaller, constant noise waves
-    noise = noise + (snoise(vec2<f32>(time * 10.0, uv.y * 2.4)) - 0.5) * 0.15;
-    noise *= settings.intensity;
+    noise = noise + (noise_field(vec2<f32>(time * 10.0, uv.y * 2.4), uv.x) - 0.5) * 0.15;
+    return noise;
+}
+
+// The part of the texture the current fragment may sample: its camera's
+// viewport if the fragment is inside of it, the whole texture otherwise.
+var<private> sample_min: vec2<f32>;
+var<private> sample_max: vec2<f32>;
+
+// Map a fragment's texture UV to the UV inside its camera's viewport, which
+// is what all of the effect's UV-space math works with, and restrict
+// `sample_screen` to the viewport.
+fn enter_viewport(uv: vec2<f32>) -> vec2<f32> {
+    let local = (uv - settings.viewport.xy) / settings.viewport.zw;
+    let inside = viewport_mask(local) > 0.0;
+    sample_min = select(vec2<f32>(0.0), settings.viewport.xy, inside);
+    sample_max = select(vec2<f32>(1.0), settings.viewport.xy + settings.viewport.zw, inside);
+    return local;
+}
+
+// 1 inside the camera's viewport and 0 outside of it, where the texture
+// belongs to other cameras and must pass through untouched.
+fn viewport_mask(local: vec2<f32>) -> f32 {
+    return select(0.0, 1.0, all(local >= vec2<f32>(0.0)) && all(local <= vec2<f32>(1.0)));
+}
+
+// Sample the screen at a viewport UV.
+fn sample_screen(local: vec2<f32>) -> vec4<f32> {
+    let uv = settings.viewport.xy + local * settings.viewport.zw;
+    return textureSample(screen_texture, texture_sampler, clamp(uv, sample_min, sample_max));
+}
+
+// The size of the camera's viewport in pixels of its target.
+fn viewport_pixels() -> vec2<f32> {
+    return vec2<f32>(textureDimensions(screen_texture)) * settings.viewport.zw;
+}
+
+// Map a viewport UV to the UV of the full image the viewport is a tile of,
+// with a `SubCameraView`. The noise, overlay, hotspots, exclusion rects, mask,
+// wipe and comparison are placed in this space so tiles stitch seamlessly.
+// Without a sub view it is the viewport UV.
+fn full_uv(uv: vec2<f32>) -> vec2<f32> {
+    return settings.sub_view.xy + uv * settings.sub_view.zw;
+}
+
+// The size of the full image in pixels of the viewport's target.
+fn full_pixels() -> vec2<f32> {
+    return viewport_pixels() / settings.sub_view.zw;
+}
+
+// Round a horizontal offset in viewport UV to whole pixels when snapping.
+fn snap_offset(offset: f32) -> f32 {
+    let width = viewport_pixels().x;
+    return select(offset, round(offset * width) / width, (settings.flags & PIXEL_SNAP_FLAG) != 0u);
+}
+
+// Round an offset in viewport UV to whole pixels when snapping.
+fn snap_shift(offset: vec2<f32>) -> vec2<f32> {
+    let pixels = viewport_pixels();
+    return select(offset, round(offset * pixels) / pixels, (settings.flags & PIXEL_SNAP_FLAG) != 0u);
+}
+
+// Move a viewport UV row to the center of its pixel row when snapping, so the
+// noise is constant over each pixel row.
+fn snap_row(y: f32) -> f32 {
+    let height = viewport_pixels().y;
+    return select(y, (floor(y * height) + 0.5) / height, (settings.flags & PIXEL_SNAP_FLAG) != 0u);
+}
+
+// The width of the border over which the effect fades in around exclusion
+// rects, in UV units.
+const EXCLUSION_FEATHER: f32 = 0.01;
+
+// How much of the effect applies at `uv`: 0 inside the exclusion rects, fading
+// to 1 over a small feathered border outside of them.
+fn exclusion(uv: vec2<f32>) -> f32 {
+    var mask = 1.0;
+#ifdef EXCLUSION_RECTS
+    let full = full_uv(uv);
+    let count = (settings.flags >> EXCLUSION_COUNT_SHIFT) & 0xfu;
+    for (var i = 0u; i < count; i++) {
+        let rect = settings.exclusion_rects[i];
+        let outside = max(rect.xy - full, full - rect.zw);
+        mask = min(mask, smoothstep(0.0, EXCLUSION_FEATHER, max(outside.x, outside.y)));
+    }
+#endif
+    return mask;
+}
+
+#ifdef WIPE
+// How far behind the front of the wipe a viewport UV is, in full image
+// heights. The front starts just outside the full image at progress 0 and has
+// moved past it far enough for the edge, and the fill's ramp behind it, at 1.
+fn behind_wipe(uv: vec2<f32>) -> f32 {
+    let pixels = full_pixels();
+    let aspect = pixels.x / pixels.y;
+    // UV has y down.
+    let direction = vec2<f32>(cos(settings.wipe_angle), -sin(settings.wipe_angle));
+    let position = dot((full_uv(uv) - 0.5) * vec2<f32>(aspect, 1.0), direction);
+    // Half the extent of the full image along the direction.
+    let reach = 0.5 * (abs(direction.x) * aspect + abs(direction.y));
+    let trail = select(1.0, 2.0, wipe_fill().a > 0.0) * settings.wipe_edge;
+    let front = -reach + settings.wipe_progress * (2.0 * reach + trail);
+    return front - position;
+}
+
+// The wipe's fill color in linear space.
+fn wipe_fill() -> vec4<f32> {
+    let srgb = unpack4x8unorm(settings.wipe_fill);
+    let linear = select(
+        pow((srgb.rgb + 0.055) / 1.055, vec3<f32>(2.4)),
+        srgb.rgb / 12.92,
+        srgb.rgb <= vec3<f32>(0.04045),
+    );
+    return vec4<f32>(linear, srgb.a);
+}
+#endif
+
+// Fade `glitched` to the wipe's fill color over the edge width behind the
+// edge of the glitch, in the last pass.
+fn fill_wipe(uv: vec2<f32>, glitched: vec4<f32>) -> vec4<f32> {
+#ifdef WIPE
+    let fill = wipe_fill();
+    let edge = settings.wipe_edge;
+    let amount = smoothstep(edge, 2.0 * edge, behind_wipe(uv)) * fill.a * viewport_mask(uv);
+    return vec4<f32>(mix(glitched.rgb, fill.rgb, amount), max(glitched.a, amount));
+#else
+    return glitched;
+#endif
+}
+
+// The intensity at a viewport UV: the settings' intensity, raised inside the
+// hotspots and limited to the part a wipe has swept.
+fn intensity(uv: vec2<f32>) -> f32 {
+    var intensity = settings.intensity;
+#ifdef HOTSPOTS
+    let pixels = full_pixels();
+    let aspect = vec2<f32>(pixels.x / pixels.y, 1.0);
+    let full = full_uv(uv);
+    let falloffs = unpack4x8unorm(settings.hotspot_falloff);
+    for (var i = 0u; i < 3u; i++) {
+        let hotspot = settings.hotspots[i];
+        let distance = length((full - hotspot.xy) * aspect) / max(hotspot.z, 1e-6);
+        let falloff = falloffs[i];
+        intensity += hotspot.w * (1.0 - smoothstep(1.0 - falloff, 1.0, distance));
+    }
+#endif
+#ifdef WIPE
+    intensity *= smoothstep(0.0, settings.wipe_edge, behind_wipe(uv));
+#endif
+    return intensity;
+}
+
+// The intensity scale painted in the mask texture, white without a mask.
+fn mask(uv: vec2<f32>) -> f32 {
+#ifdef MASK
+    // The mask is stretched over the full image.
+    return textureSampleLevel(mask_texture, texture_sampler, full_uv(uv), 0.0).r;
+#else
+    return 1.0;
+#endif
+}
+
+// The intensity scale from the distance to the scene at a texture UV, 1
+// without a depth prepass.
+fn depth_fade(texture_uv: vec2<f32>) -> f32 {
+#ifdef DEPTH
+    // The depth prepass has the size of the main texture, which differs from
+    // the target of a scaled view.
+    let size = textureDimensions(depth_texture);
+    let coords = min(vec2<u32>(texture_uv * vec2<f32>(size)), size - 1u);
+    let depth = textureLoad(depth_texture, coords, 0);
+    // Bevy's perspective projections use an infinite, reversed depth, where
+    // the view space depth is `near / depth`. The sky is at depth 0. The
+    // distances are in multiples of `near`.
+    let distance = 1.0 / max(depth, 1e-7);
+    let fade = saturate((distance - settings.depth_near) / (settings.depth_far - settings.depth_near));
+    return select(fade, 1.0 - fade, (settings.flags & DEPTH_INVERT_FLAG) != 0u);
+#else
+    return 1.0;
+#endif
+}
+
+// The aberration matrix, with its channels rotated by the cycle. Rotating
+// between two permutations of the channels mixes them, and a mix of
+// permutations keeps the sums of the rows and columns.
+fn aberration() -> mat3x3<f32> {
+    let columns = settings.color_aberration;
+    let C = mat3x3<f32>(columns[0].xyz, columns[1].xyz, columns[2].xyz);
+    let cycle_speed = columns[0].w;
+    if (cycle_speed == 0.0) {
+        return C;
+    }
+    // Moves red to green, green to blue and blue to red.
+    let S = mat3x3<f32>(vec3<f32>(0.0, 1.0, 0.0), vec3<f32>(0.0, 0.0, 1.0), vec3<f32>(1.0, 0.0, 0.0));
+    let identity = mat3x3<f32>(vec3<f32>(1.0, 0.0, 0.0), vec3<f32>(0.0, 1.0, 0.0), vec3<f32>(0.0, 0.0, 1.0));
+    var rotations = array<mat3x3<f32>, 3>(identity, S, S * S);
+    let phase = fract(held_time() * cycle_speed) * 3.0;
+    let step = u32(phase) % 3u;
+    let t = smoothstep(0.0, 1.0, fract(phase));
+    let P = rotations[step] * (1.0 - t) + rotations[(step + 1u) % 3u] * t;
+    return P * C;
+}
+
+// Replace the secondary colors `C[1]` and `C[2]` of `color` with the ones
+// sampled at the shifted positions. The primary color `C[0]` is untouched, and
+// when the shifted samples match `color` so is the result.
+fn aberrate(color: vec3<f32>, shifted_a: vec3<f32>, shifted_b: vec3<f32>) -> vec3<f32> {
+    let C = aberration();
+    return color
+        + C[1] * (dot(C[1], shifted_a) - dot(C[1], color))
+        + C[2] * (dot(C[2], shifted_b) - dot(C[2], color));
+}
+
+// The texture taps per pixel, set by the camera's `GlitchQuality`.
+const GLITCH_TAPS: u32 = #{GLITCH_TAPS}u;
+
+// The direction the secondary colors are shifted in, scaled so that a shift
+// of 1 is a viewport width in any direction. Horizontal at an angle of 0.
+fn aberration_direction() -> vec2<f32> {
+    let angle = f32(settings.flags >> ABERRATION_ANGLE_SHIFT) * ABERRATION_ANGLE_STEP;
+    let pixels = viewport_pixels();
+    // Viewport UV has y down.
+    return vec2<f32>(cos(angle), -sin(angle) * pixels.x / pixels.y);
+}
 
-    // Apply the noise as x displacement for every line
-    let xpos: f32 = uv.x - noise * noise * 0.25;
-    let texColor: vec4<f32> = textureSample(screen_texture, texture_sampler, vec2<f32>(xpos, uv.y));
+// Sample the screen shifted by `offset` along the aberration direction. With
+// more than one tap per secondary color the samples are spread evenly up to
+// the full shift and averaged.
+fn sample_shifted(uv: vec2<f32>, offset: f32) -> vec4<f32> {
+    let taps = max(GLITCH_TAPS / 2u, 1u);
+    let direction = aberration_direction();
+    var sum = vec4<f32>(0.0);
+    for (var i = 1u; i <= taps; i++) {
+        let shift = snap_shift(direction * offset * f32(i) / f32(taps));
+        sum += sample_screen(uv + shift);
+    }
+    return sum / f32(taps);
+}
+
+// Move linear color into the space the overlay is mixed in, and back. The
+// perceptual space uses a 2.2 gamma, which carries on above 1 for HDR.
+fn to_overlay_space(color: vec3<f32>) -> vec3<f32> {
+#ifdef PERCEPTUAL
+    return pow(max(color, vec3<f32>(0.0)), vec3<f32>(1.0 / 2.2));
+#else
+    return color;
+#endif
+}
+
+fn from_overlay_space(color: vec3<f32>) -> vec3<f32> {
+#ifdef PERCEPTUAL
+    return pow(color, vec3<f32>(2.2));
+#else
+    return color;
+#endif
+}
+
+// The alpha of a torn pixel: the most opaque of the displaced taps its color
+// comes from, so torn rows and color fringes stay visible when they move over
+// a transparent background. Where the noise is 0 every tap is the source
+// pixel and its alpha is kept as is. Nothing else in the effect writes alpha.
+fn torn_alpha(color: vec4<f32>, shifted_a: vec4<f32>, shifted_b: vec4<f32>) -> f32 {
+    return max(color.a, max(shifted_a.a, shifted_b.a));
+}
 
+// The noise tearing the row of a viewport UV, limited by everything keeping
+// the effect away from that pixel. `texture_uv` is the fragment's UV in the
+// whole texture.
+fn tear(uv: vec2<f32>, texture_uv: vec2<f32>, time: f32) -> f32 {
+    return row_noise(full_uv(vec2<f32>(uv.x, snap_row(uv.y))), time) * intensity(uv) * exclusion(uv) * mask(uv)
+        * depth_fade(texture_uv) * viewport_mask(uv);
+}
+
+// The cheaper noise of the fast variant: only the large noise waves.
+fn tear_fast(uv: vec2<f32>, texture_uv: vec2<f32>, time: f32) -> f32 {
+    let row = full_uv(vec2<f32>(uv.x, snap_row(uv.y)));
+    return max(0.0, noise_field(vec2<f32>(time, row.y * 0.3), row.x) - 0.3) * (1.0 / 0.7)
+        * intensity(uv) * exclusion(uv) * mask(uv) * depth_fade(texture_uv) * viewport_mask(uv);
+}
+
+// How far colors separate for the noise, as a fraction of the aberration
+// offset: 1 everywhere for a scope of 0, and for a scope of 1 only on rows
+// the noise tears by a pixel or more, fading out below that.
+fn aberration_scope(noise: f32) -> f32 {
+    let torn = saturate(abs(noise * noise * settings.displacement) * viewport_pixels().x);
+    return mix(1.0, torn, settings.color_aberration[1].w);
+}
+
+// Apply the noise as x displacement for the row.
+fn displace(uv: vec2<f32>, noise: f32) -> vec2<f32> {
+    let direction = select(1.0, -1.0, (settings.flags & TEAR_LEFT_FLAG) != 0u);
+    return vec2<f32>(uv.x - snap_offset(noise * noise * settings.displacement * direction), uv.y);
+}
+
+#ifdef DEBUG_DISPLACEMENT
+// The shift of the fragment at the viewport UV `uv` as a color: red and green
+// the shift along x and y, from 0 at the full displacement against them
+// through 0.5 for none, and blue 1 where the fragment moves at all. Fragments
+// outside the viewport pass through.
+fn debug_displacement(uv: vec2<f32>, noise: f32) -> vec4<f32> {
+    if (viewport_mask(uv) == 0.0) {
+        return sample_screen(uv);
+    }
+    let offset = uv - displace(uv, noise);
+    let shift = saturate(0.5 + 0.5 * offset / max(settings.displacement, 1e-6));
+    return vec4<f32>(shift, select(0.0, 1.0, any(offset != vec2<f32>(0.0))), 1.0);
+}
+#endif
+
+// Sample the screen where the noise tears the fragment at the viewport UV
+// `uv` to. With antialiasing the tear is also evaluated at a few fixed
+// subpixel offsets and the samples averaged, which softens its edges without
+// blurring untorn pixels or adding noise over time.
+fn sample_torn(uv: vec2<f32>, texture_uv: vec2<f32>, time: f32, noise: f32) -> vec4<f32> {
+#ifdef DISPLACEMENT_TAPS
+    var jitter = array<vec2<f32>, 4>(
+        vec2<f32>(-0.25, -0.25),
+        vec2<f32>(0.25, 0.25),
+        vec2<f32>(0.25, -0.25),
+        vec2<f32>(-0.25, 0.25),
+    );
+    let pixel = 1.0 / viewport_pixels();
+    var sum = vec4<f32>(0.0);
+    for (var i = 0u; i < #{DISPLACEMENT_TAPS}u; i++) {
+        let offset = jitter[i] * pixel;
+        let jittered_noise = tear(uv + offset, texture_uv + offset * settings.viewport.zw, time);
+        // Only the tear is jittered, the sample stays on the fragment's row.
+        let torn = displace(uv + offset, jittered_noise).x - offset.x;
+        sum += sample_screen(vec2<f32>(torn, uv.y));
+    }
+    return sum / f32(#{DISPLACEMENT_TAPS}u);
+#else
+    return sample_screen(displace(uv, noise));
+#endif
+}
+
+// Fringe the colors of `texColor`, sampled at the viewport UV `pos`, and draw
+// the overlay over them. `uv` is the undisplaced position of the fragment.
+fn fringe(texColor: vec4<f32>, pos: vec2<f32>, uv: vec2<f32>, noise: f32, time: f32) -> vec4<f32> {
+
+    // Shift the secondary colors in opposite directions
+    let aberration_offset = noise * settings.aberration_offset * aberration_scope(noise);
+    let shifted_a = sample_shifted(pos, aberration_offset);
+#if GLITCH_TAPS == 1
+    let shifted_b = shifted_a;
+#else
+    let shifted_b = sample_shifted(pos, -aberration_offset);
+#endif
+    var fragColor = to_overlay_space(aberrate(texColor.rgb, shifted_a.rgb, shifted_b.rgb));
+
+#ifdef INTERFERENCE
     // Mix in some random interference for lines
-    var fragColor = mix(texColor.rgb, vec3<f32>(rand(vec2<f32>(uv.y * time))), noise * 0.3);
+    fragColor = mix(fragColor, vec3<f32>(rand(vec2<f32>(full_uv(uv).y * time))), noise * settings.interference);
+#endif
 
+#ifdef SCANLINES
     // Apply a line pattern every 4 pixels
-    if (floor(uv.y * 0.25 % 2.0) == 0.0) {
-        fragColor *= 1.0 - (0.15 * noise);
+    if (floor(full_uv(uv).y * 0.25 % 2.0) == 0.0) {
+        fragColor *= 1.0 - (settings.scanlines * noise);
     }
-    let C = settings.color_aberration;
-    // let primary = dot(C[0], fragColor);
-    // // let primary = settings.color_indices[0];
-    // // let secondary = settings.color_indices[1];
-    // // let tertiary = settings.color_indices[2];
-
-    // // Shift green/blue channels (using the red channel)
-    // fragColor =
-    //     C[0] * primary +
-    //     C[1] * mix(primary, dot(textureSample(screen_texture, texture_sampler, vec2<f32>(xpos + noise * 0.05, uv.y)).rgb, C[1]), 0.25) +
-    //     C[2] * mix(primary, dot(textureSample(screen_texture, texture_sampler, vec2<f32>(xpos - noise * 0.05, uv.y)).rgb, C[2]), 0.25);
-    return vec4<f32>(fragColor, texColor.a);
+#endif
+    fragColor = from_overlay_space(fragColor);
+    return vec4<f32>(fragColor, torn_alpha(texColor, shifted_a, shifted_b));
+}
+
+// The fast variant's fringe: a single shifted tap stands in for both
+// secondary colors.
+fn fringe_fast(pos: vec2<f32>, noise: f32) -> vec4<f32> {
+    let texColor: vec4<f32> = sample_screen(pos);
+    let shifted = sample_screen(pos + snap_shift(aberration_direction() * noise * settings.aberration_offset * aberration_scope(noise)));
+
+    return vec4<f32>(aberrate(texColor.rgb, shifted.rgb, shifted.rgb), torn_alpha(texColor, shifted, shifted));
+}
+
+// The width of the line between the two sides of a comparison, in pixels.
+const COMPARISON_LINE: f32 = 1.5;
+
+// Show the untouched source in place of `glitched` left of the comparison
+// split, with a line at the split in the last pass.
+fn compare(uv: vec2<f32>, glitched: vec4<f32>, line: bool) -> vec4<f32> {
+#ifdef COMPARISON
+    if (viewport_mask(uv) == 0.0) {
+        return glitched;
+    }
+    let distance = (full_uv(uv).x - settings.color_aberration[2].w) * full_pixels().x;
+    if (line && abs(distance) < COMPARISON_LINE) {
+        return vec4<f32>(1.0);
+    }
+    if (distance < 0.0) {
+        return sample_screen(uv);
+    }
+#endif
+    return glitched;
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32>
+{
+    let uv = enter_viewport(in.uv);
+    let time: f32 = effect_time();
+
+    // Without noise the source pixel is output unmodified.
+    let noise = tear(uv, in.uv, time);
+#ifdef DEBUG_DISPLACEMENT
+    return debug_displacement(uv, noise);
+#else
+    return compare(uv, fill_wipe(uv, fringe(sample_torn(uv, in.uv, time, noise), displace(uv, noise), uv, noise, time)), true);
+#endif
+}
+
+// A cheaper variant for mobile GPUs: only the large noise waves drive the row
+// displacement, and a single shifted tap stands in for both secondary colors.
+@fragment
+fn fragment_fast(in: FullscreenVertexOutput) -> @location(0) vec4<f32>
+{
+    let uv = enter_viewport(in.uv);
+    let noise = tear_fast(uv, in.uv, effect_time());
+#ifdef DEBUG_DISPLACEMENT
+    return debug_displacement(uv, noise);
+#else
+    return compare(uv, fill_wipe(uv, fringe_fast(displace(uv, noise), noise)), true);
+#endif
+}
+
+// The first of the split passes: only the rows are torn. Rows move as a whole,
+// so fringing the result in the second pass matches the fused effect.
+@fragment
+fn fragment_displace(in: FullscreenVertexOutput) -> @location(0) vec4<f32>
+{
+    let uv = enter_viewport(in.uv);
+    let time: f32 = effect_time();
+#ifdef DEBUG_DISPLACEMENT
+    return debug_displacement(uv, tear(uv, in.uv, time));
+#else
+    return compare(uv, sample_torn(uv, in.uv, time, tear(uv, in.uv, time)), false);
+#endif
+}
+
+@fragment
+fn fragment_displace_fast(in: FullscreenVertexOutput) -> @location(0) vec4<f32>
+{
+    let uv = enter_viewport(in.uv);
+#ifdef DEBUG_DISPLACEMENT
+    return debug_displacement(uv, tear_fast(uv, in.uv, effect_time()));
+#else
+    return compare(uv, sample_screen(displace(uv, tear_fast(uv, in.uv, effect_time()))), false);
+#endif
+}
+
+// The second of the split passes: the colors of the torn image are fringed in
+// place, from the same noise as the first pass.
+@fragment
+fn fragment_color(in: FullscreenVertexOutput) -> @location(0) vec4<f32>
+{
+    let uv = enter_viewport(in.uv);
+    let time: f32 = effect_time();
+#ifdef DEBUG_DISPLACEMENT
+    // The first pass already drew the displacement.
+    return sample_screen(uv);
+#else
+    return compare(uv, fill_wipe(uv, fringe(sample_screen(uv), uv, uv, tear(uv, in.uv, time), time)), true);
+#endif
+}
+
+@fragment
+fn fragment_color_fast(in: FullscreenVertexOutput) -> @location(0) vec4<f32>
+{
+    let uv = enter_viewport(in.uv);
+#ifdef DEBUG_DISPLACEMENT
+    return sample_screen(uv);
+#else
+    return compare(uv, fill_wipe(uv, fringe_fast(uv, tear_fast(uv, in.uv, effect_time()))), true);
+#endif
 }
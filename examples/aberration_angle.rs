@@ -0,0 +1,48 @@
+//! Sweep the direction the colors separate in around a full turn, from along
+//! the rows through vertical and back, for swirling fringes.
+use bevy::prelude::*;
+use bevy_video_glitch::*;
+
+// Turns of the fringes per second.
+const SWEEP_SPEED: f32 = 0.25;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, VideoGlitchPlugin::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, sweep)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 0.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+        VideoGlitchSettings {
+            intensity: 0.6,
+            color: ColorSettings {
+                aberration: ColorAberration::cvd_safe(ColorVisionDeficiency::Deuteranopia),
+                aberration_offset: 0.08,
+                ..default()
+            },
+            ..default()
+        },
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::from_length(1.5))),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
+    ));
+    commands.spawn((PointLight::default(), Transform::from_xyz(2.0, 3.0, 4.0)));
+}
+
+fn sweep(time: Res<Time>, mut settings: Query<&mut VideoGlitchSettings>) {
+    for mut settings in &mut settings {
+        settings.color.aberration_angle =
+            time.elapsed_secs_wrapped() * SWEEP_SPEED * std::f32::consts::TAU;
+    }
+}
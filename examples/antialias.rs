@@ -0,0 +1,58 @@
+//! Toggle antialiased tears with the space bar.
+//!
+//! Hard tears shimmer along their edges over thin, high contrast details,
+//! most of all once the output is scaled. The GPU time of the effect is
+//! logged to compare the cost of both settings.
+use bevy::{diagnostic::LogDiagnosticsPlugin, prelude::*};
+use bevy_video_glitch::*;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            LogDiagnosticsPlugin::default(),
+            VideoGlitchPlugin::default().with_diagnostics(),
+        ))
+        .add_systems(Startup, setup)
+        .add_systems(Update, toggle_antialias)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 0.0, 6.0).looking_at(Vec3::ZERO, Vec3::Y),
+        VideoGlitchSettings::default(),
+    ));
+
+    let bar = meshes.add(Cuboid::new(0.05, 4.0, 0.05));
+    let white = materials.add(Color::WHITE);
+    for i in -20..=20 {
+        commands.spawn((
+            Mesh3d(bar.clone()),
+            MeshMaterial3d(white.clone()),
+            Transform::from_xyz(i as f32 * 0.15, 0.0, 0.0),
+        ));
+    }
+    commands.spawn(DirectionalLight::default());
+}
+
+fn toggle_antialias(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut settings: Query<&mut VideoGlitchSettings>,
+) {
+    if keys.just_pressed(KeyCode::Space) {
+        for mut settings in &mut settings {
+            settings.displacement.antialias = if settings.displacement.antialias == 0 {
+                4
+            } else {
+                0
+            };
+            info!("Antialiasing taps: {}", settings.displacement.antialias);
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! The screen pulses with a stand-in soundtrack: bursts of a sine wave, two
+//! beats a second, whose level is fed to a `GlitchAudioDriver`.
+//!
+//! ```sh
+//! cargo run --example audio --features audio
+//! ```
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use bevy_video_glitch::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, VideoGlitchPlugin::default()))
+        .init_resource::<GlitchAudioLevel>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, feed_level)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(1.5, 1.5, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+        // The driver owns the intensity.
+        VideoGlitchSettings {
+            intensity: 0.0,
+            ..default()
+        },
+        GlitchAudioDriver::new(0.05, 0.9),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::from_length(1.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
+    ));
+    commands.spawn((PointLight::default(), Transform::from_xyz(2.0, 3.0, 4.0)));
+}
+
+// A game would measure the music it plays; this makes up a level instead: a
+// sine wave at 440 Hz in bursts of a tenth of a second on every beat, with
+// its peak level over the frame as the amplitude.
+fn feed_level(time: Res<Time>, mut level: ResMut<GlitchAudioLevel>) {
+    const BEATS_PER_SECOND: f32 = 2.0;
+    const BURST: f32 = 0.1;
+    let start = time.elapsed_secs() - time.delta_secs();
+    let samples = 64;
+    level.0 = (0..samples)
+        .map(|i| {
+            let t = start + time.delta_secs() * i as f32 / samples as f32;
+            let in_burst = (t * BEATS_PER_SECOND).fract() / BEATS_PER_SECOND < BURST;
+            if in_burst {
+                (t * 440.0 * TAU).sin().abs()
+            } else {
+                0.0
+            }
+        })
+        .fold(0.0, f32::max);
+}
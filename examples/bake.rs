@@ -0,0 +1,48 @@
+//! Bake three glitched variants of a photo, e.g. for corrupted collectibles,
+//! and show them next to the original.
+//!
+//! No camera has the effect: each variant is baked once into an image that
+//! is kept from then on.
+use bevy::prelude::*;
+use bevy_video_glitch::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, VideoGlitchPlugin::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, log_baked)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    asset_server: Res<AssetServer>,
+) {
+    commands.spawn(Camera2d);
+
+    let photo: Handle<Image> = asset_server.load("images/photo.png");
+    commands.spawn((
+        Sprite::from_image(photo.clone()),
+        Transform::from_xyz(-170.0, 130.0, 0.0),
+    ));
+
+    // Different times make different variants.
+    let positions = [(170.0, 130.0), (-170.0, -130.0), (170.0, -130.0)];
+    for (time, (x, y)) in [1.0, 7.3, 42.0].into_iter().zip(positions) {
+        let variant = bake_video_glitch(
+            &mut commands,
+            &mut images,
+            photo.clone(),
+            VideoGlitchSettings::default(),
+            time,
+        );
+        commands.spawn((Sprite::from_image(variant), Transform::from_xyz(x, y, 0.0)));
+    }
+}
+
+fn log_baked(mut baked: EventReader<VideoGlitchBaked>) {
+    for baked in baked.read() {
+        info!("Baked {:?}", baked.image.id());
+    }
+}
@@ -0,0 +1,74 @@
+//! Press C to capture three seconds of the glitched cube at 30 frames per
+//! second to `captures/`, the same frames on every run.
+//!
+//! ```sh
+//! cargo run --example capture --features capture
+//! ```
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_video_glitch::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, VideoGlitchPlugin::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (rotate, start_capture, report))
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 0.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+        VideoGlitchSettings {
+            intensity: 0.5,
+            ..default()
+        },
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::from_length(1.5))),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
+        Rotates,
+    ));
+    commands.spawn((PointLight::default(), Transform::from_xyz(2.0, 3.0, 4.0)));
+}
+
+#[derive(Component)]
+struct Rotates;
+
+fn rotate(time: Res<Time>, mut query: Query<&mut Transform, With<Rotates>>) {
+    for mut transform in &mut query {
+        transform.rotate_y(0.8 * time.delta_secs());
+    }
+}
+
+fn start_capture(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    cameras: Query<Entity, (With<Camera>, Without<GlitchCapture>)>,
+) {
+    if !keys.just_pressed(KeyCode::KeyC) {
+        return;
+    }
+    for camera in &cameras {
+        info!("Capturing...");
+        commands.entity(camera).insert(
+            GlitchCapture::seconds(Duration::from_secs(3), "captures").with_fixed_fps(30.0),
+        );
+    }
+}
+
+fn report(mut captured: EventReader<GlitchCaptured>) {
+    for captured in captured.read() {
+        info!(
+            "Wrote {} frames to {}",
+            captured.frames,
+            captured.directory.display()
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! A glitched security camera feed.
+//!
+//! An offscreen camera renders a rotating cube into an image with the effect,
+//! and that image is shown on a monitor mesh seen by the main camera, which
+//! has no effect of its own. The feed's glitch updates at 15 frames a second,
+//! like a cheap camera's, whatever the display's refresh rate.
+use bevy::{
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_resource::{
+            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+        },
+        view::RenderLayers,
+    },
+};
+use bevy_video_glitch::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, VideoGlitchPlugin::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, rotate)
+        .run();
+}
+
+#[derive(Component)]
+struct Rotates;
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let size = Extent3d {
+        width: 512,
+        height: 512,
+        ..default()
+    };
+    // The feed deliberately uses a different format than the window.
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: None,
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    image.resize(size);
+    let feed = images.add(image);
+
+    // The security camera only sees the first render layer.
+    let cctv_layer = RenderLayers::layer(1);
+    commands.spawn((
+        Camera3d::default(),
+        Camera {
+            order: -1,
+            target: RenderTarget::Image(feed.clone()),
+            clear_color: Color::srgb(0.1, 0.1, 0.1).into(),
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+        cctv_layer.clone(),
+        VideoGlitchSettings {
+            intensity: 0.7,
+            effect_fps: 15.0,
+            ..default()
+        },
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::from_length(1.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
+        Rotates,
+        cctv_layer.clone(),
+    ));
+    commands.spawn((
+        PointLight::default(),
+        Transform::from_xyz(2.0, 3.0, 4.0),
+        cctv_layer,
+    ));
+
+    // The monitor showing the feed.
+    commands.spawn((
+        Mesh3d(meshes.add(Rectangle::new(1.6, 1.6))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color_texture: Some(feed),
+            unlit: true,
+            ..default()
+        })),
+    ));
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(1.0, 0.5, 2.5).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+}
+
+fn rotate(time: Res<Time>, mut query: Query<&mut Transform, With<Rotates>>) {
+    for mut transform in &mut query {
+        transform.rotate_y(0.5 * time.delta_secs());
+    }
+}
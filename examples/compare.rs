@@ -0,0 +1,59 @@
+//! Compare the untouched scene, left, with the glitched one, right. Drag with
+//! the left mouse button to move the split.
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_video_glitch::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, VideoGlitchPlugin::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, drag_split)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 1.5, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+        VideoGlitchSettings {
+            intensity: 0.6,
+            ..default()
+        },
+        GlitchComparisonSplit(0.5),
+    ));
+
+    let cube = meshes.add(Cuboid::from_length(0.8));
+    for i in -3..=3 {
+        commands.spawn((
+            Mesh3d(cube.clone()),
+            MeshMaterial3d(materials.add(Color::hsl(i as f32 * 40.0 + 120.0, 0.6, 0.5))),
+            Transform::from_xyz(i as f32 * 1.1, 0.0, 0.0),
+        ));
+    }
+    commands.spawn((PointLight::default(), Transform::from_xyz(0.0, 4.0, 4.0)));
+}
+
+// Put the split under the cursor while the left mouse button is held.
+fn drag_split(
+    buttons: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut splits: Query<&mut GlitchComparisonSplit>,
+) {
+    if !buttons.pressed(MouseButton::Left) {
+        return;
+    }
+    let Some(cursor) = windows.get_single().ok().and_then(|window| {
+        window
+            .cursor_position()
+            .map(|cursor| cursor.x / window.width())
+    }) else {
+        return;
+    };
+    for mut split in &mut splits {
+        split.0 = cursor;
+    }
+}
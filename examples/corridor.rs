@@ -0,0 +1,104 @@
+//! A long corridor whose far end shreds into glitch while the hands in front
+//! of the camera stay clean. Press I to invert the fade.
+//!
+//! Fading the effect with distance reads the depth prepass, which needs MSAA
+//! to be off.
+use bevy::{core_pipeline::prepass::DepthPrepass, prelude::*};
+use bevy_video_glitch::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, VideoGlitchPlugin::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (walk, invert_fade))
+        .run();
+}
+
+const CORRIDOR_LENGTH: f32 = 60.0;
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let wall = materials.add(Color::srgb(0.6, 0.6, 0.65));
+    let pillar = materials.add(Color::srgb(0.8, 0.4, 0.2));
+    let hands = materials.add(Color::srgb(0.9, 0.75, 0.6));
+
+    commands
+        .spawn((
+            Camera3d::default(),
+            Transform::from_xyz(0.0, 1.6, 0.0),
+            Msaa::Off,
+            DepthPrepass,
+            VideoGlitchSettings {
+                depth: DepthSettings {
+                    near: 4.0,
+                    far: 30.0,
+                    invert: false,
+                },
+                ..default()
+            },
+        ))
+        .with_children(|camera| {
+            for x in [-0.25, 0.25] {
+                camera.spawn((
+                    Mesh3d(meshes.add(Cuboid::new(0.12, 0.12, 0.4))),
+                    MeshMaterial3d(hands.clone()),
+                    Transform::from_xyz(x, -0.3, -0.5),
+                ));
+            }
+        });
+
+    // The floor, ceiling and walls, running away from the camera.
+    let center = Vec3::new(0.0, 0.0, -CORRIDOR_LENGTH / 2.0);
+    for (size, offset) in [
+        (Vec3::new(4.0, 0.1, CORRIDOR_LENGTH), Vec3::ZERO),
+        (Vec3::new(4.0, 0.1, CORRIDOR_LENGTH), Vec3::Y * 3.0),
+        (
+            Vec3::new(0.1, 3.0, CORRIDOR_LENGTH),
+            Vec3::new(-2.0, 1.5, 0.0),
+        ),
+        (
+            Vec3::new(0.1, 3.0, CORRIDOR_LENGTH),
+            Vec3::new(2.0, 1.5, 0.0),
+        ),
+    ] {
+        commands.spawn((
+            Mesh3d(meshes.add(Cuboid::from_size(size))),
+            MeshMaterial3d(wall.clone()),
+            Transform::from_translation(center + offset),
+        ));
+    }
+
+    // Pillars along the walls make the distance easy to read.
+    for i in 0..12 {
+        for x in [-1.8, 1.8] {
+            commands.spawn((
+                Mesh3d(meshes.add(Cuboid::new(0.3, 3.0, 0.3))),
+                MeshMaterial3d(pillar.clone()),
+                Transform::from_xyz(x, 1.5, -5.0 * i as f32 - 3.0),
+            ));
+        }
+    }
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(1.0, 3.0, 2.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+}
+
+// Walk up and down the corridor.
+fn walk(time: Res<Time>, mut camera: Query<&mut Transform, With<Camera>>) {
+    for mut transform in &mut camera {
+        transform.translation.z = -10.0 * (1.0 - (time.elapsed_secs() * 0.3).cos());
+    }
+}
+
+fn invert_fade(keys: Res<ButtonInput<KeyCode>>, mut settings: Query<&mut VideoGlitchSettings>) {
+    if keys.just_pressed(KeyCode::KeyI) {
+        for mut settings in &mut settings {
+            settings.depth.invert = !settings.depth.invert;
+        }
+    }
+}
@@ -1,11 +1,13 @@
+//! A rotating cube with a pulsing glitch. Press D to switch between the
+//! effect and its displacement.
 use bevy::prelude::*;
 use bevy_video_glitch::*;
 
 fn main() {
     App::new()
-        .add_plugins((DefaultPlugins, VideoGlitchPlugin))
+        .add_plugins((DefaultPlugins, VideoGlitchPlugin::default()))
         .add_systems(Startup, setup)
-        .add_systems(Update, (rotate, update_settings))
+        .add_systems(Update, (rotate, update_settings, toggle_debug))
         .run();
 }
 
@@ -17,13 +19,10 @@ fn setup(
 ) {
     // camera
     commands.spawn((
-        Camera3dBundle {
-            transform: Transform::from_translation(Vec3::new(0.0, 0.0, 5.0))
-                .looking_at(Vec3::default(), Vec3::Y),
-            camera: Camera {
-                clear_color: Color::WHITE.into(),
-                ..default()
-            },
+        Camera3d::default(),
+        Transform::from_translation(Vec3::new(0.0, 0.0, 5.0)).looking_at(Vec3::default(), Vec3::Y),
+        Camera {
+            clear_color: Color::WHITE.into(),
             ..default()
         },
         // Add the setting to the camera.
@@ -32,26 +31,27 @@ fn setup(
         // post processing effect.
         VideoGlitchSettings {
             intensity: 0.02,
-            color_aberration: Mat3::from_cols_array(&[0.5, 0.5, 0.0, 0.0, 0.5, 0.5, 0.5, 0.0, 0.5])
-                .transpose(),
+            color: ColorSettings {
+                aberration: Mat3::from_cols_array(&[0.5, 0.5, 0.0, 0.0, 0.5, 0.5, 0.5, 0.0, 0.5])
+                    .transpose(),
+                ..default()
+            },
+            ..default()
         },
     ));
 
     // cube
     commands.spawn((
-        PbrBundle {
-            mesh: meshes.add(Mesh::from(Cuboid::from_length(1.0))),
-            material: materials.add(Color::srgb(0.8, 0.7, 0.6)),
-            transform: Transform::from_xyz(0.0, 0.5, 0.0),
-            ..default()
-        },
+        Mesh3d(meshes.add(Mesh::from(Cuboid::from_length(1.0)))),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
+        Transform::from_xyz(0.0, 0.5, 0.0),
         Rotates,
     ));
     // light
-    commands.spawn(PointLightBundle {
-        transform: Transform::from_translation(Vec3::new(0.0, 0.0, 10.0)),
-        ..default()
-    });
+    commands.spawn((
+        PointLight::default(),
+        Transform::from_translation(Vec3::new(0.0, 0.0, 10.0)),
+    ));
 }
 
 #[derive(Component)]
@@ -60,15 +60,15 @@ struct Rotates;
 /// Rotates any entity around the x and y axis
 fn rotate(time: Res<Time>, mut query: Query<&mut Transform, With<Rotates>>) {
     for mut transform in &mut query {
-        transform.rotate_x(0.55 * time.delta_seconds());
-        transform.rotate_z(0.15 * time.delta_seconds());
+        transform.rotate_x(0.55 * time.delta_secs());
+        transform.rotate_z(0.15 * time.delta_secs());
     }
 }
 
 // Change the intensity over time to show that the effect is controlled from the main world
 fn update_settings(mut settings: Query<&mut VideoGlitchSettings>, time: Res<Time>) {
     for mut setting in &mut settings {
-        let mut intensity = time.elapsed_seconds();
+        let mut intensity = time.elapsed_secs();
         // Make it loop periodically.
         intensity = intensity.sin();
         // Remap it to 0..1 because the intensity can't be negative.
@@ -80,3 +80,21 @@ fn update_settings(mut settings: Query<&mut VideoGlitchSettings>, time: Res<Time
         setting.intensity = intensity;
     }
 }
+
+// Switch between the effect and the debug view of its displacement.
+fn toggle_debug(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    cameras: Query<(Entity, Has<GlitchDebugDisplacement>), With<VideoGlitchSettings>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyD) {
+        return;
+    }
+    for (camera, debug) in &cameras {
+        if debug {
+            commands.entity(camera).remove::<GlitchDebugDisplacement>();
+        } else {
+            commands.entity(camera).insert(GlitchDebugDisplacement);
+        }
+    }
+}
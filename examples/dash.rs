@@ -0,0 +1,81 @@
+//! Dash the camera sideways with the space bar and snap it back to the start
+//! with R. The dash glitches the screen, the snap is taken as a cut and
+//! doesn't.
+use bevy::prelude::*;
+use bevy_video_glitch::*;
+
+const DASH_SPEED: f32 = 30.0;
+const DASH_TIME: f32 = 0.2;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, VideoGlitchPlugin::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, dash)
+        .run();
+}
+
+#[derive(Component, Default)]
+struct Dash {
+    remaining: f32,
+    direction: f32,
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 1.5, 8.0),
+        // The driver owns the intensity.
+        VideoGlitchSettings {
+            intensity: 0.0,
+            ..default()
+        },
+        GlitchFromMotion::default()
+            .with_baseline(0.02)
+            // A dash covers 6 units, a snap back from the end of a few of
+            // them covers more in a single frame.
+            .with_max_distance(5.0),
+        Dash::default(),
+    ));
+
+    let cube = meshes.add(Cuboid::from_length(1.0));
+    let material = materials.add(Color::srgb(0.8, 0.7, 0.6));
+    for i in -10..=10 {
+        commands.spawn((
+            Mesh3d(cube.clone()),
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(i as f32 * 3.0, 0.0, 0.0),
+        ));
+    }
+    commands.spawn((PointLight::default(), Transform::from_xyz(0.0, 6.0, 4.0)));
+}
+
+fn dash(
+    time: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut cameras: Query<(&mut Transform, &mut Dash)>,
+) {
+    for (mut transform, mut dash) in &mut cameras {
+        if keys.just_pressed(KeyCode::Space) && dash.remaining <= 0.0 {
+            dash.remaining = DASH_TIME;
+            dash.direction = if transform.translation.x > 0.0 {
+                -1.0
+            } else {
+                1.0
+            };
+        }
+        if keys.just_pressed(KeyCode::KeyR) {
+            dash.remaining = 0.0;
+            transform.translation.x = 0.0;
+        }
+        if dash.remaining > 0.0 {
+            let step = time.delta_secs().min(dash.remaining);
+            dash.remaining -= step;
+            transform.translation.x += dash.direction * DASH_SPEED * step;
+        }
+    }
+}
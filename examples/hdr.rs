@@ -0,0 +1,41 @@
+//! The effect on an HDR camera with bloom.
+//!
+//! The glitch runs after tonemapping by default, on the camera's `Rgba16Float`
+//! main texture.
+use bevy::{core_pipeline::bloom::Bloom, prelude::*};
+use bevy_video_glitch::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, VideoGlitchPlugin::default()))
+        .add_systems(Startup, setup)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Camera {
+            hdr: true,
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+        Bloom::default(),
+        VideoGlitchSettings {
+            intensity: 0.6,
+            ..default()
+        },
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Sphere::new(0.5))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            emissive: LinearRgba::rgb(4.0, 1.0, 0.5),
+            ..default()
+        })),
+    ));
+}
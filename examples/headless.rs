@@ -0,0 +1,124 @@
+//! Render glitched 2D and 3D cameras into an image without a window for a few
+//! frames, then exit. Useful as a smoke test in CI.
+//!
+//! Exits with an error if the effect's pipelines don't become ready.
+use bevy::{
+    app::PluginsState,
+    prelude::*,
+    render::{
+        camera::{RenderTarget, Viewport},
+        render_resource::{
+            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+        },
+    },
+    window::ExitCondition,
+    winit::WinitPlugin,
+};
+use bevy_video_glitch::*;
+
+const FRAMES: usize = 60;
+
+fn main() -> AppExit {
+    let mut app = App::new();
+    app.add_plugins((
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                ..default()
+            })
+            .disable::<WinitPlugin>(),
+        VideoGlitchPlugin::default(),
+    ))
+    .add_systems(Startup, setup);
+
+    // Without winit nothing drives the app, so do what its runner would.
+    while app.plugins_state() == PluginsState::Adding {
+        bevy::tasks::tick_global_task_pools_on_main_thread();
+    }
+    app.finish();
+    app.cleanup();
+    for _ in 0..FRAMES {
+        app.update();
+    }
+
+    let status = app.world().resource::<VideoGlitchPipelineStatus>();
+    info!("pipeline status after {FRAMES} frames: {status:?}");
+    if status.is_ready() {
+        AppExit::Success
+    } else {
+        AppExit::error()
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let size = Extent3d {
+        width: 320,
+        height: 180,
+        ..default()
+    };
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: None,
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    image.resize(size);
+    let target = images.add(image);
+
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::from_length(1.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
+    ));
+    commands.spawn((PointLight::default(), Transform::from_xyz(2.0, 3.0, 4.0)));
+
+    // A 3D camera on the left half of the image and a 2D camera on the right,
+    // with different settings and pipelines.
+    commands.spawn((
+        Camera3d::default(),
+        Camera {
+            target: RenderTarget::Image(target.clone()),
+            viewport: Some(Viewport {
+                physical_position: UVec2::ZERO,
+                physical_size: UVec2::new(size.width / 2, size.height),
+                ..default()
+            }),
+            ..default()
+        },
+        Transform::from_xyz(1.5, 1.5, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+        VideoGlitchSettings::default(),
+    ));
+    commands.spawn((
+        Camera2d,
+        Camera {
+            order: 1,
+            target: RenderTarget::Image(target),
+            viewport: Some(Viewport {
+                physical_position: UVec2::new(size.width / 2, 0),
+                physical_size: UVec2::new(size.width / 2, size.height),
+                ..default()
+            }),
+            clear_color: ClearColorConfig::None,
+            ..default()
+        },
+        VideoGlitchSettings {
+            intensity: 0.5,
+            ..default()
+        },
+        VideoGlitchMode::Fast,
+    ));
+}
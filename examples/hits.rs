@@ -0,0 +1,83 @@
+//! Four turrets take turns shooting at the camera, each glitching the screen
+//! from its own side. Fire one yourself with the arrow keys.
+//!
+//! The camera's own intensity is low, so the hits stand out.
+use bevy::prelude::*;
+use bevy_video_glitch::*;
+
+const TURRETS: [(Vec3, KeyCode); 4] = [
+    (Vec3::new(-4.0, 0.0, -5.0), KeyCode::ArrowLeft),
+    (Vec3::new(4.0, 0.0, -5.0), KeyCode::ArrowRight),
+    (Vec3::new(0.0, 3.0, -5.0), KeyCode::ArrowUp),
+    (Vec3::new(0.0, -3.0, -5.0), KeyCode::ArrowDown),
+];
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, VideoGlitchPlugin::default()))
+        .insert_resource(Volley(Timer::from_seconds(0.8, TimerMode::Repeating)))
+        .add_systems(Startup, setup)
+        .add_systems(Update, fire)
+        .run();
+}
+
+#[derive(Resource)]
+struct Volley(Timer);
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        VideoGlitchSettings {
+            intensity: 0.05,
+            ..default()
+        },
+    ));
+
+    let turret = meshes.add(Cuboid::from_length(0.8));
+    let material = materials.add(Color::srgb(0.8, 0.3, 0.2));
+    for (position, _) in TURRETS {
+        commands.spawn((
+            Mesh3d(turret.clone()),
+            MeshMaterial3d(material.clone()),
+            Transform::from_translation(position),
+        ));
+    }
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(12.0, 8.0, 0.2))),
+        MeshMaterial3d(materials.add(Color::srgb(0.6, 0.6, 0.6))),
+        Transform::from_xyz(0.0, 0.0, -7.0),
+    ));
+    commands.spawn((PointLight::default(), Transform::from_xyz(0.0, 0.0, -2.0)));
+}
+
+// Shoot from the next turret in turn, or the one picked with the keys.
+fn fire(
+    mut commands: Commands,
+    time: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut volley: ResMut<Volley>,
+    mut next: Local<usize>,
+    cameras: Query<(Entity, &GlobalTransform), With<VideoGlitchSettings>>,
+) {
+    let mut turret = TURRETS
+        .iter()
+        .find(|(_, key)| keys.just_pressed(*key))
+        .map(|(position, _)| *position);
+    if volley.0.tick(time.delta()).just_finished() && turret.is_none() {
+        turret = Some(TURRETS[*next].0);
+        *next = (*next + 1) % TURRETS.len();
+    }
+    let Some(turret) = turret else {
+        return;
+    };
+    for (camera, transform) in &cameras {
+        let direction = turret - transform.translation();
+        commands
+            .entity(camera)
+            .insert(GlitchHit::from_world_direction(transform, direction, 1.5));
+    }
+}
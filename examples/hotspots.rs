@@ -0,0 +1,93 @@
+//! Two corrupted spheres wander around a clean scene, each carrying a bubble
+//! of glitch with it on screen.
+//!
+//! The camera's own intensity is 0, so only the hotspots glitch.
+use bevy::prelude::*;
+use bevy_video_glitch::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, VideoGlitchPlugin::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, wander)
+        .run();
+}
+
+#[derive(Component)]
+struct Wander {
+    speed: f32,
+    phase: f32,
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 6.0, 9.0).looking_at(Vec3::ZERO, Vec3::Y),
+        VideoGlitchSettings {
+            intensity: 0.0,
+            ..default()
+        },
+    ));
+
+    let sphere = meshes.add(Sphere::new(0.4));
+    for (speed, phase, radius, color) in [
+        (
+            0.7,
+            0.0,
+            GlitchHotspotRadius::World(1.2),
+            Color::srgb(0.8, 0.2, 0.3),
+        ),
+        (
+            0.4,
+            2.0,
+            GlitchHotspotRadius::Screen(0.15),
+            Color::srgb(0.3, 0.5, 0.8),
+        ),
+    ] {
+        commands.spawn((
+            Mesh3d(sphere.clone()),
+            MeshMaterial3d(materials.add(color)),
+            Transform::from_xyz(0.0, 0.4, 0.0),
+            GlitchHotspot {
+                radius,
+                strength: 0.8,
+                falloff: 0.6,
+            },
+            Wander { speed, phase },
+        ));
+    }
+
+    // A grid of cubes for the hotspots to glitch.
+    let cube = meshes.add(Cuboid::from_length(0.5));
+    let grey = materials.add(Color::srgb(0.7, 0.7, 0.7));
+    for x in -4..=4 {
+        for z in -4..=4 {
+            commands.spawn((
+                Mesh3d(cube.clone()),
+                MeshMaterial3d(grey.clone()),
+                Transform::from_xyz(x as f32 * 1.2, 0.25, z as f32 * 1.2),
+            ));
+        }
+    }
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(12.0, 12.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.3, 0.3, 0.3))),
+    ));
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(1.0, 3.0, 2.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+}
+
+// Move along a looping path, each at its own pace.
+fn wander(time: Res<Time>, mut query: Query<(&mut Transform, &Wander)>) {
+    for (mut transform, wander) in &mut query {
+        let t = time.elapsed_secs() * wander.speed + wander.phase;
+        transform.translation.x = 4.0 * t.sin();
+        transform.translation.z = 3.0 * (2.0 * t).sin();
+    }
+}
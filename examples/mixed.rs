@@ -0,0 +1,67 @@
+//! Shows a 3D camera and a 2D camera with matching settings side by side.
+//!
+//! Both graphs run the effect after tonemapping by default, so the two windows
+//! should glitch the same way.
+use bevy::{prelude::*, render::camera::RenderTarget, window::WindowRef};
+use bevy_video_glitch::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, VideoGlitchPlugin::default()))
+        .add_systems(Startup, setup)
+        .run();
+}
+
+fn settings() -> VideoGlitchSettings {
+    VideoGlitchSettings {
+        intensity: 0.5,
+        color: ColorSettings {
+            aberration: Mat3::from_cols_array(&[0.5, 0.5, 0.0, 0.0, 0.5, 0.5, 0.5, 0.0, 0.5])
+                .transpose(),
+            ..default()
+        },
+        ..default()
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // 3D camera in the primary window
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 0.0, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+        settings(),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::from_length(1.0))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgb(0.8, 0.7, 0.6),
+            unlit: true,
+            ..default()
+        })),
+    ));
+
+    // 2D camera in a second window
+    let window = commands
+        .spawn(Window {
+            title: "2D".into(),
+            ..default()
+        })
+        .id();
+    commands.spawn((
+        Camera2d,
+        Camera {
+            target: RenderTarget::Window(WindowRef::Entity(window)),
+            ..default()
+        },
+        settings(),
+    ));
+    commands.spawn(Sprite {
+        color: Color::srgb(0.8, 0.7, 0.6),
+        custom_size: Some(Vec2::splat(200.0)),
+        ..default()
+    });
+}
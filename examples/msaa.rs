@@ -0,0 +1,47 @@
+//! Toggle MSAA at runtime with the space bar while the effect is running.
+//!
+//! The effect always works on the resolved image, so it looks the same with
+//! and without MSAA.
+use bevy::prelude::*;
+use bevy_video_glitch::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, VideoGlitchPlugin::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, toggle_msaa)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(1.5, 1.5, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+        Msaa::Sample4,
+        VideoGlitchSettings {
+            intensity: 0.5,
+            ..default()
+        },
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::from_length(1.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
+    ));
+    commands.spawn((PointLight::default(), Transform::from_xyz(2.0, 3.0, 4.0)));
+}
+
+fn toggle_msaa(keys: Res<ButtonInput<KeyCode>>, mut cameras: Query<&mut Msaa>) {
+    if keys.just_pressed(KeyCode::Space) {
+        for mut msaa in &mut cameras {
+            *msaa = match *msaa {
+                Msaa::Off => Msaa::Sample4,
+                _ => Msaa::Off,
+            };
+            info!("MSAA: {:?}", *msaa);
+        }
+    }
+}
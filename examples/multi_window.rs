@@ -0,0 +1,116 @@
+//! Two windows: one split into four viewports and a second one, every camera
+//! with its own settings.
+use bevy::{
+    prelude::*,
+    render::camera::{RenderTarget, Viewport},
+    window::{WindowRef, WindowResized},
+};
+use bevy_video_glitch::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, VideoGlitchPlugin::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, set_viewports)
+        .run();
+}
+
+/// The quadrant of the primary window a camera renders to.
+#[derive(Component)]
+struct Quadrant(UVec2);
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::from_length(1.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
+    ));
+    commands.spawn((PointLight::default(), Transform::from_xyz(2.0, 3.0, 4.0)));
+
+    let quadrants = [
+        (UVec2::new(0, 0), 0.1, Mat3::IDENTITY),
+        (
+            UVec2::new(1, 0),
+            0.4,
+            Mat3::from_cols(Vec3::Y, Vec3::Z, Vec3::X),
+        ),
+        (
+            UVec2::new(0, 1),
+            0.7,
+            Mat3::from_cols(Vec3::Z, Vec3::X, Vec3::Y),
+        ),
+        (UVec2::new(1, 1), 1.0, Mat3::IDENTITY),
+    ];
+    for (order, (quadrant, intensity, aberration)) in quadrants.into_iter().enumerate() {
+        commands.spawn((
+            Camera3d::default(),
+            Camera {
+                order: order as isize,
+                // Only the first camera clears the window.
+                clear_color: if order == 0 {
+                    ClearColorConfig::Default
+                } else {
+                    ClearColorConfig::None
+                },
+                ..default()
+            },
+            Transform::from_xyz(order as f32 - 1.5, 1.0, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+            VideoGlitchSettings {
+                intensity,
+                color: ColorSettings {
+                    aberration,
+                    ..default()
+                },
+                ..default()
+            },
+            Quadrant(quadrant),
+        ));
+    }
+
+    let second_window = commands
+        .spawn(Window {
+            title: "Second window".into(),
+            ..default()
+        })
+        .id();
+    commands.spawn((
+        Camera3d::default(),
+        Camera {
+            target: RenderTarget::Window(WindowRef::Entity(second_window)),
+            hdr: true,
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+        VideoGlitchSettings {
+            intensity: 0.5,
+            ..default()
+        },
+        VideoGlitchMode::Fast,
+    ));
+}
+
+fn set_viewports(
+    windows: Query<&Window>,
+    mut resized: EventReader<WindowResized>,
+    mut cameras: Query<(&mut Camera, &Quadrant)>,
+) {
+    for event in resized.read() {
+        let Ok(window) = windows.get(event.window) else {
+            continue;
+        };
+        let half = window.physical_size() / 2;
+        for (mut camera, quadrant) in &mut cameras {
+            if !matches!(camera.target, RenderTarget::Window(WindowRef::Primary)) {
+                continue;
+            }
+            camera.viewport = Some(Viewport {
+                physical_position: quadrant.0 * half,
+                physical_size: half,
+                ..default()
+            });
+        }
+    }
+}
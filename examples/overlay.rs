@@ -0,0 +1,61 @@
+//! Glitch a camera's output through an overlay camera, without the effect in
+//! the camera's own render graph. Press space to tear the overlay down, which
+//! gives the camera its target and settings back, and to set it up again.
+use bevy::prelude::*;
+use bevy_video_glitch::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, VideoGlitchPlugin::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, toggle)
+        .run();
+}
+
+#[derive(Resource)]
+struct Overlay {
+    source: Entity,
+    overlay: Option<Entity>,
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let source = commands
+        .spawn((
+            Camera3d::default(),
+            Transform::from_xyz(0.0, 0.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+            // Moved to the overlay camera while it exists.
+            VideoGlitchSettings {
+                intensity: 0.6,
+                ..default()
+            },
+        ))
+        .id();
+    let overlay = spawn_glitch_overlay(&mut commands, source);
+    commands.insert_resource(Overlay {
+        source,
+        overlay: Some(overlay),
+    });
+
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::from_length(1.5))),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
+    ));
+    commands.spawn((PointLight::default(), Transform::from_xyz(2.0, 3.0, 4.0)));
+}
+
+fn toggle(mut commands: Commands, keys: Res<ButtonInput<KeyCode>>, mut overlay: ResMut<Overlay>) {
+    if !keys.just_pressed(KeyCode::Space) {
+        return;
+    }
+    overlay.overlay = match overlay.overlay {
+        Some(entity) => {
+            despawn_glitch_overlay(&mut commands, entity);
+            None
+        }
+        None => Some(spawn_glitch_overlay(&mut commands, overlay.source)),
+    };
+}
@@ -0,0 +1,81 @@
+//! Rumble every connected gamepad and shake the camera whenever the glitch
+//! bursts, in sync with the tears on screen.
+use std::time::Duration;
+
+use bevy::{
+    input::gamepad::{GamepadRumbleIntensity, GamepadRumbleRequest},
+    prelude::*,
+};
+use bevy_video_glitch::*;
+
+// The activity below which nothing rumbles.
+const RUMBLE_THRESHOLD: f32 = 0.05;
+// How far the camera shakes at full activity.
+const SHAKE: f32 = 0.15;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, VideoGlitchPlugin::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (rumble, shake))
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 0.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+        VideoGlitchSettings {
+            intensity: 0.8,
+            ..default()
+        },
+        // Filled in every frame with how much the bursts tear the screen.
+        GlitchSignal::default(),
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::from_length(1.5))),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
+    ));
+    commands.spawn((PointLight::default(), Transform::from_xyz(2.0, 3.0, 4.0)));
+}
+
+// Rumble for a frame at a time while the bursts last, harder the more they
+// tear.
+fn rumble(
+    signals: Query<&GlitchSignal>,
+    gamepads: Query<Entity, With<Gamepad>>,
+    mut requests: EventWriter<GamepadRumbleRequest>,
+) {
+    let Some(activity) = signals
+        .iter()
+        .map(|signal| signal.activity)
+        .reduce(f32::max)
+    else {
+        return;
+    };
+    if activity < RUMBLE_THRESHOLD {
+        return;
+    }
+    for gamepad in &gamepads {
+        requests.send(GamepadRumbleRequest::Add {
+            gamepad,
+            duration: Duration::from_millis(50),
+            intensity: GamepadRumbleIntensity {
+                strong_motor: activity,
+                weak_motor: activity.sqrt(),
+            },
+        });
+    }
+}
+
+// Nudge the camera sideways by the activity.
+fn shake(mut cameras: Query<(&mut Transform, &GlitchSignal)>) {
+    for (mut transform, signal) in &mut cameras {
+        transform.translation.x = signal.activity * SHAKE;
+    }
+}
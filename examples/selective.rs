@@ -0,0 +1,69 @@
+//! Glitch only one of three cubes, the "corrupted" one in the middle.
+//!
+//! A second camera renders the tagged cube into the main camera's mask. The
+//! cube in front partly hides it and keeps the hidden part clean.
+use bevy::prelude::*;
+use bevy_video_glitch::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, VideoGlitchPlugin::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, sway)
+        .run();
+}
+
+#[derive(Component)]
+struct Sway;
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let camera = commands
+        .spawn((
+            Camera3d::default(),
+            Transform::from_xyz(0.0, 1.5, 6.0).looking_at(Vec3::ZERO, Vec3::Y),
+            VideoGlitchSettings::default(),
+        ))
+        .id();
+    commands.spawn((Camera3d::default(), GlitchMaskSource { camera }));
+
+    let cube = meshes.add(Cuboid::from_length(1.0));
+    commands.spawn((
+        Mesh3d(cube.clone()),
+        MeshMaterial3d(materials.add(Color::srgb(0.3, 0.5, 0.8))),
+        Transform::from_xyz(-2.0, 0.5, 0.0),
+    ));
+    commands.spawn((
+        Mesh3d(cube.clone()),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.2, 0.3))),
+        Transform::from_xyz(0.0, 0.5, 0.0),
+        GlitchMasked,
+    ));
+    commands.spawn((
+        Mesh3d(cube.clone()),
+        MeshMaterial3d(materials.add(Color::srgb(0.3, 0.8, 0.4))),
+        Transform::from_xyz(2.0, 0.5, 0.0),
+    ));
+    // Slides in front of the corrupted cube.
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(0.8, 0.8, 0.2))),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.8, 0.8))),
+        Transform::from_xyz(0.0, 0.6, 1.5),
+        GlitchMaskOccluder,
+        Sway,
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(10.0, 10.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.3, 0.3, 0.3))),
+    ));
+    commands.spawn((PointLight::default(), Transform::from_xyz(2.0, 4.0, 4.0)));
+}
+
+fn sway(time: Res<Time>, mut query: Query<&mut Transform, With<Sway>>) {
+    for mut transform in &mut query {
+        transform.translation.x = time.elapsed_secs().sin();
+    }
+}
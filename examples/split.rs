@@ -0,0 +1,60 @@
+//! Compare the effect run as one pass with the effect split into a tearing
+//! and a coloring pass, between which passes of your own can be added.
+//!
+//! The effect is held at a fixed time so both runs render the same frame.
+//! Press space to save it, then compare the two screenshots:
+//!
+//! ```sh
+//! cargo run --example split           # saves video_glitch_fused.png
+//! cargo run --example split -- split  # saves video_glitch_split.png
+//! ```
+use bevy::{
+    prelude::*,
+    render::view::screenshot::{save_to_disk, Screenshot},
+};
+use bevy_video_glitch::*;
+
+fn main() {
+    let split = std::env::args().nth(1).as_deref() == Some("split");
+    let plugin = if split {
+        VideoGlitchPlugin::default().with_split_passes()
+    } else {
+        VideoGlitchPlugin::default()
+    };
+    App::new()
+        .add_plugins((DefaultPlugins, plugin))
+        .insert_resource(Mode(if split { "split" } else { "fused" }))
+        .add_systems(Startup, setup)
+        .add_systems(Update, save_screenshot)
+        .run();
+}
+
+#[derive(Resource)]
+struct Mode(&'static str);
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(1.5, 1.5, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+        VideoGlitchSettings::default(),
+        GlitchTimeOverride(4.2),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::from_length(1.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
+    ));
+    commands.spawn((PointLight::default(), Transform::from_xyz(2.0, 3.0, 4.0)));
+}
+
+fn save_screenshot(mut commands: Commands, keys: Res<ButtonInput<KeyCode>>, mode: Res<Mode>) {
+    if keys.just_pressed(KeyCode::Space) {
+        let path = format!("video_glitch_{}.png", mode.0);
+        commands
+            .spawn(Screenshot::primary_window())
+            .observe(save_to_disk(path));
+    }
+}
@@ -0,0 +1,72 @@
+//! Two cameras side by side with different intensities.
+//!
+//! The left camera glitches subtly and the right camera heavily. Each effect
+//! stays inside its own viewport.
+use bevy::{prelude::*, render::camera::Viewport, window::WindowResized};
+use bevy_video_glitch::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, VideoGlitchPlugin::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, set_viewports)
+        .run();
+}
+
+#[derive(Component)]
+struct Side(u32);
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::from_length(1.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
+    ));
+    commands.spawn((PointLight::default(), Transform::from_xyz(2.0, 3.0, 4.0)));
+
+    for (index, intensity) in [0.1, 0.9].into_iter().enumerate() {
+        commands.spawn((
+            Camera3d::default(),
+            Transform::from_xyz(0.0, 0.0, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+            Camera {
+                order: index as isize,
+                // Don't clear the other camera's half.
+                clear_color: if index == 0 {
+                    ClearColorConfig::Default
+                } else {
+                    ClearColorConfig::None
+                },
+                ..default()
+            },
+            VideoGlitchSettings {
+                intensity,
+                ..default()
+            },
+            Side(index as u32),
+        ));
+    }
+}
+
+fn set_viewports(
+    windows: Query<&Window>,
+    mut resized: EventReader<WindowResized>,
+    mut cameras: Query<(&mut Camera, &Side)>,
+) {
+    // Also runs on the first frame since the window starts out "resized".
+    for event in resized.read() {
+        let Ok(window) = windows.get(event.window) else {
+            continue;
+        };
+        let size = window.physical_size();
+        for (mut camera, side) in &mut cameras {
+            camera.viewport = Some(Viewport {
+                physical_position: UVec2::new(side.0 * size.x / 2, 0),
+                physical_size: UVec2::new(size.x / 2, size.y),
+                ..default()
+            });
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! Toggle the effect on a camera with temporal antialiasing with the space
+//! bar.
+//!
+//! The effect runs after TAA, so the tears vanish the frame it is turned off
+//! instead of lingering in TAA's history.
+use bevy::{
+    core_pipeline::experimental::taa::{TemporalAntiAliasPlugin, TemporalAntiAliasing},
+    prelude::*,
+};
+use bevy_video_glitch::*;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            TemporalAntiAliasPlugin,
+            VideoGlitchPlugin::default(),
+        ))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (rotate, toggle_glitch))
+        .run();
+}
+
+#[derive(Component)]
+struct Rotate;
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(1.5, 1.5, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+        // TAA requires MSAA to be off.
+        Msaa::Off,
+        TemporalAntiAliasing::default(),
+        VideoGlitchSettings::default(),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::from_length(1.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
+        Rotate,
+    ));
+    commands.spawn((PointLight::default(), Transform::from_xyz(2.0, 3.0, 4.0)));
+}
+
+fn rotate(time: Res<Time>, mut query: Query<&mut Transform, With<Rotate>>) {
+    for mut transform in &mut query {
+        transform.rotate_y(time.delta_secs());
+    }
+}
+
+fn toggle_glitch(keys: Res<ButtonInput<KeyCode>>, mut settings: Query<&mut VideoGlitchSettings>) {
+    if keys.just_pressed(KeyCode::Space) {
+        for mut settings in &mut settings {
+            settings.intensity = 1.0 - settings.intensity;
+        }
+    }
+}
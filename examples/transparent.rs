@@ -0,0 +1,109 @@
+//! A glitched cube rendered with a transparent background into an image, which
+//! is composited over a checkerboard by a 2D camera.
+//!
+//! The checkerboard shows through everywhere but the cube and its torn rows.
+use bevy::{
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_resource::{
+            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+        },
+        view::RenderLayers,
+    },
+};
+use bevy_video_glitch::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, VideoGlitchPlugin::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, rotate)
+        .run();
+}
+
+#[derive(Component)]
+struct Rotates;
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let size = Extent3d {
+        width: 512,
+        height: 512,
+        ..default()
+    };
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: None,
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    image.resize(size);
+    let layer = images.add(image);
+
+    // The cube is drawn on its own render layer over a transparent clear.
+    let cube_layer = RenderLayers::layer(1);
+    commands.spawn((
+        Camera3d::default(),
+        Camera {
+            order: -1,
+            target: RenderTarget::Image(layer.clone()),
+            clear_color: Color::NONE.into(),
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+        cube_layer.clone(),
+        VideoGlitchSettings::default(),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::from_length(1.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
+        Rotates,
+        cube_layer.clone(),
+    ));
+    commands.spawn((
+        PointLight::default(),
+        Transform::from_xyz(2.0, 3.0, 4.0),
+        cube_layer,
+    ));
+
+    // The checkerboard and the composited layer on top of it.
+    commands.spawn(Camera2d);
+    for x in -8..8 {
+        for y in -5..5 {
+            let shade = if (x + y) % 2 == 0 { 0.25 } else { 0.75 };
+            commands.spawn((
+                Sprite {
+                    color: Color::srgb(shade, shade, shade),
+                    custom_size: Some(Vec2::splat(64.0)),
+                    ..default()
+                },
+                Transform::from_xyz(x as f32 * 64.0 + 32.0, y as f32 * 64.0 + 32.0, 0.0),
+            ));
+        }
+    }
+    commands.spawn((
+        Sprite::from_image(layer),
+        Transform::from_xyz(0.0, 0.0, 1.0),
+    ));
+}
+
+fn rotate(time: Res<Time>, mut query: Query<&mut Transform, With<Rotates>>) {
+    for mut transform in &mut query {
+        transform.rotate_x(0.7 * time.delta_secs());
+        transform.rotate_y(time.delta_secs());
+    }
+}
@@ -0,0 +1,71 @@
+//! A button over a glitched scene.
+//!
+//! By default the UI stays untouched above the effect. Pass `after-ui` to
+//! glitch the UI along with the scene:
+//!
+//! ```sh
+//! cargo run --example ui --features bevy_ui -- after-ui
+//! ```
+use bevy::prelude::*;
+use bevy_video_glitch::*;
+
+fn main() {
+    let position = match std::env::args().nth(1).as_deref() {
+        Some("after-ui") => GlitchGraphPosition::AfterUi,
+        _ => GlitchGraphPosition::default(),
+    };
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            VideoGlitchPlugin::default().with_position(position),
+        ))
+        .add_systems(Startup, setup)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(1.5, 1.5, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+        VideoGlitchSettings::default(),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::from_length(1.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
+    ));
+    commands.spawn((PointLight::default(), Transform::from_xyz(2.0, 3.0, 4.0)));
+
+    commands
+        .spawn(Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::End,
+            justify_content: JustifyContent::Center,
+            padding: UiRect::all(Val::Px(40.0)),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        padding: UiRect::axes(Val::Px(24.0), Val::Px(12.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new("Continue"),
+                        TextFont {
+                            font_size: 32.0,
+                            ..default()
+                        },
+                    ));
+                });
+        });
+}
@@ -0,0 +1,133 @@
+//! Walk between two rooms with different glitch volumes, joined by a corridor
+//! where their looks blend. The camera walks back and forth on its own; hold
+//! the arrow keys to walk it yourself.
+use bevy::prelude::*;
+use bevy_video_glitch::*;
+
+const WALK_SPEED: f32 = 3.0;
+// The rooms are this far from the middle of the corridor.
+const ROOM_OFFSET: f32 = 7.0;
+const ROOM_SIZE: Vec3 = Vec3::new(8.0, 4.0, 8.0);
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, VideoGlitchPlugin::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, walk)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 1.5, 0.0).looking_at(Vec3::new(0.0, 1.0, -10.0), Vec3::Y),
+        // Clean outside of the volumes.
+        VideoGlitchSettings {
+            intensity: 0.0,
+            ..default()
+        },
+    ));
+
+    // A worn tape in the left room and a broken stream in the right one.
+    let rooms = [
+        (
+            -ROOM_OFFSET,
+            Color::srgb(0.3, 0.5, 0.8),
+            VideoGlitchSettings {
+                intensity: 0.5,
+                displacement: DisplacementSettings {
+                    amount: 0.1,
+                    speed: 1.0,
+                    noise_mode: GlitchNoiseMode::Value,
+                    ..default()
+                },
+                overlay: OverlaySettings {
+                    interference: 0.5,
+                    scanlines: 0.3,
+                    color_space: GlitchColorSpace::Perceptual,
+                },
+                ..default()
+            },
+        ),
+        (
+            ROOM_OFFSET,
+            Color::srgb(0.8, 0.3, 0.3),
+            VideoGlitchSettings {
+                intensity: 0.8,
+                displacement: DisplacementSettings {
+                    amount: 0.35,
+                    speed: 6.0,
+                    pixel_snap: true,
+                    noise_mode: GlitchNoiseMode::Cell,
+                    ..default()
+                },
+                color: ColorSettings {
+                    aberration: Mat3::from_cols(Vec3::Y, Vec3::Z, Vec3::X),
+                    aberration_offset: 0.03,
+                    ..default()
+                },
+                effect_fps: 15.0,
+                ..default()
+            },
+        ),
+    ];
+    let floor = meshes.add(Cuboid::new(ROOM_SIZE.x, 0.1, ROOM_SIZE.z));
+    let pillar = meshes.add(Cuboid::new(0.5, ROOM_SIZE.y, 0.5));
+    for (x, color, settings) in rooms {
+        let material = materials.add(color);
+        commands.spawn((
+            GlitchVolume {
+                shape: GlitchVolumeShape::Box(ROOM_SIZE / 2.0),
+                // Both fade in over the corridor between the rooms.
+                blend_distance: 2.0 * ROOM_OFFSET - ROOM_SIZE.x,
+                settings,
+                ..default()
+            },
+            Transform::from_xyz(x, ROOM_SIZE.y / 2.0, 0.0),
+        ));
+        commands.spawn((
+            Mesh3d(floor.clone()),
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(x, 0.0, 0.0),
+        ));
+        for corner in [Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0)] {
+            let corner = corner * (ROOM_SIZE.xz() / 2.0 - 0.5);
+            commands.spawn((
+                Mesh3d(pillar.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::from_xyz(x + corner.x, ROOM_SIZE.y / 2.0, corner.y),
+            ));
+        }
+    }
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(2.0 * ROOM_OFFSET - ROOM_SIZE.x, 0.1, 2.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.6, 0.6, 0.6))),
+    ));
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(1.0, 3.0, 2.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+}
+
+// Walk along the rooms, on its own until an arrow key is pressed.
+fn walk(
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut cameras: Query<&mut Transform, With<Camera3d>>,
+    mut manual: Local<bool>,
+) {
+    let input = keys.pressed(KeyCode::ArrowRight) as i32 - keys.pressed(KeyCode::ArrowLeft) as i32;
+    *manual |= input != 0;
+    for mut transform in &mut cameras {
+        transform.translation.x = if *manual {
+            (transform.translation.x + input as f32 * WALK_SPEED * time.delta_secs())
+                .clamp(-ROOM_OFFSET, ROOM_OFFSET)
+        } else {
+            ROOM_OFFSET * (time.elapsed_secs() * WALK_SPEED / ROOM_OFFSET / 2.0).sin()
+        };
+    }
+}
@@ -0,0 +1,75 @@
+//! A scene transition: press space to sweep a wipe of corruption across the
+//! screen to black, and again to sweep it back.
+use bevy::prelude::*;
+use bevy_video_glitch::*;
+
+// How long a sweep takes, in seconds.
+const DURATION: f32 = 1.0;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, VideoGlitchPlugin::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, sweep)
+        .run();
+}
+
+// Where the wipe is heading, 1 to cover the screen, and how far it has come
+// in linear time.
+#[derive(Component, Default)]
+struct Sweep {
+    target: f32,
+    linear: f32,
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 1.5, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+        VideoGlitchSettings {
+            intensity: 1.0,
+            ..default()
+        },
+        GlitchWipe {
+            angle: -0.3,
+            fill: Some(Color::BLACK),
+            ..default()
+        },
+        Sweep::default(),
+    ));
+
+    let cube = meshes.add(Cuboid::from_length(0.8));
+    for i in -3..=3 {
+        commands.spawn((
+            Mesh3d(cube.clone()),
+            MeshMaterial3d(materials.add(Color::hsl(i as f32 * 40.0 + 120.0, 0.6, 0.5))),
+            Transform::from_xyz(i as f32 * 1.1, 0.0, 0.0),
+        ));
+    }
+    commands.spawn((PointLight::default(), Transform::from_xyz(0.0, 4.0, 4.0)));
+}
+
+// Move the wipe towards its target, eased so it starts and ends gently.
+fn sweep(
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut wipes: Query<(&mut GlitchWipe, &mut Sweep)>,
+) {
+    for (mut wipe, mut sweep) in &mut wipes {
+        if keys.just_pressed(KeyCode::Space) {
+            sweep.target = 1.0 - sweep.target;
+        }
+        let step = time.delta_secs() / DURATION;
+        sweep.linear = if sweep.target > sweep.linear {
+            (sweep.linear + step).min(sweep.target)
+        } else {
+            (sweep.linear - step).max(sweep.target)
+        };
+        let x = sweep.linear;
+        wipe.progress = x * x * (3.0 - 2.0 * x);
+    }
+}
@@ -0,0 +1,230 @@
+//! Helpers for building [`ColorSettings::aberration`] matrices.
+//!
+//! [`ColorSettings::aberration`]: crate::ColorSettings::aberration
+use bevy::prelude::*;
+
+// The permutations of the three color channels other than the identity, as
+// matrices.
+pub(crate) const CHANNEL_PERMUTATIONS: [Mat3; 5] = [
+    Mat3::from_cols(Vec3::Y, Vec3::X, Vec3::Z),
+    Mat3::from_cols(Vec3::X, Vec3::Z, Vec3::Y),
+    Mat3::from_cols(Vec3::Z, Vec3::Y, Vec3::X),
+    Mat3::from_cols(Vec3::Y, Vec3::Z, Vec3::X),
+    Mat3::from_cols(Vec3::Z, Vec3::X, Vec3::Y),
+];
+
+/// The common kinds of dichromatic color vision deficiency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum ColorVisionDeficiency {
+    /// Missing long wavelength (red) cones.
+    Protanopia,
+    /// Missing medium wavelength (green) cones.
+    Deuteranopia,
+    /// Missing short wavelength (blue) cones.
+    Tritanopia,
+}
+
+impl ColorVisionDeficiency {
+    /// The linear RGB simulation matrix for this deficiency at full severity
+    /// from Machado, Oliveira and Fernandes, "A Physiologically-based Model for
+    /// Simulation of Color Vision Deficiency" (2009).
+    pub fn simulation_matrix(self) -> Mat3 {
+        let rows = match self {
+            Self::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            Self::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            Self::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        };
+        Mat3::from_cols_array_2d(&rows).transpose()
+    }
+
+    /// How `color`, in linear RGB, looks to someone with this deficiency.
+    pub fn simulate(self, color: Vec3) -> Vec3 {
+        self.simulation_matrix() * color
+    }
+
+    /// The unit direction in linear RGB along which color differences are
+    /// the least visible with this deficiency.
+    ///
+    /// The protanopia and deuteranopia matrices have rank two, so this is the
+    /// direction they map to zero: `simulate(confusion_axis())` is
+    /// (numerically) zero. The tritanopia matrix keeps some of every
+    /// direction, and this is the one it shrinks the most.
+    pub fn confusion_axis(self) -> Vec3 {
+        let m = self.simulation_matrix();
+        let rows = m.transpose();
+        let mut axis = rows.x_axis.cross(rows.y_axis).normalize();
+        // The direction shrunk the most is the eigenvector of MᵀM with the
+        // smallest eigenvalue, found by inverse iteration when M has full
+        // rank.
+        let normal = m.transpose() * m;
+        if normal.determinant() > 1e-6 {
+            let inverse = normal.inverse();
+            for _ in 0..16 {
+                axis = (inverse * axis).normalize();
+            }
+        }
+        axis
+    }
+}
+
+/// Constructors for [`ColorSettings::aberration`] matrices.
+///
+/// [`ColorSettings::aberration`]: crate::ColorSettings::aberration
+pub struct ColorAberration;
+
+impl ColorAberration {
+    /// An aberration matrix whose fringes stay visible with `cvd`.
+    ///
+    /// For protanopia and deuteranopia the fringes are biased toward the
+    /// blue/yellow axis, for tritanopia toward red/cyan, and then made exactly
+    /// orthogonal to the confusion axis with [`Self::project_cvd_safe`].
+    pub fn cvd_safe(cvd: ColorVisionDeficiency) -> Mat3 {
+        let base = match cvd {
+            ColorVisionDeficiency::Protanopia | ColorVisionDeficiency::Deuteranopia => {
+                Mat3::from_cols(
+                    Vec3::new(0.5, 0.5, 0.0),
+                    Vec3::new(0.0, 0.0, 1.0),
+                    Vec3::new(0.5, 0.5, 0.0),
+                )
+            }
+            ColorVisionDeficiency::Tritanopia => Mat3::from_cols(
+                Vec3::new(0.0, 0.5, 0.5),
+                Vec3::new(1.0, 0.0, 0.0),
+                Vec3::new(0.0, 0.5, 0.5),
+            ),
+        };
+        Self::project_cvd_safe(base, cvd)
+    }
+
+    /// Make an arbitrary aberration matrix safe for `cvd`.
+    ///
+    /// The fringes are the secondary colors, the second and third columns.
+    /// Their component along [`ColorVisionDeficiency::confusion_axis`] is
+    /// removed, so every fringe is visible, and each column is rescaled to
+    /// keep its original sum, so a stochastic matrix stays stochastic. The
+    /// primary color, the first column, is left as is.
+    pub fn project_cvd_safe(matrix: Mat3, cvd: ColorVisionDeficiency) -> Mat3 {
+        let axis = cvd.confusion_axis();
+        let project = |column: Vec3| {
+            let projected = column - axis * column.dot(axis);
+            let sum = projected.element_sum();
+            if sum.abs() > f32::EPSILON {
+                projected * (column.element_sum() / sum)
+            } else {
+                projected
+            }
+        };
+        Mat3::from_cols(
+            matrix.x_axis,
+            project(matrix.y_axis),
+            project(matrix.z_axis),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [ColorVisionDeficiency; 3] = [
+        ColorVisionDeficiency::Protanopia,
+        ColorVisionDeficiency::Deuteranopia,
+        ColorVisionDeficiency::Tritanopia,
+    ];
+
+    #[test]
+    fn simulation_matches_the_published_matrices() {
+        assert!(ColorVisionDeficiency::Protanopia
+            .simulate(Vec3::X)
+            .abs_diff_eq(Vec3::new(0.152286, 0.114503, -0.003882), 1e-6));
+        assert!(ColorVisionDeficiency::Deuteranopia
+            .simulate(Vec3::Y)
+            .abs_diff_eq(Vec3::new(0.860646, 0.672501, 0.042940), 1e-6));
+        assert!(ColorVisionDeficiency::Tritanopia
+            .simulate(Vec3::Z)
+            .abs_diff_eq(Vec3::new(-0.178779, 0.147602, 0.303900), 1e-6));
+    }
+
+    #[test]
+    fn simulation_keeps_grays() {
+        for cvd in ALL {
+            for gray in [0.0, 0.5, 1.0] {
+                assert!(cvd
+                    .simulate(Vec3::splat(gray))
+                    .abs_diff_eq(Vec3::splat(gray), 1e-5));
+            }
+        }
+    }
+
+    #[test]
+    fn confusion_axis_is_invisible() {
+        for cvd in [
+            ColorVisionDeficiency::Protanopia,
+            ColorVisionDeficiency::Deuteranopia,
+        ] {
+            let axis = cvd.confusion_axis();
+            assert!((axis.length() - 1.0).abs() < 1e-5);
+            assert!(cvd.simulate(axis).abs_diff_eq(Vec3::ZERO, 1e-4), "{cvd:?}");
+        }
+    }
+
+    #[test]
+    fn confusion_axis_is_the_least_visible() {
+        for cvd in ALL {
+            let shrunk = cvd.simulate(cvd.confusion_axis()).length();
+            for x in -4..=4 {
+                for y in -4..=4 {
+                    for z in -4..=4 {
+                        let Some(direction) =
+                            Vec3::new(x as f32, y as f32, z as f32).try_normalize()
+                        else {
+                            continue;
+                        };
+                        assert!(
+                            shrunk <= cvd.simulate(direction).length() + 1e-5,
+                            "{cvd:?} {direction}"
+                        );
+                    }
+                }
+            }
+        }
+        // The published tritanopia matrix has no invisible direction.
+        let tritanopia = ColorVisionDeficiency::Tritanopia;
+        assert!((tritanopia.simulate(tritanopia.confusion_axis()).length() - 0.156).abs() < 1e-3);
+    }
+
+    #[test]
+    fn safe_fringes_avoid_the_confusion_axis() {
+        for cvd in ALL {
+            let axis = cvd.confusion_axis();
+            for matrix in [ColorAberration::cvd_safe(cvd), Mat3::IDENTITY]
+                .into_iter()
+                .chain(CHANNEL_PERMUTATIONS)
+            {
+                let safe = ColorAberration::project_cvd_safe(matrix, cvd);
+                assert_eq!(safe.x_axis, matrix.x_axis);
+                for (fringe, original) in
+                    [(safe.y_axis, matrix.y_axis), (safe.z_axis, matrix.z_axis)]
+                {
+                    assert!(fringe.dot(axis).abs() < 1e-5, "{cvd:?} {matrix}");
+                    assert!(
+                        (fringe.element_sum() - original.element_sum()).abs() < 1e-5,
+                        "{cvd:?} {matrix}"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Pump a camera's intensity with the level of the soundtrack.
+use bevy::prelude::*;
+
+use crate::{GlitchModulation, GlitchModulator};
+
+/// The level of the soundtrack this frame, from 0 for silence to 1 at full
+/// scale, written by the game.
+///
+/// Bevy's audio doesn't expose the envelope of the sounds it plays, so the
+/// level comes from wherever the game has it, e.g. an analysis of the
+/// samples it streams or a level track authored along the music. A frame
+/// without a new level counts as silence, so the effect settles back to its
+/// baseline when the game stops writing it.
+#[derive(Resource, Reflect, Debug, Clone, Copy, Default)]
+#[reflect(Resource, Default)]
+pub struct GlitchAudioLevel(pub f32);
+
+/// Adds to the intensity of a camera from the [`GlitchAudioLevel`] every
+/// frame, as a [`GlitchModulator`].
+///
+/// The level is smoothed, rising over `attack` and falling over `release`
+/// seconds of real time whatever the frame rate, then mapped from
+/// `quiet..loud` onto `baseline..peak`, which is added to the intensity of
+/// the camera's [`VideoGlitchSettings`]. Set that intensity to 0 to have the
+/// driver own it.
+///
+/// [`VideoGlitchSettings`]: crate::VideoGlitchSettings
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component, Default)]
+pub struct GlitchAudioDriver {
+    /// How quickly a louder level comes through, in seconds to cover about
+    /// two thirds of the way.
+    pub attack: f32,
+    /// How quickly a quieter level comes through, in seconds to cover about
+    /// two thirds of the way.
+    pub release: f32,
+    /// The level at and below which the intensity is `baseline`.
+    pub quiet: f32,
+    /// The level at and above which the intensity is `peak`.
+    pub loud: f32,
+    /// The intensity added during silence.
+    pub baseline: f32,
+    /// The intensity added at the `loud` level.
+    pub peak: f32,
+    smoothed: f32,
+}
+
+impl Default for GlitchAudioDriver {
+    fn default() -> Self {
+        Self {
+            attack: 0.02,
+            release: 0.3,
+            quiet: 0.0,
+            loud: 1.0,
+            baseline: 0.0,
+            peak: 1.0,
+            smoothed: 0.0,
+        }
+    }
+}
+
+impl GlitchAudioDriver {
+    /// A driver adding `baseline` to the intensity during silence, up to
+    /// `peak` at the `loud` level, with the default attack, release and
+    /// levels.
+    pub fn new(baseline: f32, peak: f32) -> Self {
+        Self {
+            baseline,
+            peak,
+            ..default()
+        }
+    }
+
+    /// The smoothed level, from 0 at `quiet` to 1 at `loud`.
+    pub fn level(&self) -> f32 {
+        self.smoothed
+    }
+
+    fn advance(&mut self, level: f32, delta: f32) {
+        let target =
+            ((level - self.quiet) / (self.loud - self.quiet).max(f32::EPSILON)).clamp(0.0, 1.0);
+        let time_constant = if target > self.smoothed {
+            self.attack
+        } else {
+            self.release
+        };
+        // Exponential smoothing, which takes the same time to settle however
+        // the time is split into frames.
+        let blend = if time_constant > 0.0 {
+            1.0 - (-delta / time_constant).exp()
+        } else {
+            1.0
+        };
+        self.smoothed += (target - self.smoothed) * blend;
+    }
+}
+
+impl GlitchModulator for GlitchAudioDriver {
+    fn modulation(&self) -> Option<GlitchModulation> {
+        Some(GlitchModulation::Offset(
+            self.baseline.lerp(self.peak, self.smoothed),
+        ))
+    }
+}
+
+pub(crate) fn drive_glitch_from_audio(
+    time: Res<Time<Real>>,
+    level: Option<Res<GlitchAudioLevel>>,
+    mut cameras: Query<&mut GlitchAudioDriver>,
+) {
+    let level = level
+        .filter(|level| level.is_changed())
+        .map_or(0.0, |level| level.0);
+    for mut driver in &mut cameras {
+        driver.advance(level, time.delta_secs());
+    }
+}
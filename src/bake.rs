@@ -0,0 +1,318 @@
+//! Apply the effect once to an image and keep the result, e.g. for save slot
+//! thumbnails.
+use std::sync::{
+    mpsc::{Receiver, Sender},
+    Mutex,
+};
+
+use bevy::{
+    core_pipeline::tonemapping::{DebandDither, Tonemapping},
+    prelude::*,
+    render::{
+        camera::{RenderTarget, ScalingMode},
+        render_asset::{RenderAssetUsages, RenderAssets},
+        render_resource::{
+            BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Extent3d, ImageCopyBuffer,
+            ImageDataLayout, Maintain, MapMode, PipelineCache, TextureDimension, TextureFormat,
+            TextureUsages,
+        },
+        renderer::{RenderDevice, RenderQueue},
+        texture::GpuImage,
+        view::RenderLayers,
+        Extract,
+    },
+};
+
+use crate::{
+    GlitchTimeOverride, VideoGlitchPipelineStatus, VideoGlitchSettings, BAKE_RENDER_LAYER,
+};
+
+/// Sent when an image from [`bake_video_glitch`] holds the glitched result.
+#[derive(Event, Debug, Clone)]
+pub struct VideoGlitchBaked {
+    /// The baked image.
+    pub image: Handle<Image>,
+}
+
+/// Apply the effect with `settings` at `time` to `source` once, returning the
+/// image the result is written to.
+///
+/// The image is transparent until [`VideoGlitchBaked`] is sent for it, a few
+/// frames later; from then on it is an ordinary image asset, on the CPU as
+/// well as the GPU. The result is in [`TextureFormat::Rgba8UnormSrgb`] and has
+/// the size of `source`, whatever its format. A source that is still loading
+/// is baked once it has loaded.
+///
+/// Different times give different glitches, so baking the same image at a few
+/// times makes variants of it. Bakes run one after another, each drawn by a
+/// temporary camera on [`GlitchBake::RENDER_LAYER`]; reading a result back
+/// briefly stalls the frame it arrives in. [`GlitchVolume`]s and
+/// [`GlitchHotspot`]s in the world leave that camera alone, so the result
+/// only depends on `settings` and `time`.
+///
+/// [`GlitchVolume`]: crate::GlitchVolume
+/// [`GlitchHotspot`]: crate::GlitchHotspot
+pub fn bake_video_glitch(
+    commands: &mut Commands,
+    images: &mut Assets<Image>,
+    source: Handle<Image>,
+    settings: VideoGlitchSettings,
+    time: f32,
+) -> Handle<Image> {
+    let target = images.add(target_image(Extent3d::default()));
+    commands.spawn(GlitchBake {
+        source,
+        target: target.clone(),
+        settings,
+        time,
+        stage: BakeStage::Queued,
+    });
+    target
+}
+
+// Marks the temporary camera drawing a bake, which the world's volumes and
+// hotspots must not reach.
+#[derive(Component)]
+pub(crate) struct GlitchBakeCamera;
+
+/// A bake in progress, see [`bake_video_glitch`].
+#[derive(Component)]
+// For the quad and camera drawing the bake, its children, to be placed and
+// visible.
+#[require(Transform, Visibility)]
+pub struct GlitchBake {
+    source: Handle<Image>,
+    target: Handle<Image>,
+    settings: VideoGlitchSettings,
+    time: f32,
+    stage: BakeStage,
+}
+
+impl GlitchBake {
+    /// The render layer of the cameras drawing the bakes, which other
+    /// cameras don't see unless told to.
+    pub const RENDER_LAYER: usize = BAKE_RENDER_LAYER;
+}
+
+enum BakeStage {
+    Queued,
+    // Counts the frames drawn with every pipeline ready.
+    Rendering(u32),
+    // Read back in the frame this is extracted in.
+    Read,
+    Reading,
+}
+
+// Frames to wait after the effect's pipelines are ready, for the pipelines
+// drawing the source image to be ready as well.
+const SETTLE_FRAMES: u32 = 3;
+
+fn target_image(size: Extent3d) -> Image {
+    let mut image = Image::new_fill(
+        size,
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING
+        | TextureUsages::COPY_SRC
+        | TextureUsages::COPY_DST
+        | TextureUsages::RENDER_ATTACHMENT;
+    image
+}
+
+/// Bytes read back from the GPU for a baked image, without row padding.
+pub(crate) type BakeResult = (AssetId<Image>, Vec<u8>);
+
+#[derive(Resource)]
+pub(crate) struct BakeReceiver(pub(crate) Mutex<Receiver<BakeResult>>);
+
+#[derive(Resource)]
+pub(crate) struct BakeSender(pub(crate) Sender<BakeResult>);
+
+pub(crate) fn run_glitch_bakes(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    // Absent without the `PbrPlugin`, which draws the images.
+    meshes: Option<ResMut<Assets<Mesh>>>,
+    materials: Option<ResMut<Assets<StandardMaterial>>>,
+    status: Res<VideoGlitchPipelineStatus>,
+    receiver: Res<BakeReceiver>,
+    mut baked: EventWriter<VideoGlitchBaked>,
+    mut bakes: Query<(Entity, &mut GlitchBake)>,
+) {
+    for (target, data) in receiver.0.lock().unwrap().try_iter() {
+        let Some((entity, bake)) = bakes.iter().find(|(_, bake)| bake.target.id() == target) else {
+            continue;
+        };
+        if let Some(image) = images.get_mut(&bake.target) {
+            image.data = data;
+        }
+        baked.send(VideoGlitchBaked {
+            image: bake.target.clone(),
+        });
+        commands.entity(entity).despawn_recursive();
+    }
+
+    // Only one bake is drawn at a time, so the cameras don't see each
+    // other's images.
+    let mut active = false;
+    for (_, mut bake) in &mut bakes {
+        match bake.stage {
+            BakeStage::Queued => {}
+            BakeStage::Rendering(frames) if status.is_ready() => {
+                bake.stage = if frames + 1 >= SETTLE_FRAMES {
+                    BakeStage::Read
+                } else {
+                    BakeStage::Rendering(frames + 1)
+                };
+            }
+            BakeStage::Rendering(_) | BakeStage::Reading => {}
+            BakeStage::Read => bake.stage = BakeStage::Reading,
+        }
+        active |= !matches!(bake.stage, BakeStage::Queued);
+    }
+    let (false, Some(mut meshes), Some(mut materials)) = (active, meshes, materials) else {
+        return;
+    };
+
+    let Some((entity, mut bake)) = bakes
+        .iter_mut()
+        .find(|(_, bake)| images.contains(&bake.source))
+    else {
+        return;
+    };
+    let size = images.get(&bake.source).unwrap().texture_descriptor.size;
+    images.insert(&bake.target, target_image(size));
+
+    let layer = RenderLayers::layer(GlitchBake::RENDER_LAYER);
+    let (width, height) = (size.width as f32, size.height as f32);
+    let quad = (
+        Mesh3d(meshes.add(Rectangle::new(width, height))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color_texture: Some(bake.source.clone()),
+            unlit: true,
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        })),
+    );
+    let camera = (
+        Camera3d::default(),
+        Camera {
+            order: -100,
+            target: RenderTarget::Image(bake.target.clone()),
+            clear_color: ClearColorConfig::Custom(Color::NONE),
+            ..default()
+        },
+        Projection::Orthographic(OrthographicProjection {
+            scaling_mode: ScalingMode::Fixed { width, height },
+            ..OrthographicProjection::default_3d()
+        }),
+        Transform::from_xyz(0.0, 0.0, 10.0),
+        // Leave the colors of the source as they are.
+        Tonemapping::None,
+        DebandDither::Disabled,
+    );
+    commands.entity(entity).with_children(|parent| {
+        parent.spawn((quad, layer.clone()));
+        parent.spawn((
+            camera,
+            layer,
+            bake.settings,
+            GlitchTimeOverride(bake.time),
+            GlitchBakeCamera,
+        ));
+    });
+    bake.stage = BakeStage::Rendering(0);
+}
+
+/// The images to read back this frame, in the render world.
+#[derive(Resource, Default)]
+pub(crate) struct BakeReadbacks(Vec<AssetId<Image>>);
+
+pub(crate) fn extract_glitch_bakes(
+    mut readbacks: ResMut<BakeReadbacks>,
+    bakes: Extract<Query<&GlitchBake>>,
+) {
+    readbacks.0 = bakes
+        .iter()
+        .filter(|bake| matches!(bake.stage, BakeStage::Read))
+        .map(|bake| bake.target.id())
+        .collect();
+}
+
+/// Copy the baked images to the CPU once the frame that drew them has been
+/// submitted.
+pub(crate) fn read_back_glitch_bakes(
+    readbacks: Res<BakeReadbacks>,
+    gpu_images: Res<RenderAssets<GpuImage>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    pipeline_cache: Res<PipelineCache>,
+    sender: Res<BakeSender>,
+    // The images waiting for every pipeline to compile.
+    mut pending: Local<Vec<AssetId<Image>>>,
+) {
+    pending.extend(readbacks.0.iter().copied());
+    // The quad's material may still be compiling after the effect is ready,
+    // leaving the image empty. The bake cameras draw every frame until their
+    // image is read back, so a later frame has it.
+    if pipeline_cache.waiting_pipelines().next().is_some() {
+        return;
+    }
+    for target in pending.drain(..) {
+        let Some(image) = gpu_images.get(target) else {
+            continue;
+        };
+        let row_bytes = image.size.x as usize * 4;
+        let padded_row_bytes = RenderDevice::align_copy_bytes_per_row(row_bytes);
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("video_glitch_bake_readback"),
+            size: (padded_row_bytes * image.size.y as usize) as u64,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = render_device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("video_glitch_bake_readback"),
+        });
+        encoder.copy_texture_to_buffer(
+            image.texture.as_image_copy(),
+            ImageCopyBuffer {
+                buffer: &buffer,
+                layout: ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row_bytes as u32),
+                    rows_per_image: None,
+                },
+            },
+            Extent3d {
+                width: image.size.x,
+                height: image.size.y,
+                depth_or_array_layers: 1,
+            },
+        );
+        render_queue.submit([encoder.finish()]);
+
+        let sender = sender.0.clone();
+        let mapped = buffer.clone();
+        buffer.slice(..).map_async(MapMode::Read, move |result| {
+            if let Err(err) = result {
+                error!("VideoGlitchPlugin: failed to read back a baked image: {err}");
+                return;
+            }
+            let data = mapped
+                .slice(..)
+                .get_mapped_range()
+                .chunks(padded_row_bytes)
+                .flat_map(|row| &row[..row_bytes])
+                .copied()
+                .collect();
+            mapped.unmap();
+            // The main world may be gone when the app is exiting.
+            let _ = sender.send((target, data));
+        });
+        render_device.poll(Maintain::Wait);
+    }
+}
@@ -0,0 +1,81 @@
+//! Reuse each view's bind groups across frames instead of creating them every
+//! frame.
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Mutex,
+};
+
+use bevy::{
+    prelude::*,
+    render::render_resource::{BindGroup, BufferId, SamplerId, TextureViewId},
+    utils::HashMap,
+};
+
+use crate::VideoGlitchUniform;
+
+/// Everything a view's bind group is made from. Resizing a view or growing a
+/// buffer replaces the texture view or buffer, which changes the key.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) struct BindGroupKey {
+    pub(crate) source: TextureViewId,
+    // Absent when the settings are passed as push constants.
+    pub(crate) settings: Option<BufferId>,
+    pub(crate) globals: BufferId,
+    pub(crate) mask: TextureViewId,
+    pub(crate) depth: TextureViewId,
+    pub(crate) sampler: SamplerId,
+}
+
+// The post processing ping-pong only ever reads from one of two main
+// textures, so two bind groups per view cover every frame.
+const BIND_GROUPS_PER_VIEW: usize = 2;
+
+/// The bind groups of each view, in the render world.
+#[derive(Resource, Default)]
+pub struct VideoGlitchBindGroups {
+    views: Mutex<HashMap<Entity, Vec<(BindGroupKey, BindGroup)>>>,
+    created: AtomicU64,
+}
+
+impl VideoGlitchBindGroups {
+    /// How many bind groups have been created so far. With unchanging views
+    /// this stops growing after each view's first two frames.
+    pub fn created(&self) -> u64 {
+        self.created.load(Ordering::Relaxed)
+    }
+
+    /// Get the bind group of `view` for `key`, creating it if there is none.
+    pub(crate) fn get_or_create(
+        &self,
+        view: Entity,
+        key: BindGroupKey,
+        create: impl FnOnce() -> BindGroup,
+    ) -> BindGroup {
+        let mut views = self.views.lock().unwrap();
+        let bind_groups = views.entry(view).or_default();
+        if let Some((_, bind_group)) = bind_groups.iter().find(|(cached, _)| *cached == key) {
+            return bind_group.clone();
+        }
+
+        // Anything beyond the two live main textures is stale.
+        if bind_groups.len() >= BIND_GROUPS_PER_VIEW {
+            bind_groups.remove(0);
+        }
+        let bind_group = create();
+        bind_groups.push((key, bind_group.clone()));
+        self.created.fetch_add(1, Ordering::Relaxed);
+        bind_group
+    }
+}
+
+/// Forget the bind groups of views that no longer have the effect.
+pub(crate) fn prepare_bind_groups(
+    mut bind_groups: ResMut<VideoGlitchBindGroups>,
+    views: Query<(), With<VideoGlitchUniform>>,
+) {
+    bind_groups
+        .views
+        .get_mut()
+        .unwrap()
+        .retain(|view, _| views.contains(*view));
+}
@@ -0,0 +1,331 @@
+//! Record a camera's glitched output frame by frame, e.g. for GIFs and
+//! trailers.
+use std::{path::PathBuf, time::Duration};
+
+use bevy::{
+    prelude::*,
+    render::view::screenshot::{Screenshot, ScreenshotCaptured},
+    tasks::{block_on, futures_lite::future, IoTaskPool, Task},
+};
+
+use crate::GlitchTimeOverride;
+
+// How long to wait for the requested frames to be read back.
+const READ_BACK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Sent when a [`GlitchCapture`] has written all of its frames.
+#[derive(Event, Debug, Clone)]
+pub struct GlitchCaptured {
+    /// The camera the frames were captured from.
+    pub camera: Entity,
+    /// The directory the frames were written to.
+    pub directory: PathBuf,
+    /// The number of frames written.
+    pub frames: u32,
+}
+
+/// Add this to a camera to write every frame it renders, glitch included, to
+/// numbered PNGs in a directory, `00000.png` onwards.
+///
+/// The frames are read back from the GPU without blocking and written on the
+/// IO task pool. Once all of them are written, [`GlitchCaptured`] is sent and
+/// the component removes itself; remove it earlier to stop capturing. Frames
+/// that aren't read back within 5 seconds of the last request, e.g. because
+/// the camera stopped rendering, are given up on with a warning, and the
+/// capture ends with the frames it has.
+///
+/// By default the effect runs on its usual clock, so the frames follow the
+/// frame rate of the app. [`GlitchCapture::with_fixed_fps`] instead steps the
+/// camera's [`GlitchTimeOverride`] by exactly one frame at that rate per
+/// frame rendered, which makes the capture the same on every run, however
+/// slowly the frames are rendered. The camera's own override, or its absence,
+/// is put back when the capture ends or is removed. A camera with
+/// [`VideoGlitchHideInScreenshots`] is captured without the glitch.
+///
+/// Failures to write a frame are logged.
+///
+/// [`VideoGlitchHideInScreenshots`]: crate::VideoGlitchHideInScreenshots
+#[derive(Component, Debug)]
+pub struct GlitchCapture {
+    directory: PathBuf,
+    length: CaptureLength,
+    fixed_fps: Option<f32>,
+    // The frames requested so far, and those read back so far.
+    requested: u32,
+    read_back: u32,
+    // When the capture started, in real time, and the effect's time then
+    // with a fixed frame rate.
+    started: Option<(Duration, f32)>,
+    // When the last frame was requested, in real time.
+    finished_requesting: Option<Duration>,
+    // The camera's own time override, put back once the capture is removed.
+    previous_time: Option<GlitchTimeOverride>,
+    saving: Vec<Task<()>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CaptureLength {
+    Frames(u32),
+    Duration(Duration),
+}
+
+impl GlitchCapture {
+    /// Capture the next `frames` frames to `directory`, which is created if
+    /// needed.
+    pub fn frames(frames: u32, directory: impl Into<PathBuf>) -> Self {
+        Self::new(CaptureLength::Frames(frames), directory.into())
+    }
+
+    /// Capture the frames of the next `duration` to `directory`, which is
+    /// created if needed. With a fixed frame rate, the duration is of the
+    /// effect's time rather than real time.
+    pub fn seconds(duration: Duration, directory: impl Into<PathBuf>) -> Self {
+        Self::new(CaptureLength::Duration(duration), directory.into())
+    }
+
+    /// Step the effect's time by `1 / fps` seconds per captured frame, for a
+    /// deterministic capture to play back at `fps`.
+    pub fn with_fixed_fps(mut self, fps: f32) -> Self {
+        self.fixed_fps = Some(fps).filter(|fps| *fps > 0.0);
+        self
+    }
+
+    fn new(length: CaptureLength, directory: PathBuf) -> Self {
+        Self {
+            directory,
+            length,
+            fixed_fps: None,
+            requested: 0,
+            read_back: 0,
+            started: None,
+            finished_requesting: None,
+            previous_time: None,
+            saving: Vec::new(),
+        }
+    }
+
+    // Whether another frame is wanted, `elapsed` seconds of real time after
+    // the capture started.
+    fn wants_frame(&self, elapsed: Duration) -> bool {
+        match (self.length, self.fixed_fps) {
+            (CaptureLength::Frames(frames), _) => self.requested < frames,
+            (CaptureLength::Duration(duration), Some(fps)) => {
+                (self.requested as f32) < (duration.as_secs_f32() * fps).ceil()
+            }
+            (CaptureLength::Duration(duration), None) => elapsed < duration,
+        }
+    }
+}
+
+pub(crate) fn run_glitch_captures(
+    mut commands: Commands,
+    real_time: Res<Time<Real>>,
+    time: Res<Time>,
+    mut captured: EventWriter<GlitchCaptured>,
+    mut cameras: Query<(
+        Entity,
+        &Camera,
+        &mut GlitchCapture,
+        Option<&GlitchTimeOverride>,
+    )>,
+) {
+    for (entity, camera, mut capture, time_override) in &mut cameras {
+        let (started, start_time) = *capture.started.get_or_insert_with(|| {
+            (
+                real_time.elapsed(),
+                time_override.map_or(time.elapsed_secs_wrapped(), |time| time.0),
+            )
+        });
+        if capture.requested == 0 {
+            capture.previous_time = time_override.copied();
+            if let Err(err) = std::fs::create_dir_all(&capture.directory) {
+                error!(
+                    "Cannot capture glitch frames to {}: {err}",
+                    capture.directory.display()
+                );
+                commands.entity(entity).remove::<GlitchCapture>();
+                continue;
+            }
+        }
+
+        capture
+            .saving
+            .retain_mut(|task| block_on(future::poll_once(task)).is_none());
+
+        if capture.wants_frame(real_time.elapsed() - started) {
+            let frame = capture.requested;
+            if let Some(fps) = capture.fixed_fps {
+                commands
+                    .entity(entity)
+                    .insert(GlitchTimeOverride(start_time + frame as f32 / fps));
+            }
+            let path = capture.directory.join(format!("{frame:05}.png"));
+            commands.spawn(Screenshot(camera.target.clone())).observe(
+                move |captured: Trigger<ScreenshotCaptured>,
+                      mut captures: Query<&mut GlitchCapture>| {
+                    // Write the frame without waiting for it.
+                    let task = save_frame(captured.event().0.clone(), path.clone());
+                    match captures.get_mut(entity) {
+                        Ok(mut capture) => {
+                            capture.read_back += 1;
+                            capture.saving.push(task);
+                        }
+                        // The capture was stopped, finish writing the frame
+                        // anyway.
+                        Err(_) => task.detach(),
+                    }
+                },
+            );
+            capture.requested += 1;
+            continue;
+        }
+
+        // Every frame has been requested, wait for the last ones to be read
+        // back and written.
+        let finished_requesting = *capture
+            .finished_requesting
+            .get_or_insert(real_time.elapsed());
+        if capture.read_back < capture.requested {
+            if real_time.elapsed() - finished_requesting < READ_BACK_TIMEOUT {
+                continue;
+            }
+            warn!(
+                "Only {} of the {} glitch frames captured from {entity} were read back, \
+                 ending the capture without the others",
+                capture.read_back, capture.requested
+            );
+        }
+        if !capture.saving.is_empty() {
+            continue;
+        }
+        captured.send(GlitchCaptured {
+            camera: entity,
+            directory: capture.directory.clone(),
+            frames: capture.read_back,
+        });
+        commands.entity(entity).remove::<GlitchCapture>();
+    }
+}
+
+// Put a camera's own time back when its capture ends or is removed early,
+// once a fixed frame rate has stepped it.
+pub(crate) fn restore_glitch_capture_time(
+    trigger: Trigger<OnRemove, GlitchCapture>,
+    mut commands: Commands,
+    captures: Query<&GlitchCapture>,
+) {
+    let entity = trigger.entity();
+    let Ok(capture) = captures.get(entity) else {
+        return;
+    };
+    if capture.fixed_fps.is_none() || capture.requested == 0 {
+        return;
+    }
+    let previous = capture.previous_time;
+    commands.queue(move |world: &mut World| {
+        // The camera may be removed along with the capture.
+        let Ok(mut camera) = world.get_entity_mut(entity) else {
+            return;
+        };
+        match previous {
+            Some(previous) => {
+                camera.insert(previous);
+            }
+            None => {
+                camera.remove::<GlitchTimeOverride>();
+            }
+        }
+    });
+}
+
+fn save_frame(image: Image, path: PathBuf) -> Task<()> {
+    IoTaskPool::get().spawn(async move {
+        let result = image
+            .try_into_dynamic()
+            .map_err(|err| err.to_string())
+            .and_then(|image| image.to_rgba8().save(&path).map_err(|err| err.to_string()));
+        if let Err(err) = result {
+            error!("Cannot write glitch frame to {}: {err}", path.display());
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+
+    // An app capturing without rendering, so no frame is ever read back, and
+    // whose real time advances a second per update.
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs(1)))
+            .add_event::<GlitchCaptured>()
+            .add_systems(PostUpdate, run_glitch_captures)
+            .add_observer(restore_glitch_capture_time);
+        app
+    }
+
+    fn capture(frames: u32) -> GlitchCapture {
+        GlitchCapture::frames(frames, std::env::temp_dir().join("glitch_capture_test"))
+            .with_fixed_fps(10.0)
+    }
+
+    #[test]
+    fn removing_a_capture_restores_the_time() {
+        let mut app = app();
+        let camera = app
+            .world_mut()
+            .spawn((Camera::default(), GlitchTimeOverride(3.0), capture(100)))
+            .id();
+        app.update();
+        app.update();
+        assert_ne!(
+            app.world().get::<GlitchTimeOverride>(camera).unwrap().0,
+            3.0
+        );
+
+        app.world_mut().entity_mut(camera).remove::<GlitchCapture>();
+        app.update();
+        assert_eq!(
+            app.world().get::<GlitchTimeOverride>(camera).unwrap().0,
+            3.0
+        );
+    }
+
+    #[test]
+    fn removing_a_capture_removes_its_time() {
+        let mut app = app();
+        let camera = app
+            .world_mut()
+            .spawn((Camera::default(), capture(100)))
+            .id();
+        app.update();
+        assert!(app.world().get::<GlitchTimeOverride>(camera).is_some());
+
+        app.world_mut().entity_mut(camera).remove::<GlitchCapture>();
+        app.update();
+        assert!(app.world().get::<GlitchTimeOverride>(camera).is_none());
+    }
+
+    #[test]
+    fn frames_never_read_back_time_out() {
+        let mut app = app();
+        let camera = app.world_mut().spawn((Camera::default(), capture(2))).id();
+        let mut captured = Vec::new();
+        for _ in 0..10 {
+            app.update();
+            captured.extend(
+                app.world_mut()
+                    .resource_mut::<Events<GlitchCaptured>>()
+                    .drain(),
+            );
+        }
+        assert!(app.world().get::<GlitchCapture>(camera).is_none());
+        assert!(app.world().get::<GlitchTimeOverride>(camera).is_none());
+        assert_eq!(captured.len(), 1);
+        assert_eq!(captured[0].frames, 0);
+    }
+}
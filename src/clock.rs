@@ -0,0 +1,64 @@
+//! Choose which clock drives a camera's effect.
+use bevy::prelude::*;
+
+/// The clocks a camera's effect can follow.
+#[derive(Reflect, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[reflect(Default)]
+pub enum GlitchTimeSource {
+    /// [`Time<Virtual>`], which slows down, speeds up and pauses with the
+    /// game, like the effect does without a [`GlitchClock`].
+    #[default]
+    Virtual,
+    /// [`Time<Real>`], which keeps ticking at wall clock speed, e.g. for an
+    /// overlay that shouldn't slow down during a slow motion kill cam.
+    Real,
+}
+
+/// Drive a camera's effect from a clock of its own instead of the time in
+/// bevy's shader globals.
+///
+/// The clock adds up the time passing on its [`GlitchTimeSource`], so
+/// switching the source at runtime carries the effect on from where it was
+/// rather than jumping to the other clock's time.
+#[derive(Component, Reflect, Debug, Clone, Copy, Default)]
+#[reflect(Component, Default)]
+pub struct GlitchClock {
+    /// The clock the time passes on.
+    pub source: GlitchTimeSource,
+    elapsed: f32,
+}
+
+impl GlitchClock {
+    /// A clock following `source`.
+    pub fn new(source: GlitchTimeSource) -> Self {
+        Self {
+            source,
+            elapsed: 0.0,
+        }
+    }
+
+    /// The time the effect currently uses, in seconds. Like the time in the
+    /// shader globals it wraps around after an hour, to stay precise.
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+}
+
+pub(crate) fn advance_glitch_clocks(
+    virtual_time: Res<Time<Virtual>>,
+    real_time: Res<Time<Real>>,
+    mut clocks: Query<&mut GlitchClock>,
+) {
+    let wrap_period = virtual_time.wrap_period().as_secs_f32();
+    for mut clock in &mut clocks {
+        // Start in step with the globals' time, which follows virtual time.
+        if clock.is_added() {
+            clock.elapsed = virtual_time.elapsed_secs_wrapped();
+        }
+        let delta = match clock.source {
+            GlitchTimeSource::Virtual => virtual_time.delta_secs(),
+            GlitchTimeSource::Real => real_time.delta_secs(),
+        };
+        clock.elapsed = (clock.elapsed + delta) % wrap_period;
+    }
+}
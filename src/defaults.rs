@@ -0,0 +1,82 @@
+//! Give every camera the effect without inserting it by hand.
+use bevy::prelude::*;
+
+use crate::{GlitchMaskSource, VideoGlitchLayers, VideoGlitchSettings};
+
+/// Keeps [`VideoGlitchPlugin::default_settings`] off a camera.
+///
+/// Remove it to have the defaults applied again. Removing a camera's
+/// [`VideoGlitchSettings`] only turns the effect off until the defaults are
+/// inserted again, the next frame; insert this instead.
+///
+/// [`VideoGlitchPlugin::default_settings`]: crate::VideoGlitchPlugin::default_settings
+#[derive(Component, Reflect, Debug, Default, Clone, Copy)]
+#[reflect(Component, Default)]
+pub struct NoVideoGlitch;
+
+/// The settings of [`VideoGlitchPlugin::default_settings`], when set.
+///
+/// [`VideoGlitchPlugin::default_settings`]: crate::VideoGlitchPlugin::default_settings
+#[derive(Resource)]
+pub(crate) struct DefaultGlitchSettings(pub(crate) VideoGlitchSettings);
+
+/// Insert the default settings on every camera without settings of its own,
+/// except mask cameras.
+pub(crate) fn apply_default_glitch_settings(
+    mut commands: Commands,
+    defaults: Res<DefaultGlitchSettings>,
+    cameras: Query<
+        Entity,
+        (
+            With<Camera>,
+            Without<VideoGlitchSettings>,
+            Without<VideoGlitchLayers>,
+            Without<NoVideoGlitch>,
+            // Glitching a mask would tear the area it masks.
+            Without<GlitchMaskSource>,
+        ),
+    >,
+) {
+    for entity in &cameras {
+        // Settings inserted the same frame, before these commands apply, win,
+        // and cameras despawned meanwhile are skipped.
+        commands.entity(entity).try_insert_if_new(defaults.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.insert_resource(DefaultGlitchSettings(VideoGlitchSettings::default()))
+            .add_systems(Update, apply_default_glitch_settings);
+        app
+    }
+
+    #[test]
+    fn cameras_get_the_defaults() {
+        let mut app = app();
+        let camera = app.world_mut().spawn(Camera::default()).id();
+        let opted_out = app
+            .world_mut()
+            .spawn((Camera::default(), NoVideoGlitch))
+            .id();
+        app.update();
+        assert!(app.world().get::<VideoGlitchSettings>(camera).is_some());
+        assert!(app.world().get::<VideoGlitchSettings>(opted_out).is_none());
+    }
+
+    #[test]
+    fn mask_cameras_are_left_alone() {
+        let mut app = app();
+        let camera = app.world_mut().spawn(Camera::default()).id();
+        let mask = app
+            .world_mut()
+            .spawn((Camera::default(), GlitchMaskSource { camera }))
+            .id();
+        app.update();
+        assert!(app.world().get::<VideoGlitchSettings>(mask).is_none());
+    }
+}
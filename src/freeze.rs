@@ -0,0 +1,138 @@
+//! Freeze a camera's last frame and keep glitching it, e.g. behind a pause
+//! menu.
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use bevy::{
+    ecs::world::Command,
+    prelude::*,
+    render::{
+        camera::CameraMainTextureUsages,
+        extract_component::ExtractComponent,
+        render_resource::{
+            CommandEncoder, Extent3d, Texture, TextureDescriptor, TextureDimension, TextureFormat,
+            TextureUsages, TextureView, TextureViewDescriptor,
+        },
+        renderer::RenderDevice,
+        view::ViewTarget,
+    },
+    utils::HashMap,
+};
+
+/// Present on a camera whose frame is frozen.
+///
+/// Use [`GlitchFreeze::capture`] and [`GlitchFreeze::release`] rather than
+/// inserting it by hand: capturing needs the camera's main texture to be
+/// copyable.
+///
+/// While frozen, the effect glitches the captured frame every frame instead
+/// of the live scene. If the camera's target is resized the frame is captured
+/// again at the new size.
+#[derive(Component, Reflect, Debug, Default, Clone, Copy, ExtractComponent)]
+#[reflect(Component, Default)]
+pub struct GlitchFreeze;
+
+impl GlitchFreeze {
+    /// Freeze the current frame of `camera`.
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_video_glitch::GlitchFreeze;
+    /// fn pause(mut commands: Commands, camera: Query<Entity, With<Camera>>) {
+    ///     commands.queue(GlitchFreeze::capture(camera.single()));
+    /// }
+    /// ```
+    pub fn capture(camera: Entity) -> impl Command {
+        move |world: &mut World| {
+            let Ok(mut entity) = world.get_entity_mut(camera) else {
+                return;
+            };
+            // The frame is copied out of the camera's main texture.
+            let usages = entity
+                .get::<CameraMainTextureUsages>()
+                .map_or(CameraMainTextureUsages::default().0, |usages| usages.0);
+            entity.insert((
+                GlitchFreeze,
+                CameraMainTextureUsages(usages | TextureUsages::COPY_SRC),
+            ));
+        }
+    }
+
+    /// Go back to glitching the live scene of `camera`.
+    pub fn release(camera: Entity) -> impl Command {
+        move |world: &mut World| {
+            if let Ok(mut entity) = world.get_entity_mut(camera) {
+                entity.remove::<GlitchFreeze>();
+            }
+        }
+    }
+}
+
+pub(crate) struct FrozenFrame {
+    texture: Texture,
+    pub(crate) view: TextureView,
+    size: Extent3d,
+    format: TextureFormat,
+    // Cleared whenever the texture is (re)allocated; the node captures the
+    // frame once and sets it.
+    captured: AtomicBool,
+}
+
+impl FrozenFrame {
+    /// Copy the view's current main texture into the frozen frame unless that
+    /// already happened.
+    pub(crate) fn capture_once(&self, encoder: &mut CommandEncoder, view_target: &ViewTarget) {
+        if self.captured.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        encoder.copy_texture_to_texture(
+            view_target.main_texture().as_image_copy(),
+            self.texture.as_image_copy(),
+            self.size,
+        );
+    }
+}
+
+/// The frozen frames of each view, in the render world.
+#[derive(Resource, Default, Deref)]
+pub(crate) struct FrozenFrames(HashMap<Entity, FrozenFrame>);
+
+pub(crate) fn prepare_frozen_frames(
+    mut frozen_frames: ResMut<FrozenFrames>,
+    render_device: Res<RenderDevice>,
+    views: Query<(Entity, &ViewTarget), With<GlitchFreeze>>,
+) {
+    frozen_frames.0.retain(|entity, _| views.contains(*entity));
+
+    for (entity, view_target) in &views {
+        let size = view_target.main_texture().size();
+        let format = view_target.main_texture_format();
+        if frozen_frames
+            .get(&entity)
+            .is_some_and(|frame| frame.size == size && frame.format == format)
+        {
+            continue;
+        }
+
+        let texture = render_device.create_texture(&TextureDescriptor {
+            label: Some("video_glitch_frozen_frame"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&TextureViewDescriptor::default());
+        frozen_frames.0.insert(
+            entity,
+            FrozenFrame {
+                texture,
+                view,
+                size,
+                format,
+                captured: AtomicBool::new(false),
+            },
+        );
+    }
+}
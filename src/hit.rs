@@ -0,0 +1,119 @@
+//! Glitch the screen from the side a hit comes from.
+use bevy::prelude::*;
+
+/// The radius of the glitch a [`GlitchHit`] brings in from the edge of the
+/// screen, as a fraction of the viewport height.
+const HIT_RADIUS: f32 = 0.9;
+
+/// A short burst of glitch on a camera, coming in from one side of the
+/// screen, e.g. when the player is shot from the left.
+///
+/// While it lasts, its strength is added to the camera's intensity around the
+/// edge of the screen the hit comes from, fading towards the opposite edge,
+/// and torn rows are shifted away from that edge. Rows only tear
+/// horizontally, so hits from straight above or below only move where the
+/// glitch is. The strength decays to 0 over `duration` seconds of virtual
+/// time, after which the component removes itself. Inserting another hit
+/// replaces the current one.
+///
+/// The hit takes one of the camera's [`MAX_GLITCH_HOTSPOTS`] hotspot slots
+/// while it is among the strongest.
+///
+/// [`MAX_GLITCH_HOTSPOTS`]: crate::MAX_GLITCH_HOTSPOTS
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component, Default)]
+pub struct GlitchHit {
+    /// The direction on screen the hit comes from, with x to the right and y
+    /// up. It doesn't need to be normalized.
+    pub direction: Vec2,
+    /// The intensity added at the edge when the hit lands.
+    pub strength: f32,
+    /// How long the hit lasts, in seconds. By default it has a value of 0.4.
+    pub duration: f32,
+    elapsed: f32,
+}
+
+impl Default for GlitchHit {
+    fn default() -> Self {
+        Self::from_screen_direction(Vec2::NEG_Y, 1.0)
+    }
+}
+
+impl GlitchHit {
+    /// A hit coming from `direction` on screen, with x to the right and y up.
+    pub fn from_screen_direction(direction: Vec2, strength: f32) -> Self {
+        Self {
+            direction,
+            strength,
+            duration: 0.4,
+            elapsed: 0.0,
+        }
+    }
+
+    /// A hit coming from `direction` in the world, pointing from the camera
+    /// towards the source of the hit, as seen by a camera at
+    /// `camera_transform`.
+    ///
+    /// Hits from straight ahead or behind come from the bottom of the screen.
+    pub fn from_world_direction(
+        camera_transform: &GlobalTransform,
+        direction: Vec3,
+        strength: f32,
+    ) -> Self {
+        let local = camera_transform.rotation().inverse() * direction;
+        let on_screen = local.xy().try_normalize().unwrap_or(Vec2::NEG_Y);
+        Self::from_screen_direction(on_screen, strength)
+    }
+
+    /// Set how long the hit lasts, in seconds.
+    pub fn with_duration(mut self, duration: f32) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// The intensity the hit currently adds at the edge.
+    pub fn current_strength(&self) -> f32 {
+        let remaining = 1.0 - self.elapsed / self.duration.max(f32::EPSILON);
+        // Fall off quickly after the impact, then linger.
+        self.strength * remaining.max(0.0).powi(2)
+    }
+
+    /// Where on screen the glitch comes in from, in viewport UV: the point of
+    /// the edge in `direction` from the center.
+    pub(crate) fn edge(&self) -> Vec2 {
+        let direction = self.direction.try_normalize().unwrap_or(Vec2::NEG_Y);
+        let direction = Vec2::new(direction.x, -direction.y);
+        Vec2::splat(0.5) + direction / (2.0 * direction.abs().max_element())
+    }
+
+    /// The hit's glitch as a hotspot, in the form the settings uniform takes
+    /// them, and its falloff.
+    pub(crate) fn hotspot(&self) -> (Vec4, f32) {
+        (
+            self.edge()
+                .extend(HIT_RADIUS)
+                .extend(self.current_strength()),
+            1.0,
+        )
+    }
+
+    /// Whether rows are shifted to the left, away from a hit from the right,
+    /// instead of to the right.
+    pub(crate) fn tears_left(&self) -> bool {
+        self.direction.x > 0.0
+    }
+}
+
+/// Decay every hit, and remove the ones that are over.
+pub(crate) fn decay_glitch_hits(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut hits: Query<(Entity, &mut GlitchHit)>,
+) {
+    for (entity, mut hit) in &mut hits {
+        hit.elapsed += time.delta_secs();
+        if hit.elapsed >= hit.duration {
+            commands.entity(entity).remove::<GlitchHit>();
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! Raise the effect's intensity on screen around world entities.
+use bevy::prelude::*;
+
+use crate::{
+    bake::GlitchBakeCamera, settings::sub_view_uv, GlitchHit, VideoGlitchLayers,
+    VideoGlitchSettings,
+};
+
+/// The maximum number of [`GlitchHotspot`]s affecting a camera at once.
+///
+/// Each hotspot takes a slot of the settings uniform, which is kept small
+/// enough to be passed as push constants.
+pub const MAX_GLITCH_HOTSPOTS: usize = 3;
+
+/// The size of a [`GlitchHotspot`].
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Default)]
+pub enum GlitchHotspotRadius {
+    /// A radius in world units around the entity, which shrinks on screen as
+    /// it moves away from the camera.
+    World(f32),
+    /// A radius as a fraction of the viewport's height, whatever the
+    /// entity's distance.
+    Screen(f32),
+}
+
+impl Default for GlitchHotspotRadius {
+    fn default() -> Self {
+        Self::World(1.0)
+    }
+}
+
+/// Carries a bubble of glitch around an entity on screen, e.g. a corrupted
+/// NPC.
+///
+/// Every camera with the effect adds `strength` to its intensity in a circle
+/// around the entity's position, on top of the camera's own intensity, which
+/// can be 0 to glitch nothing but the hotspots. Hotspots behind a camera or
+/// entirely outside of its viewport are left out, and of the rest only the
+/// [`MAX_GLITCH_HOTSPOTS`] strongest are used.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component, Default)]
+pub struct GlitchHotspot {
+    /// The size of the circle.
+    pub radius: GlitchHotspotRadius,
+    /// The intensity added in the center of the circle.
+    pub strength: f32,
+    /// The fraction of the radius over which the strength fades out towards
+    /// the edge of the circle, from a hard edge at 0 to a fade from the
+    /// center at 1.
+    pub falloff: f32,
+}
+
+impl Default for GlitchHotspot {
+    fn default() -> Self {
+        Self {
+            radius: GlitchHotspotRadius::default(),
+            strength: 1.0,
+            falloff: 0.5,
+        }
+    }
+}
+
+/// The hotspots a camera sees, in the form the settings uniform takes them.
+///
+/// Public only because the settings' extraction queries it; the module is
+/// private.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+pub struct GlitchHotspotsInView {
+    // Each as (full image UV, radius as a fraction of the full image height,
+    // strength), which are the viewport's without a `SubCameraView`. Unused
+    // slots have no strength.
+    pub(crate) hotspots: [Vec4; MAX_GLITCH_HOTSPOTS],
+    pub(crate) falloff: Vec3,
+}
+
+// Keep the smallest falloff away from a zero width fade.
+const MIN_FALLOFF: f32 = 0.001;
+
+/// Project every hotspot into the viewports of the cameras with the effect,
+/// along with the camera's own [`GlitchHit`].
+pub(crate) fn project_glitch_hotspots(
+    mut commands: Commands,
+    hotspots: Query<(&GlitchHotspot, &GlobalTransform)>,
+    mut cameras: Query<
+        (
+            Entity,
+            &Camera,
+            &GlobalTransform,
+            Option<&GlitchHit>,
+            Option<&mut GlitchHotspotsInView>,
+        ),
+        (
+            Or<(With<VideoGlitchSettings>, With<VideoGlitchLayers>)>,
+            Without<GlitchBakeCamera>,
+        ),
+    >,
+) {
+    for (entity, camera, camera_transform, hit, in_view) in &mut cameras {
+        if hotspots.is_empty() && hit.is_none() && in_view.is_none() {
+            continue;
+        }
+        let Some(viewport) = camera.logical_viewport_size() else {
+            continue;
+        };
+        let aspect = viewport.x / viewport.y;
+        // With a sub view, NDC spans the camera's tile of the full image.
+        let sub_view = sub_view_uv(camera);
+        let full_aspect = aspect * sub_view.height() / sub_view.width();
+
+        let mut visible: Vec<(Vec4, f32)> = hotspots
+            .iter()
+            .filter(|(hotspot, _)| hotspot.strength > 0.0)
+            .filter_map(|(hotspot, transform)| {
+                let position = transform.translation();
+                let center = camera.world_to_ndc(camera_transform, position)?;
+                // Bevy's depth is reversed, so whatever is in front of the
+                // camera lies between 0 and 1.
+                if !(center.z > 0.0 && center.z <= 1.0) {
+                    return None;
+                }
+                let radius = match hotspot.radius {
+                    GlitchHotspotRadius::Screen(radius) => radius,
+                    GlitchHotspotRadius::World(radius) => {
+                        let edge = camera.world_to_ndc(
+                            camera_transform,
+                            position + camera_transform.up() * radius,
+                        )?;
+                        // NDC spans 2 units over the viewport.
+                        ((edge.xy() - center.xy()) * Vec2::new(aspect, 1.0) / 2.0).length()
+                            * sub_view.height()
+                    }
+                };
+                let uv = sub_view.min
+                    + Vec2::new(center.x + 1.0, 1.0 - center.y) / 2.0 * sub_view.size();
+                let reach = Vec2::new(radius / full_aspect, radius);
+                let overlaps = (uv + reach).cmpgt(sub_view.min).all()
+                    && (uv - reach).cmplt(sub_view.max).all();
+                overlaps.then(|| {
+                    (
+                        uv.extend(radius).extend(hotspot.strength),
+                        hotspot.falloff.clamp(MIN_FALLOFF, 1.0),
+                    )
+                })
+            })
+            .chain(hit.map(GlitchHit::hotspot))
+            .collect();
+        visible.sort_by(|(a, _), (b, _)| b.w.total_cmp(&a.w));
+
+        let mut projected = GlitchHotspotsInView::default();
+        for (i, (hotspot, falloff)) in visible.into_iter().take(MAX_GLITCH_HOTSPOTS).enumerate() {
+            projected.hotspots[i] = hotspot;
+            projected.falloff[i] = falloff;
+        }
+        match in_view {
+            Some(mut in_view) => {
+                in_view.set_if_neq(projected);
+            }
+            None => {
+                commands.entity(entity).insert(projected);
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! Several independent glitch layers on one camera.
+use bevy::prelude::*;
+
+use crate::VideoGlitchSettings;
+
+/// Extra glitch layers stacked on top of a camera's [`VideoGlitchSettings`].
+///
+/// Each layer can be driven by its own system without clobbering the others,
+/// e.g. a subtle static base glitch plus a "damage" layer that bursts on top.
+/// The camera's own `VideoGlitchSettings`, if any, count as a layer with
+/// weight 1. A camera with only `VideoGlitchLayers` gets the effect too.
+///
+/// Layers are combined into the single uniform the shader reads with
+/// [`VideoGlitchSettings::combine`].
+#[derive(Component, Reflect, Debug, Clone, Default)]
+#[reflect(Component, Default)]
+pub struct VideoGlitchLayers(pub Vec<GlitchLayer>);
+
+/// One layer of [`VideoGlitchLayers`].
+#[derive(Reflect, Debug, Clone, Copy)]
+#[reflect(Default)]
+pub struct GlitchLayer {
+    /// The settings of this layer.
+    pub settings: VideoGlitchSettings,
+    /// How strongly this layer contributes. By default it has a value of 1.
+    pub weight: f32,
+}
+
+impl Default for GlitchLayer {
+    fn default() -> Self {
+        Self {
+            settings: default(),
+            weight: 1.0,
+        }
+    }
+}
+
+impl From<VideoGlitchSettings> for GlitchLayer {
+    fn from(settings: VideoGlitchSettings) -> Self {
+        Self {
+            settings,
+            weight: 1.0,
+        }
+    }
+}
+
+impl VideoGlitchSettings {
+    /// Combine weighted layers into one set of settings:
+    ///
+    /// - `intensity` is the weighted sum of the layer intensities, clamped to
+    ///   [0, 1].
+    /// - Every other field is the average of the layers weighted by
+    ///   `weight * intensity`, so a layer only shapes the look while it is
+    ///   active. If no layer is active the first layer's fields are used.
+    ///
+    /// Returns `None` if there are no layers.
+    pub fn combine<'a>(
+        layers: impl IntoIterator<Item = (&'a VideoGlitchSettings, f32)>,
+    ) -> Option<VideoGlitchSettings> {
+        let mut layers = layers.into_iter().peekable();
+        let first = *layers.peek()?.0;
+
+        // The weighted intensities are both the combined intensity and the
+        // weights of the average.
+        let mut total = 0.0;
+        let mut sum = Self::scaled(&first, 0.0);
+        for (settings, weight) in layers {
+            let w = settings.intensity * weight;
+            total += w;
+            sum.accumulate(&Self::scaled(settings, w));
+        }
+
+        let mut combined = if total > f32::EPSILON {
+            Self::scaled(&sum, total.recip())
+        } else {
+            first
+        };
+        combined.intensity = total.clamp(0.0, 1.0);
+        Some(combined)
+    }
+
+    /// Blend from these settings towards `other` by `t`, from 0 to 1. The
+    /// fields [`Self::combine`] doesn't blend switch over halfway.
+    pub(crate) fn lerp(&self, other: &Self, t: f32) -> Self {
+        let mut blended = Self::scaled(if t < 0.5 { self } else { other }, 0.0);
+        blended.accumulate(&Self::scaled(self, 1.0 - t));
+        blended.accumulate(&Self::scaled(other, t));
+        blended.intensity = self.intensity.lerp(other.intensity, t);
+        blended
+    }
+
+    // Scales every field except `intensity` by `w`.
+    fn scaled(settings: &Self, w: f32) -> Self {
+        let mut scaled = *settings;
+        scaled.displacement.amount *= w;
+        scaled.displacement.speed *= w;
+        scaled.color.aberration *= w;
+        scaled.color.aberration_offset *= w;
+        scaled.color.aberration_cycle_speed *= w;
+        scaled.color.aberration_scope *= w;
+        scaled.color.aberration_angle *= w;
+        scaled.overlay.interference *= w;
+        scaled.overlay.scanlines *= w;
+        scaled
+    }
+
+    // Adds every field except `intensity`. Flags such as `pixel_snap`, the
+    // `noise_mode`, the `response` curve, the `depth` fade and `effect_fps`
+    // aren't blended and keep the first layer's value.
+    fn accumulate(&mut self, other: &Self) {
+        self.displacement.amount += other.displacement.amount;
+        self.displacement.speed += other.displacement.speed;
+        self.color.aberration += other.color.aberration;
+        self.color.aberration_offset += other.color.aberration_offset;
+        self.color.aberration_cycle_speed += other.color.aberration_cycle_speed;
+        self.color.aberration_scope += other.color.aberration_scope;
+        self.color.aberration_angle += other.color.aberration_angle;
+        self.overlay.interference += other.overlay.interference;
+        self.overlay.scanlines += other.overlay.scanlines;
+    }
+}
@@ -1,64 +1,714 @@
 #![doc(html_root_url = "https://docs.rs/bevy_video_glitch/0.2.0")]
 #![doc = include_str!("../README.md")]
+// Systems take many, deeply nested parameters by nature.
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+#[cfg(not(feature = "hot-reload"))]
+use bevy::asset::load_internal_asset;
+#[cfg(feature = "bevy_ui")]
+use bevy::ui::graph::NodeUi;
 use bevy::{
-    asset::load_internal_asset,
+    app::SubApp,
     core_pipeline::{
+        blit::BlitPipeline,
         core_2d::graph::{Core2d, Node2d},
         core_3d::graph::{Core3d, Node3d},
         fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+        prepass::ViewPrepassTextures,
     },
     ecs::query::QueryItem,
+    image::{BevyDefault, ImageAddressMode, ImageFilterMode, ImageSamplerDescriptor},
     prelude::*,
     render::{
-        extract_component::{
-            ComponentUniforms, ExtractComponent, ExtractComponentPlugin, UniformComponentPlugin,
-        },
+        camera::{CameraUpdateSystem, ExtractedCamera, NormalizedRenderTarget},
+        diagnostic::{RecordDiagnostics, RenderDiagnosticsPlugin},
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
         globals::{GlobalsBuffer, GlobalsUniform},
+        render_asset::RenderAssets,
         render_graph::{
-            NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner,
+            InternedRenderLabel, NodeRunError, RenderGraph, RenderGraphApp, RenderGraphContext,
+            RenderLabel, RenderSubGraph, ViewNode, ViewNodeRunner,
         },
         render_resource::{
-            binding_types::{sampler, texture_2d, uniform_buffer},
-            BindGroupEntries, BindGroupLayout, BindGroupLayoutEntries, CachedRenderPipelineId,
-            ColorTargetState, ColorWrites, FragmentState, MultisampleState, Operations,
-            PipelineCache, PrimitiveState, RenderPassColorAttachment, RenderPassDescriptor,
-            RenderPipelineDescriptor, Sampler, SamplerBindingType, SamplerDescriptor, ShaderStages,
-            ShaderType, TextureFormat, TextureSampleType,
+            binding_types::{sampler, texture_2d, texture_depth_2d, uniform_buffer},
+            encase, BindGroupEntries, BindGroupLayout, BindGroupLayoutEntries, BlendState,
+            CachedPipelineState, CachedRenderPipelineId, ColorTargetState, ColorWrites,
+            FragmentState, LoadOp, MultisampleState, Operations, PipelineCache, PrimitiveState,
+            PushConstantRange, RenderPassColorAttachment, RenderPassDescriptor,
+            RenderPipelineDescriptor, Sampler, SamplerBindingType, ShaderDefVal, ShaderStages,
+            ShaderType, SpecializedRenderPipeline, SpecializedRenderPipelines, TextureDescriptor,
+            TextureDimension, TextureFormat, TextureSampleType, TextureUsages, TextureView,
+            TextureViewDescriptor,
+        },
+        renderer::{RenderAdapter, RenderContext, RenderDevice},
+        settings::WgpuFeatures,
+        texture::{FallbackImage, GpuImage, TextureCache},
+        view::{
+            screenshot::{Capturing, Screenshot},
+            ViewTarget,
         },
-        renderer::{RenderContext, RenderDevice},
-        texture::BevyDefault,
-        view::ViewTarget,
-        RenderApp,
+        Extract, Render, RenderApp, RenderSet,
     },
+    utils::{HashMap, HashSet},
+    window::PrimaryWindow,
 };
+// Bevy doesn't re-export the format features of adapters.
+use wgpu_types::TextureFormatFeatureFlags;
+
+mod aberration;
+#[cfg(feature = "audio")]
+mod audio;
+mod bake;
+mod bind_group;
+#[cfg(feature = "capture")]
+mod capture;
+mod clock;
+mod defaults;
+mod freeze;
+mod hit;
+mod hotspot;
+mod layers;
+mod mask;
+mod modulation;
+mod motion;
+mod overlay;
+#[cfg(feature = "presets")]
+mod preset;
+#[cfg(feature = "rand")]
+mod random;
+mod scale;
+mod settings;
+mod signal;
+mod stats;
+mod status;
+mod uniform;
+mod volume;
+mod wire;
+
+pub use aberration::{ColorAberration, ColorVisionDeficiency};
+#[cfg(feature = "audio")]
+pub use audio::{GlitchAudioDriver, GlitchAudioLevel};
+pub use bake::{bake_video_glitch, GlitchBake, VideoGlitchBaked};
+use bake::{
+    extract_glitch_bakes, read_back_glitch_bakes, run_glitch_bakes, BakeReadbacks, BakeReceiver,
+    BakeSender,
+};
+pub use bind_group::VideoGlitchBindGroups;
+use bind_group::{prepare_bind_groups, BindGroupKey};
+#[cfg(feature = "capture")]
+pub use capture::{GlitchCapture, GlitchCaptured};
+use clock::advance_glitch_clocks;
+pub use clock::{GlitchClock, GlitchTimeSource};
+pub use defaults::NoVideoGlitch;
+use defaults::{apply_default_glitch_settings, DefaultGlitchSettings};
+pub use freeze::GlitchFreeze;
+use freeze::{prepare_frozen_frames, FrozenFrames};
+use hit::decay_glitch_hits;
+pub use hit::GlitchHit;
+use hotspot::project_glitch_hotspots;
+pub use hotspot::{GlitchHotspot, GlitchHotspotRadius, MAX_GLITCH_HOTSPOTS};
+pub use layers::{GlitchLayer, VideoGlitchLayers};
+use mask::{sync_glitch_mask_twins, update_glitch_mask_sources};
+pub use mask::{GlitchMaskOccluder, GlitchMaskSource, GlitchMasked};
+use modulation::{configure_glitch_modulators, GlitchModulatorSystems};
+pub use modulation::{GlitchModulation, GlitchModulator, GlitchModulatorAppExt};
+pub use motion::{GlitchFromMotion, GlitchSpeedMapping};
+use overlay::resize_glitch_overlays;
+pub use overlay::{despawn_glitch_overlay, spawn_glitch_overlay, GlitchOverlay};
+#[cfg(all(feature = "presets", not(target_arch = "wasm32")))]
+pub use preset::SaveGlitchPreset;
+#[cfg(feature = "presets")]
+pub use preset::{GlitchPreset, GlitchPresetError, GlitchPresetLoader};
+#[cfg(feature = "rand")]
+pub use random::GlitchFlavor;
+pub use scale::VideoGlitchScale;
+use scale::{prepare_scaled_glitch_targets, ScaledGlitchTarget};
+use settings::MAX_ANTIALIAS;
+pub use settings::{
+    ColorSettings, DepthSettings, DisplacementSettings, GlitchColorSpace, GlitchComparisonSplit,
+    GlitchExclusionRects, GlitchMask, GlitchNoiseMode, GlitchResponse, GlitchTimeOverride,
+    GlitchWipe, OverlaySettings, VideoGlitchSettings, VideoGlitchUniform, MAX_EXCLUSION_RECTS,
+};
+pub use signal::GlitchSignal;
+pub use stats::GlitchEffective;
+pub use status::VideoGlitchPipelineError;
+pub use status::VideoGlitchPipelineStatus;
+use status::{update_pipeline_status, SharedPipelineStatus};
+pub use uniform::VideoGlitchUniforms;
+use uniform::{
+    extract_video_glitch_uniforms, prepare_video_glitch_uniforms, VideoGlitchUniformOffset,
+};
+use volume::find_glitch_volumes;
+pub use volume::{GlitchVolume, GlitchVolumeShape};
+pub use wire::GLITCH_WIRE_SIZE;
+
+// The render layers the crate's own cameras draw on, kept together so they
+// don't collide. Overlays take one layer each from the first up, so theirs
+// stays the highest.
+const BAKE_RENDER_LAYER: usize = 30;
+const MASK_RENDER_LAYER: usize = 31;
+const OVERLAY_FIRST_RENDER_LAYER: usize = 32;
+const _: () = assert!(
+    BAKE_RENDER_LAYER != MASK_RENDER_LAYER
+        && BAKE_RENDER_LAYER < OVERLAY_FIRST_RENDER_LAYER
+        && MASK_RENDER_LAYER < OVERLAY_FIRST_RENDER_LAYER,
+    "the crate's render layers collide"
+);
 
 // $ cargo install uuid-tools && uuid -o simple
 pub const VIDEO_GLITCH_SHADER_HANDLE: Handle<Shader> =
     Handle::weak_from_u128(0x7b1d58197dc34e26b0c69a3c8091a014u128);
 
-pub struct VideoGlitchPlugin;
+/// Adds the video glitch post processing effect.
+///
+/// By default the effect is registered in both the 2D and 3D render graphs.
+/// Use [`VideoGlitchPlugin::only_2d`] or [`VideoGlitchPlugin::only_3d`] to
+/// leave the other graph untouched.
+///
+/// Adding the plugin more than once, e.g. through two plugin groups, is
+/// harmless: only the first one added is used, and the others log a warning.
+#[derive(Debug, Clone)]
+pub struct VideoGlitchPlugin {
+    /// Register the effect in the [`Core2d`] render graph.
+    pub core_2d: bool,
+    /// Register the effect in the [`Core3d`] render graph.
+    pub core_3d: bool,
+    /// Where the effect runs in the render graphs.
+    pub position: GlitchGraphPosition,
+    /// How the glitched result is blended into the output. `None`, the
+    /// default, overwrites it.
+    pub blend: Option<BlendState>,
+    /// What the output holds before the effect is drawn when [`Self::blend`]
+    /// is set. Ignored without a blend.
+    ///
+    /// In the standard post processing ping-pong the output is the texture the
+    /// view wrote to previously, so [`LoadOp::Load`] is mostly useful when the
+    /// effect runs as an overlay pass in a custom graph.
+    pub load: LoadOp<LinearRgba>,
+    /// Pass the settings as push constants on devices that support them,
+    /// instead of through a uniform buffer. Both paths render the same
+    /// image; turn this off to compare them. On by default.
+    ///
+    /// Devices without [`WgpuFeatures::PUSH_CONSTANTS`], e.g. WebGPU and
+    /// WebGL2, or whose push constants are too small for the settings fall
+    /// back to the uniform buffer on their own, so leaving this on is safe
+    /// everywhere.
+    pub push_constants: bool,
+    /// Publish the time the effect takes on the CPU and GPU to the
+    /// [`DiagnosticsStore`] as `render/video_glitch/elapsed_cpu` and
+    /// `render/video_glitch/elapsed_gpu`, summed over all views.
+    ///
+    /// This adds bevy's [`RenderDiagnosticsPlugin`], which allocates timestamp
+    /// queries on devices that support them. Elsewhere only the CPU time is
+    /// published. Off by default.
+    ///
+    /// [`DiagnosticsStore`]: bevy::diagnostic::DiagnosticsStore
+    pub diagnostics: bool,
+    /// The quality of cameras without a [`GlitchQuality`] of their own.
+    /// Medium by default.
+    pub quality: GlitchQuality,
+    /// The most texture taps any camera may spend per pixel, capping
+    /// [`GlitchQuality::High`] and [`GlitchQuality::Custom`]. From 1 to 256,
+    /// 64 by default.
+    ///
+    /// Each tap count compiles a pipeline of its own, so raising it only
+    /// costs the cameras asking for more.
+    pub max_taps: u32,
+    /// The most subpixel samples any camera may take per tear, capping
+    /// [`DisplacementSettings::antialias`]. From 0 to 4, where 0 and 1 turn
+    /// antialiasing off everywhere, e.g. on mobile. 4 by default.
+    pub max_antialias: u32,
+    /// Run the effect as two passes, [`VideoGlitchDisplaceLabel`] tearing the
+    /// rows and then [`VideoGlitchColorLabel`] fringing the colors and drawing
+    /// the overlay, instead of the single [`VideoGlitchLabel`] pass.
+    ///
+    /// Passes of your own can then run on the torn image before its colors
+    /// are fringed, with render graph edges between the two labels. Both
+    /// passes look the same as the single one, at the cost of an extra
+    /// fullscreen pass. They always run at full resolution, ignoring
+    /// [`VideoGlitchScale`], and are timed as `video_glitch_displace` and
+    /// `video_glitch_color` by [`Self::diagnostics`]. Off by default.
+    pub split_passes: bool,
+    /// What cameras show while their pipeline compiles, the first time they
+    /// use the effect. To hold a scene back until the effect is drawing
+    /// instead, wait for [`VideoGlitchPipelineStatus::is_ready`].
+    pub fallback: GlitchFallback,
+    /// Compile each pipeline for both LDR and HDR cameras, so switching a
+    /// camera's `hdr` later doesn't leave it without the effect while the
+    /// other variant compiles. Doubles the pipelines compiled. Off by
+    /// default.
+    pub warm_ldr_and_hdr: bool,
+    /// How the effect samples the screen, e.g. with [`ImageFilterMode::Linear`]
+    /// to smooth the shifted colors. Cameras can adjust it with a
+    /// [`GlitchSampling`]. Nearest filtering and clamped edges by default.
+    ///
+    /// A sampler with nothing but nearest filters is bound as non-filtering.
+    pub sampler: ImageSamplerDescriptor,
+    /// Settings inserted on every camera, 2D or 3D, that has neither
+    /// [`VideoGlitchSettings`] nor [`VideoGlitchLayers`] of its own, including
+    /// cameras spawned later or by other plugins, but not the cameras of
+    /// [`GlitchMaskSource`]s. Add [`NoVideoGlitch`] to a camera to keep them
+    /// off it. `None` by default.
+    pub default_settings: Option<VideoGlitchSettings>,
+}
+
+impl Default for VideoGlitchPlugin {
+    fn default() -> Self {
+        Self {
+            core_2d: true,
+            core_3d: true,
+            position: GlitchGraphPosition::default(),
+            blend: None,
+            load: LoadOp::Load,
+            push_constants: true,
+            diagnostics: false,
+            quality: GlitchQuality::default(),
+            max_taps: 64,
+            max_antialias: MAX_ANTIALIAS,
+            split_passes: false,
+            fallback: GlitchFallback::default(),
+            warm_ldr_and_hdr: false,
+            sampler: ImageSamplerDescriptor::default(),
+            default_settings: None,
+        }
+    }
+}
+
+impl VideoGlitchPlugin {
+    /// Only register the effect for 2D cameras.
+    pub fn only_2d() -> Self {
+        Self {
+            core_3d: false,
+            ..default()
+        }
+    }
+
+    /// Only register the effect for 3D cameras.
+    pub fn only_3d() -> Self {
+        Self {
+            core_2d: false,
+            ..default()
+        }
+    }
+
+    /// Run the effect at `position` in the render graphs.
+    pub fn with_position(mut self, position: GlitchGraphPosition) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// Composite the glitched result into the output with `blend`, starting
+    /// from `load`.
+    pub fn with_blend(mut self, blend: BlendState, load: LoadOp<LinearRgba>) -> Self {
+        self.blend = Some(blend);
+        self.load = load;
+        self
+    }
+
+    /// Allow or forbid passing the settings as push constants, see
+    /// [`Self::push_constants`].
+    pub fn with_push_constants(mut self, push_constants: bool) -> Self {
+        self.push_constants = push_constants;
+        self
+    }
+
+    /// Publish the time the effect takes, see [`Self::diagnostics`].
+    pub fn with_diagnostics(mut self) -> Self {
+        self.diagnostics = true;
+        self
+    }
+
+    /// Render cameras without a [`GlitchQuality`] of their own at `quality`.
+    pub fn with_quality(mut self, quality: GlitchQuality) -> Self {
+        self.quality = quality;
+        self
+    }
+
+    /// Let cameras spend at most `taps` texture taps per pixel, see
+    /// [`Self::max_taps`].
+    pub fn with_max_taps(mut self, taps: u32) -> Self {
+        self.max_taps = taps;
+        self
+    }
+
+    /// Let cameras take at most `samples` subpixel samples per tear, see
+    /// [`Self::max_antialias`].
+    pub fn with_max_antialias(mut self, samples: u32) -> Self {
+        self.max_antialias = samples;
+        self
+    }
+
+    /// Run the effect as two passes, see [`Self::split_passes`].
+    pub fn with_split_passes(mut self) -> Self {
+        self.split_passes = true;
+        self
+    }
+
+    /// Show `fallback` while a camera's pipeline compiles.
+    pub fn with_fallback(mut self, fallback: GlitchFallback) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    /// Compile pipelines for LDR and HDR cameras alike, see
+    /// [`Self::warm_ldr_and_hdr`].
+    pub fn with_warm_ldr_and_hdr(mut self) -> Self {
+        self.warm_ldr_and_hdr = true;
+        self
+    }
+
+    /// Sample the screen with `sampler`, see [`Self::sampler`].
+    pub fn with_sampler(mut self, sampler: ImageSamplerDescriptor) -> Self {
+        self.sampler = sampler;
+        self
+    }
+
+    /// Glitch every camera with `settings`, see [`Self::default_settings`].
+    pub fn with_default_settings(mut self, settings: VideoGlitchSettings) -> Self {
+        self.default_settings = Some(settings);
+        self
+    }
+
+    // The passes the effect runs as, in order.
+    fn passes(&self) -> &'static [GlitchPass] {
+        if self.split_passes {
+            &[GlitchPass::Displace, GlitchPass::Color]
+        } else {
+            &[GlitchPass::Fused]
+        }
+    }
+
+    fn first_label(&self) -> InternedRenderLabel {
+        self.passes()[0].label()
+    }
+
+    #[cfg(feature = "bevy_ui")]
+    fn last_label(&self) -> InternedRenderLabel {
+        self.passes()[self.passes().len() - 1].label()
+    }
+}
+
+/// What a camera shows while the pipeline drawing its effect compiles.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum GlitchFallback {
+    /// The scene without the effect.
+    #[default]
+    PassThrough,
+    /// A solid color over the camera's whole output, e.g. black to open a
+    /// cinematic on. Cameras whose pipeline failed to compile still pass the
+    /// scene through.
+    Solid(Color),
+}
+
+/// Where the effect runs relative to bevy's other post processing nodes.
+///
+/// The placement changes the look. Before bloom and tonemapping the effect
+/// sees linear HDR values on `hdr` cameras, so glitched bright fringes bloom
+/// and get tonemapped with the rest of the scene. After tonemapping it works
+/// on the final LDR colors, which keeps the aberration colors exactly as
+/// configured.
+#[derive(Debug, Clone, Default)]
+pub enum GlitchGraphPosition {
+    /// Between the end of the main pass and temporal antialiasing, on
+    /// purpose: TAA accumulates the tears into its history, which smears them
+    /// over the following frames. Requires the [`TemporalAntiAliasPlugin`] to
+    /// be added before this plugin. 2D has no TAA, there this is the same as
+    /// [`Self::BeforeBloom`].
+    ///
+    /// [`TemporalAntiAliasPlugin`]: bevy::core_pipeline::experimental::taa::TemporalAntiAliasPlugin
+    BeforeTaa,
+    /// Between the end of the main pass and bloom, after TAA if it is enabled
+    /// so the tears stay out of its history.
+    BeforeBloom,
+    /// Between bloom and tonemapping, so the aberration works on pre-tonemap
+    /// values. This is where earlier versions placed the effect in 2D.
+    BeforeTonemapping,
+    /// Between tonemapping and the end of main pass post processing. This is
+    /// the same spot in 2D and 3D, so matching settings look the same on both
+    /// kinds of cameras.
+    #[default]
+    AfterTonemapping,
+    /// Between the end of main pass post processing and upscaling, i.e. after
+    /// FXAA and any other post processing so antialiasing doesn't soften the
+    /// tears. With the `bevy_ui` feature this is still before the UI.
+    BeforeUpscaling,
+    /// Between the UI and upscaling, so the UI is glitched along with the
+    /// scene. Every other position leaves the UI untouched.
+    #[cfg(feature = "bevy_ui")]
+    AfterUi,
+    /// Between two arbitrary nodes. Both labels must exist in every graph the
+    /// effect is registered in.
+    Custom {
+        /// The node that runs before the effect.
+        after: InternedRenderLabel,
+        /// The node that runs after the effect.
+        before: InternedRenderLabel,
+    },
+}
+
+impl GlitchGraphPosition {
+    fn edges_3d(&self) -> (InternedRenderLabel, InternedRenderLabel) {
+        match self {
+            Self::BeforeTaa => (Node3d::EndMainPass.intern(), Node3d::Taa.intern()),
+            Self::BeforeBloom => (Node3d::EndMainPass.intern(), Node3d::Bloom.intern()),
+            Self::BeforeTonemapping => (Node3d::Bloom.intern(), Node3d::Tonemapping.intern()),
+            Self::AfterTonemapping => (
+                Node3d::Tonemapping.intern(),
+                Node3d::EndMainPassPostProcessing.intern(),
+            ),
+            Self::BeforeUpscaling => (
+                Node3d::EndMainPassPostProcessing.intern(),
+                Node3d::Upscaling.intern(),
+            ),
+            #[cfg(feature = "bevy_ui")]
+            Self::AfterUi => (NodeUi::UiPass.intern(), Node3d::Upscaling.intern()),
+            Self::Custom { after, before } => (*after, *before),
+        }
+    }
+
+    fn edges_2d(&self) -> (InternedRenderLabel, InternedRenderLabel) {
+        match self {
+            Self::BeforeTaa | Self::BeforeBloom => {
+                (Node2d::EndMainPass.intern(), Node2d::Bloom.intern())
+            }
+            Self::BeforeTonemapping => (Node2d::Bloom.intern(), Node2d::Tonemapping.intern()),
+            Self::AfterTonemapping => (
+                Node2d::Tonemapping.intern(),
+                Node2d::EndMainPassPostProcessing.intern(),
+            ),
+            Self::BeforeUpscaling => (
+                Node2d::EndMainPassPostProcessing.intern(),
+                Node2d::Upscaling.intern(),
+            ),
+            #[cfg(feature = "bevy_ui")]
+            Self::AfterUi => (NodeUi::UiPass.intern(), Node2d::Upscaling.intern()),
+            Self::Custom { after, before } => (*after, *before),
+        }
+    }
+}
+
+/// Add the nodes of `passes` to `graph` between `after` and `before`,
+/// panicking with a readable message if either of them isn't part of the
+/// graph. Graphs that don't exist are skipped.
+fn add_video_glitch_nodes(
+    render_app: &mut SubApp,
+    graph: impl RenderSubGraph,
+    (after, before): (InternedRenderLabel, InternedRenderLabel),
+    passes: &[GlitchPass],
+) {
+    let graph = graph.intern();
+    let render_graph = render_app.world().resource::<RenderGraph>();
+    // Without e.g. the core pipeline there are no cameras to apply the
+    // effect to.
+    let Some(sub_graph) = render_graph.get_sub_graph(graph) else {
+        debug!("VideoGlitchPlugin: render graph {graph:?} does not exist, skipping it");
+        return;
+    };
+    for label in [after, before] {
+        if sub_graph.get_node_state(label).is_err() {
+            panic!(
+                "VideoGlitchPlugin: cannot place the effect next to {label:?} because it is not \
+                 part of the {graph:?} render graph; was its plugin added?"
+            );
+        }
+    }
+
+    render_app
+        .world_mut()
+        .resource_scope(|world, mut render_graph: Mut<RenderGraph>| {
+            let sub_graph = render_graph.sub_graph_mut(graph);
+            for pass in passes {
+                // The [`ViewNodeRunner`] is a special [`Node`] that will automatically run the node for each view
+                // matching the [`ViewQuery`]
+                sub_graph.add_node(
+                    pass.label(),
+                    ViewNodeRunner::new(VideoGlitchNode { pass: *pass }, world),
+                );
+            }
+        });
+    // This will automatically create all required node edges to enforce the given ordering.
+    let mut labels = vec![after];
+    labels.extend(passes.iter().map(GlitchPass::label));
+    labels.push(before);
+    for edge in labels.windows(2) {
+        render_app.add_render_graph_edge(graph, edge[0], edge[1]);
+    }
+}
+
+// Marks an app the plugin has been built for, so further instances leave it
+// alone.
+#[derive(Resource)]
+struct VideoGlitchPluginAdded {
+    finished: bool,
+}
 
 impl Plugin for VideoGlitchPlugin {
     fn build(&self, app: &mut App) {
+        // Bailing out before anything is registered also keeps the internal
+        // shader from being loaded twice.
+        if app.world().contains_resource::<VideoGlitchPluginAdded>() {
+            warn!(
+                "VideoGlitchPlugin was added more than once, only the configuration of the \
+                 first one is used"
+            );
+            return;
+        }
+        app.insert_resource(VideoGlitchPluginAdded { finished: false });
+
+        if !(1..=MAX_GLITCH_TAPS).contains(&self.max_taps) {
+            panic!(
+                "VideoGlitchPlugin: max_taps must be from 1 to {MAX_GLITCH_TAPS}, not {}",
+                self.max_taps
+            );
+        }
+        if self.max_antialias > MAX_ANTIALIAS {
+            panic!(
+                "VideoGlitchPlugin: max_antialias must be from 0 to {MAX_ANTIALIAS}, not {}",
+                self.max_antialias
+            );
+        }
+
+        // With the `hot-reload` feature the shader is loaded through the
+        // [`AssetServer`] in [`VideoGlitchPipeline::from_world`] instead, so
+        // edits to `assets/shaders/video-glitch.wgsl` are picked up live.
+        #[cfg(not(feature = "hot-reload"))]
         load_internal_asset!(
             app,
             VIDEO_GLITCH_SHADER_HANDLE,
             "../assets/shaders/video-glitch.wgsl",
             Shader::from_wgsl
         );
-        app.register_type::<VideoGlitchSettings>().add_plugins((
-            // The settings will be a component that lives in the main world but will
-            // be extracted to the render world every frame.
-            // This makes it possible to control the effect from the main world.
-            // This plugin will take care of extracting it automatically.
-            // It's important to derive [`ExtractComponent`] on [`VideoGlitchSettings`]
-            // for this plugin to work correctly.
-            ExtractComponentPlugin::<VideoGlitchSettings>::default(),
-            // The settings will also be the data used in the shader.
-            // This plugin will prepare the component for the GPU by creating a uniform buffer
-            // and writing the data to that buffer every frame.
-            UniformComponentPlugin::<VideoGlitchSettings>::default(),
-        ));
+        app.register_type::<VideoGlitchSettings>()
+            .register_type::<GlitchResponse>()
+            .register_type::<DisplacementSettings>()
+            .register_type::<ColorSettings>()
+            .register_type::<OverlaySettings>()
+            .register_type::<GlitchColorSpace>()
+            .register_type::<GlitchNoiseMode>()
+            .register_type::<DepthSettings>()
+            .register_type::<VideoGlitchLayers>()
+            .register_type::<GlitchExclusionRects>()
+            .register_type::<GlitchTimeOverride>()
+            .register_type::<GlitchComparisonSplit>()
+            .register_type::<GlitchWipe>()
+            .register_type::<GlitchClock>()
+            .register_type::<GlitchTimeSource>()
+            .add_systems(PostUpdate, advance_glitch_clocks)
+            .register_type::<GlitchMask>()
+            .register_type::<GlitchMaskSource>()
+            .register_type::<GlitchMasked>()
+            .register_type::<GlitchMaskOccluder>()
+            .add_systems(
+                PostUpdate,
+                (
+                    update_glitch_mask_sources.before(CameraUpdateSystem),
+                    sync_glitch_mask_twins,
+                ),
+            )
+            .add_systems(PostUpdate, settings::warn_truncated_exclusion_rects)
+            .register_type::<GlitchHotspot>()
+            .register_type::<GlitchHotspotRadius>()
+            .register_type::<GlitchHit>()
+            .configure_sets(
+                PostUpdate,
+                GlitchModulationSet
+                    .after(TransformSystem::TransformPropagate)
+                    .after(CameraUpdateSystem),
+            )
+            .add_systems(
+                PostUpdate,
+                (decay_glitch_hits, project_glitch_hotspots)
+                    .chain()
+                    .in_set(GlitchModulationSet),
+            )
+            .register_type::<GlitchFromMotion>()
+            .register_type::<GlitchSpeedMapping>()
+            .add_systems(
+                PostUpdate,
+                motion::drive_glitch_from_motion.in_set(GlitchModulationSet),
+            )
+            .add_glitch_modulator::<GlitchFromMotion>()
+            .register_type::<GlitchVolume>()
+            .register_type::<GlitchVolumeShape>()
+            .add_systems(PostUpdate, find_glitch_volumes.in_set(GlitchModulationSet))
+            .register_type::<GlitchModulation>()
+            .register_type::<GlitchEffective>()
+            .add_systems(
+                PostUpdate,
+                stats::update_glitch_effective.after(GlitchModulatorSystems::Combine),
+            )
+            .register_type::<GlitchSignal>()
+            .add_systems(
+                PostUpdate,
+                signal::update_glitch_signals.after(GlitchModulatorSystems::Combine),
+            )
+            .register_type::<VideoGlitchMode>()
+            .register_type::<VideoGlitchHideInScreenshots>()
+            .register_type::<GlitchFreeze>()
+            .register_type::<GlitchDebugDisplacement>()
+            .register_type::<VideoGlitchScale>()
+            .register_type::<GlitchQuality>()
+            .register_type::<GlitchSampling>()
+            .add_plugins((
+                ExtractComponentPlugin::<VideoGlitchMode>::default(),
+                ExtractComponentPlugin::<VideoGlitchHideInScreenshots>::default(),
+                ExtractComponentPlugin::<GlitchFreeze>::default(),
+                ExtractComponentPlugin::<GlitchDebugDisplacement>::default(),
+                ExtractComponentPlugin::<GlitchMask>::default(),
+                ExtractComponentPlugin::<VideoGlitchScale>::default(),
+                ExtractComponentPlugin::<GlitchQuality>::default(),
+                ExtractComponentPlugin::<GlitchSampling>::default(),
+            ));
+
+        configure_glitch_modulators(app);
+
+        app.register_type::<NoVideoGlitch>();
+        if let Some(settings) = self.default_settings {
+            app.insert_resource(DefaultGlitchSettings(settings))
+                .add_systems(
+                    PostUpdate,
+                    apply_default_glitch_settings.before(GlitchModulationSet),
+                );
+        }
+
+        if self.diagnostics && !app.is_plugin_added::<RenderDiagnosticsPlugin>() {
+            app.add_plugins(RenderDiagnosticsPlugin);
+        }
+
+        #[cfg(feature = "audio")]
+        app.register_type::<GlitchAudioLevel>()
+            .register_type::<GlitchAudioDriver>()
+            .add_systems(
+                PostUpdate,
+                audio::drive_glitch_from_audio.in_set(GlitchModulationSet),
+            )
+            .add_glitch_modulator::<GlitchAudioDriver>();
+
+        #[cfg(feature = "presets")]
+        app.init_asset::<GlitchPreset>()
+            .init_asset_loader::<GlitchPresetLoader>();
+        #[cfg(all(feature = "presets", not(target_arch = "wasm32")))]
+        app.add_event::<SaveGlitchPreset>()
+            .add_systems(Update, preset::save_glitch_presets);
+
+        #[cfg(feature = "capture")]
+        app.add_event::<GlitchCaptured>()
+            .add_systems(PostUpdate, capture::run_glitch_captures)
+            .add_observer(capture::restore_glitch_capture_time);
+
+        let pipeline_status = SharedPipelineStatus::default();
+        app.init_resource::<VideoGlitchPipelineStatus>()
+            .add_event::<VideoGlitchPipelineError>()
+            .insert_resource(pipeline_status.clone())
+            .add_systems(First, update_pipeline_status);
+
+        // Baked images are read back in the render world and handed over
+        // through a channel.
+        let (bake_sender, bake_receiver) = std::sync::mpsc::channel();
+        app.add_event::<VideoGlitchBaked>()
+            .insert_resource(BakeReceiver(bake_receiver.into()))
+            .add_systems(Update, run_glitch_bakes);
+
+        app.add_systems(PostUpdate, resize_glitch_overlays.after(CameraUpdateSystem));
 
         // We need to get the render app from the main app
         let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
@@ -66,82 +716,270 @@ impl Plugin for VideoGlitchPlugin {
         };
 
         render_app
-            // Bevy's renderer uses a render graph which is a collection of nodes in a directed acyclic graph.
-            // It currently runs on each view/camera and executes each node in the specified order.
-            // It will make sure that any node that needs a dependency from another node
-            // only runs when that dependency is done.
-            //
-            // Each node can execute arbitrary work, but it generally runs at least one render pass.
-            // A node only has access to the render world, so if you need data from the main world
-            // you need to extract it manually or with the plugin like above.
-            // Add a [`Node`] to the [`RenderGraph`]
-            // The Node needs to impl FromWorld
-            //
-            // The [`ViewNodeRunner`] is a special [`Node`] that will automatically run the node for each view
-            // matching the [`ViewQuery`]
-            .add_render_graph_node::<ViewNodeRunner<VideoGlitchNode>>(
-                // Specify the name of the graph, in this case we want the graph for 3d
-                Core3d,
-                // It also needs the name of the node
-                VideoGlitchLabel,
-            )
-            .add_render_graph_edges(
-                Core3d,
-                // Specify the node ordering.
-                // This will automatically create all required node edges to enforce the given ordering.
-                (
-                    Node3d::Tonemapping,
-                    VideoGlitchLabel,
-                    Node3d::EndMainPassPostProcessing,
-                ),
-            )
-            .add_render_graph_node::<ViewNodeRunner<VideoGlitchNode>>(Core2d, VideoGlitchLabel)
-            .add_render_graph_edges(
-                Core2d,
-                (Node2d::EndMainPass, VideoGlitchLabel, Node2d::Tonemapping),
-            );
+            .insert_resource(VideoGlitchConfig {
+                blend: self.blend,
+                load: self.blend.map(|_| self.load),
+                push_constants: self.push_constants,
+                quality: self.quality,
+                max_taps: self.max_taps,
+                max_antialias: self.max_antialias,
+                passes: self.passes(),
+                fallback: self.fallback,
+                warm_ldr_and_hdr: self.warm_ldr_and_hdr,
+                sampler: self.sampler.clone(),
+            })
+            .init_resource::<SpecializedRenderPipelines<VideoGlitchPipeline>>()
+            .init_resource::<ReadyVideoGlitchPipelines>()
+            .init_resource::<FrozenFrames>()
+            .init_resource::<VideoGlitchBindGroups>()
+            .init_resource::<VideoGlitchUniforms>()
+            .init_resource::<BakeReadbacks>()
+            .init_resource::<PendingScreenshots>()
+            .insert_resource(BakeSender(bake_sender))
+            .insert_resource(pipeline_status);
+
+        // Bevy's renderer uses a render graph which is a collection of nodes in a directed acyclic graph.
+        // It currently runs on each view/camera and executes each node in the specified order.
+        // It will make sure that any node that needs a dependency from another node
+        // only runs when that dependency is done.
+        //
+        // Each node can execute arbitrary work, but it generally runs at least one render pass.
+        // A node only has access to the render world, so if you need data from the main world
+        // you need to extract it manually or with the plugin like above.
+        if self.core_3d {
+            add_video_glitch_nodes(render_app, Core3d, self.position.edges_3d(), self.passes());
+        }
+        if self.core_2d {
+            add_video_glitch_nodes(render_app, Core2d, self.position.edges_2d(), self.passes());
+        }
+    }
+
+    fn is_unique(&self) -> bool {
+        // Further instances are skipped in `build` rather than rejected.
+        false
     }
 
     fn finish(&self, app: &mut App) {
+        // Only the first instance finishes, like it is the only one built.
+        let mut added = app.world_mut().resource_mut::<VideoGlitchPluginAdded>();
+        if std::mem::replace(&mut added.finished, true) {
+            return;
+        }
+
         // We need to get the render app from the main app
         let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
             return;
         };
 
+        // Minimal render setups may leave out parts of bevy the effect relies
+        // on. Leave the effect off in them rather than failing every frame.
+        let world = render_app.world();
+        let missing = [
+            ("RenderDevice", world.contains_resource::<RenderDevice>()),
+            ("GlobalsBuffer", world.contains_resource::<GlobalsBuffer>()),
+            ("FallbackImage", world.contains_resource::<FallbackImage>()),
+            ("TextureCache", world.contains_resource::<TextureCache>()),
+            ("BlitPipeline", world.contains_resource::<BlitPipeline>()),
+        ]
+        .into_iter()
+        .find_map(|(name, exists)| (!exists).then_some(name));
+        if let Some(missing) = missing {
+            debug!(
+                "VideoGlitchPlugin: {missing} is missing from the render world, the effect is \
+                 disabled"
+            );
+            return;
+        }
+
         render_app
             // Initialize the pipeline
-            .init_resource::<VideoGlitchPipeline>();
+            .init_resource::<VideoGlitchPipeline>()
+            // The settings live in the main world and are extracted to the
+            // render world every frame as the flat [`VideoGlitchUniform`] the
+            // shader reads. This makes it possible to control the effect from
+            // the main world. Only the uniforms that changed are written to
+            // the GPU.
+            .add_systems(
+                ExtractSchedule,
+                (
+                    extract_video_glitch_uniforms,
+                    extract_glitch_bakes,
+                    extract_pending_screenshots,
+                ),
+            )
+            .add_systems(
+                Render,
+                (
+                    prepare_video_glitch_uniforms
+                        .in_set(RenderSet::PrepareResources)
+                        .run_if(uses_uniform_buffer),
+                    // Pick the pipeline variant for each view before the graph runs.
+                    prepare_video_glitch_pipelines.in_set(RenderSet::Prepare),
+                    prepare_frozen_frames.in_set(RenderSet::PrepareResources),
+                    prepare_scaled_glitch_targets.in_set(RenderSet::PrepareResources),
+                    prepare_bind_groups.in_set(RenderSet::PrepareBindGroups),
+                    // After the frame drawing the bakes has been submitted.
+                    read_back_glitch_bakes.in_set(RenderSet::Cleanup),
+                ),
+            );
+
+        // TAA also sits between the end of the main pass and bloom, so keep
+        // the effect after it. Every plugin has been built by now, whichever
+        // order TAA and this plugin were added in.
+        if self.core_3d && matches!(self.position, GlitchGraphPosition::BeforeBloom) {
+            let has_taa = render_app
+                .world()
+                .resource::<RenderGraph>()
+                .get_sub_graph(Core3d)
+                .is_some_and(|graph| graph.get_node_state(Node3d::Taa).is_ok());
+            if has_taa {
+                render_app.add_render_graph_edge(Core3d, Node3d::Taa, self.first_label());
+            }
+        }
+
+        // The UI sits between the same nodes as `BeforeUpscaling`; keep the
+        // effect before it so the UI stays untouched.
+        #[cfg(feature = "bevy_ui")]
+        if matches!(self.position, GlitchGraphPosition::BeforeUpscaling) {
+            let graphs = [
+                self.core_3d.then_some(Core3d.intern()),
+                self.core_2d.then_some(Core2d.intern()),
+            ];
+            for graph in graphs.into_iter().flatten() {
+                let has_ui = render_app
+                    .world()
+                    .resource::<RenderGraph>()
+                    .get_sub_graph(graph)
+                    .is_some_and(|graph| graph.get_node_state(NodeUi::UiPass).is_ok());
+                if has_ui {
+                    render_app.add_render_graph_edge(graph, self.last_label(), NodeUi::UiPass);
+                }
+            }
+        }
     }
 }
+/// The systems in [`PostUpdate`] driving the effect of cameras from the world,
+/// e.g. [`GlitchFromMotion`], [`GlitchHit`]s and [`GlitchHotspot`]s. They run
+/// after transforms are propagated, and [`GlitchEffective`] is written after
+/// them. Order systems of your own that drive the effect before it.
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+pub struct GlitchModulationSet;
+
+/// The render graph node of the effect when it runs as a single pass.
 #[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
 pub struct VideoGlitchLabel;
 
+/// The render graph node tearing the rows when the effect runs as two
+/// passes, see [`VideoGlitchPlugin::split_passes`].
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+pub struct VideoGlitchDisplaceLabel;
+
+/// The render graph node fringing the colors and drawing the overlay when the
+/// effect runs as two passes, see [`VideoGlitchPlugin::split_passes`].
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+pub struct VideoGlitchColorLabel;
+
+// The parts of the effect a node draws.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+enum GlitchPass {
+    // The whole effect at once.
+    Fused,
+    Displace,
+    Color,
+}
+
+impl GlitchPass {
+    fn label(&self) -> InternedRenderLabel {
+        match self {
+            Self::Fused => VideoGlitchLabel.intern(),
+            Self::Displace => VideoGlitchDisplaceLabel.intern(),
+            Self::Color => VideoGlitchColorLabel.intern(),
+        }
+    }
+
+    // Only the last pass blends into the output.
+    fn is_last(&self) -> bool {
+        *self != Self::Displace
+    }
+}
+
 // The post process node used for the render graph
-#[derive(Default)]
-struct VideoGlitchNode;
+struct VideoGlitchNode {
+    pass: GlitchPass,
+}
 
 // The ViewNode trait is required by the ViewNodeRunner
 impl ViewNode for VideoGlitchNode {
     // The node needs a query to gather data from the ECS in order to do its rendering,
     // but it's not a normal system so we need to define it manually.
     //
-    // This query will only run on the view entity
-    type ViewQuery = &'static ViewTarget;
+    // This query will only run on the view entity. Views without the
+    // extracted settings, i.e. cameras without [`VideoGlitchSettings`], don't
+    // match and skip the node entirely.
+    type ViewQuery = (
+        &'static ViewTarget,
+        &'static VideoGlitchUniform,
+        // The offset of this view's settings in the uniform buffer, absent
+        // when they are passed as push constants
+        Option<&'static VideoGlitchUniformOffset>,
+        &'static VideoGlitchPipelineIds,
+        &'static ExtractedCamera,
+        Has<VideoGlitchHideInScreenshots>,
+        Option<&'static GlitchMask>,
+        Option<&'static ScaledGlitchTarget>,
+        Option<&'static ViewPrepassTextures>,
+        Option<&'static GlitchSampling>,
+        Has<GlitchDebugDisplacement>,
+    );
 
     // Runs the node logic
     // This is where you encode draw commands.
     //
-    // This will run on every view on which the graph is running.
-    // If you don't want your effect to run on every camera,
-    // you'll need to make sure you have a marker component as part of [`ViewQuery`]
-    // to identify which camera(s) should run the effect.
+    // This will run on every view on which the graph is running and that
+    // matches [`ViewQuery`], so only on cameras with the effect.
     fn run(
         &self,
-        _graph: &mut RenderGraphContext,
+        graph: &mut RenderGraphContext,
         render_context: &mut RenderContext,
-        view_target: QueryItem<Self::ViewQuery>,
+        (
+            view_target,
+            settings,
+            settings_offset,
+            pipeline_ids,
+            camera,
+            hide_in_screenshots,
+            mask,
+            scaled,
+            prepass_textures,
+            sampling,
+            debug_displacement,
+        ): QueryItem<Self::ViewQuery>,
         world: &World,
     ) -> Result<(), NodeRunError> {
+        // Leave the frame untouched if a screenshot of this camera's target
+        // is being captured. The screenshot request is extracted for exactly
+        // the captured frame, so frames around it keep the effect.
+        if hide_in_screenshots && is_capturing_screenshot(world, camera) {
+            return Ok(());
+        }
+
+        // A frozen view captures its current frame once and from then on
+        // glitches that instead of the live scene.
+        // The second of the split passes works on the output of the first.
+        let frozen_frame = match self.pass {
+            GlitchPass::Color => None,
+            _ => world.resource::<FrozenFrames>().get(&graph.view_entity()),
+        };
+
+        // Skip the pass when it would copy the frame unchanged. This must
+        // happen before `post_process_write()`, which flips the main texture
+        // and so commits us to drawing into the destination. A frozen frame
+        // still has to be shown in place of the live scene, and the debug
+        // view even when nothing moves.
+        if settings.is_noop() && frozen_frame.is_none() && !debug_displacement {
+            return Ok(());
+        }
+
         // Get the pipeline resource that contains the global data we need
         // to create the render pipeline
         let video_glitch_pipeline = world.resource::<VideoGlitchPipeline>();
@@ -151,23 +989,100 @@ impl ViewNode for VideoGlitchNode {
         // which is expensive due to shader compilation.
         let pipeline_cache = world.resource::<PipelineCache>();
 
-        // Get the pipeline from the cache
-        let Some(pipeline) = pipeline_cache.get_render_pipeline(video_glitch_pipeline.pipeline_id)
-        else {
+        // Get the pipeline specialized for this view from the cache
+        let Some(pipeline_id) = pipeline_ids.get(self.pass) else {
             return Ok(());
         };
-
-        // Get the settings uniform binding
-        let settings_uniforms = world.resource::<ComponentUniforms<VideoGlitchSettings>>();
-        let Some(settings_binding) = settings_uniforms.uniforms().binding() else {
+        let Some(pipeline) = pipeline_cache.get_render_pipeline(pipeline_id) else {
+            let still_compiling = !matches!(
+                pipeline_cache.get_render_pipeline_state(pipeline_id),
+                CachedPipelineState::Err(_)
+            );
+            if let (GlitchFallback::Solid(color), true) = (
+                world.resource::<VideoGlitchConfig>().fallback,
+                still_compiling,
+            ) {
+                let post_process = view_target.post_process_write();
+                render_context.begin_tracked_render_pass(RenderPassDescriptor {
+                    label: Some("video_glitch_fallback_pass"),
+                    color_attachments: &[Some(RenderPassColorAttachment {
+                        view: post_process.destination,
+                        resolve_target: None,
+                        ops: Operations {
+                            load: LoadOp::Clear(color.to_linear().into()),
+                            ..default()
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+            }
             return Ok(());
         };
 
+        // A view running below full resolution also needs the pipelines that
+        // stretch the result back.
+        let scaled = match scaled.filter(|_| self.pass == GlitchPass::Fused) {
+            Some(scaled) => {
+                let (Some(upscale), Some(pass_through)) = (
+                    pipeline_cache.get_render_pipeline(scaled.upscale_pipeline),
+                    pipeline_cache.get_render_pipeline(scaled.pass_through_pipeline),
+                ) else {
+                    return Ok(());
+                };
+                Some((scaled, upscale, pass_through))
+            }
+            None => None,
+        };
+
+        // Get the settings uniform binding, unless the settings are passed as
+        // push constants
+        let settings_uniform = if video_glitch_pipeline.push_constants {
+            None
+        } else {
+            let settings_uniforms = world.resource::<VideoGlitchUniforms>();
+            let (Some(buffer), Some(binding), Some(offset)) = (
+                settings_uniforms.buffer(),
+                settings_uniforms.binding(),
+                settings_offset,
+            ) else {
+                return Ok(());
+            };
+            Some((buffer, binding, offset.0))
+        };
+
         let globals_buffer = world.resource::<GlobalsBuffer>();
-        let Some(global_uniforms) = globals_buffer.buffer.binding() else {
+        let (Some(globals), Some(global_uniforms)) = (
+            globals_buffer.buffer.buffer(),
+            globals_buffer.buffer.binding(),
+        ) else {
             return Ok(());
         };
 
+        // Cameras without a mask, or whose mask is still loading, use a white
+        // placeholder that leaves the intensity unchanged.
+        let mask_view = mask
+            .and_then(|mask| world.resource::<RenderAssets<GpuImage>>().get(&mask.0))
+            .map_or(
+                &world.resource::<FallbackImage>().d2.texture_view,
+                |image| &image.texture_view,
+            );
+
+        // Likewise for views without a depth prepass.
+        let depth_view = depth_prepass_view(prepass_textures)
+            .unwrap_or(&video_glitch_pipeline.depth_placeholder);
+        let filterable = is_filterable(
+            world.resource::<RenderAdapter>(),
+            view_target.main_texture_format(),
+        );
+        let sampler =
+            video_glitch_pipeline.sampler(sampling.copied().unwrap_or_default(), filterable);
+
+        if let Some(frozen_frame) = frozen_frame {
+            frozen_frame.capture_once(render_context.command_encoder(), view_target);
+        }
+
         // This will start a new "post process write", obtaining two texture
         // views from the view target - a `source` and a `destination`.
         // `source` is the "current" main texture and you _must_ write into
@@ -177,203 +1092,1042 @@ impl ViewNode for VideoGlitchNode {
         // the current main texture information to be lost.
         let post_process = view_target.post_process_write();
 
-        // The bind_group gets created each frame.
-        //
-        // Normally, you would create a bind_group in the Queue set,
-        // but this doesn't work with the post_process_write().
-        // The reason it doesn't work is because each post_process_write will alternate the source/destination.
-        // The only way to have the correct source/destination for the bind_group
-        // is to make sure you get it during the node execution.
-        let bind_group = render_context.render_device().create_bind_group(
-            "video_glitch_bind_group",
-            &video_glitch_pipeline.layout,
-            // It's important for this to match the BindGroupLayout defined in the VideoGlitchPipeline
-            &BindGroupEntries::sequential((
-                // Make sure to use the source view
-                post_process.source,
-                // Use the sampler created for the pipeline
-                &video_glitch_pipeline.sampler,
-                // Set the settings binding
-                settings_binding.clone(),
-                global_uniforms,
-            )),
+        // The bind group can't be created in the Queue set: each
+        // post_process_write() alternates the source/destination, and the
+        // only way to have the correct source for the bind group is to get it
+        // during the node execution. Instead each view keeps one bind group
+        // per source and reuses them across frames.
+        let source = frozen_frame.map_or(post_process.source, |frame| &frame.view);
+        let key = BindGroupKey {
+            source: source.id(),
+            settings: settings_uniform.as_ref().map(|(buffer, _, _)| buffer.id()),
+            globals: globals.id(),
+            mask: mask_view.id(),
+            depth: depth_view.id(),
+            sampler: sampler.id(),
+        };
+        let settings_offset = settings_uniform.as_ref().map(|(_, _, offset)| *offset);
+        let bind_group = world.resource::<VideoGlitchBindGroups>().get_or_create(
+            graph.view_entity(),
+            key,
+            || match settings_uniform {
+                Some((_, settings_binding, _)) => render_context.render_device().create_bind_group(
+                    "video_glitch_bind_group",
+                    video_glitch_pipeline.layout(filterable),
+                    // It's important for this to match the BindGroupLayout defined in the VideoGlitchPipeline
+                    &BindGroupEntries::sequential((
+                        // Make sure to use the source view
+                        source,
+                        // Use the sampler created for the pipeline
+                        sampler,
+                        // Set the settings binding
+                        settings_binding,
+                        global_uniforms,
+                        mask_view,
+                        depth_view,
+                    )),
+                ),
+                None => render_context.render_device().create_bind_group(
+                    "video_glitch_bind_group",
+                    video_glitch_pipeline.layout(filterable),
+                    &BindGroupEntries::with_indices((
+                        (0, source),
+                        (1, sampler),
+                        (3, global_uniforms),
+                        (4, mask_view),
+                        (5, depth_view),
+                    )),
+                ),
+            },
         );
 
+        let ops = match world.resource::<VideoGlitchConfig>().load {
+            _ if !self.pass.is_last() => Operations::default(),
+            Some(LoadOp::Clear(color)) => Operations {
+                load: LoadOp::Clear(color.into()),
+                ..default()
+            },
+            Some(LoadOp::Load) => Operations {
+                load: LoadOp::Load,
+                ..default()
+            },
+            None => Operations::default(),
+        };
+
+        // Only records anything with the `RenderDiagnosticsPlugin`.
+        let diagnostics = render_context.diagnostic_recorder();
+
         // Begin the render pass
         let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
             label: Some("video_glitch_pass"),
             color_attachments: &[Some(RenderPassColorAttachment {
                 // We need to specify the post process destination view here
-                // to make sure we write to the appropriate texture.
-                view: post_process.destination,
+                // to make sure we write to the appropriate texture. A scaled
+                // view draws into its smaller texture first, which is
+                // overwritten completely.
+                view: scaled.map_or(post_process.destination, |(scaled, _, _)| {
+                    &scaled.texture.default_view
+                }),
                 resolve_target: None,
-                ops: Operations::default(),
+                ops: match scaled {
+                    Some(_) => Operations::default(),
+                    None => ops,
+                },
             })],
             depth_stencil_attachment: None,
             timestamp_writes: None,
             occlusion_query_set: None,
         });
 
+        let pass_span = diagnostics.pass_span(
+            &mut render_pass,
+            match self.pass {
+                GlitchPass::Fused => "video_glitch",
+                GlitchPass::Displace => "video_glitch_displace",
+                GlitchPass::Color => "video_glitch_color",
+            },
+        );
+
         // This is mostly just wgpu boilerplate for drawing a fullscreen triangle,
         // using the pipeline/bind_group created above
         render_pass.set_render_pipeline(pipeline);
-        render_pass.set_bind_group(0, &bind_group, &[]);
+        match settings_offset {
+            Some(offset) => render_pass.set_bind_group(0, &bind_group, &[offset]),
+            None => {
+                render_pass.set_bind_group(0, &bind_group, &[]);
+                let mut push_constants = encase::UniformBuffer::new(Vec::<u8>::new());
+                push_constants.write(settings).unwrap();
+                render_pass.set_push_constants(
+                    ShaderStages::FRAGMENT,
+                    0,
+                    &push_constants.into_inner(),
+                );
+            }
+        }
         render_pass.draw(0..3, 0..1);
+        pass_span.end(&mut render_pass);
+        drop(render_pass);
+
+        let Some((scaled, upscale, pass_through_pipeline)) = scaled else {
+            return Ok(());
+        };
+
+        // Other cameras sharing the main texture keep their pixels at full
+        // resolution: copy the whole source, then only upscale inside the
+        // viewport.
+        let pass_through = camera.viewport.as_ref().map(|viewport| {
+            let blit_pipeline = world.resource::<BlitPipeline>();
+            let bind_group = render_context.render_device().create_bind_group(
+                "video_glitch_pass_through_bind_group",
+                &blit_pipeline.texture_bind_group,
+                &BindGroupEntries::sequential((post_process.source, &blit_pipeline.sampler)),
+            );
+            (viewport, bind_group)
+        });
+
+        // Stretch the smaller texture back over the destination.
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("video_glitch_upscale_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                resolve_target: None,
+                ops,
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        let pass_span = diagnostics.pass_span(&mut render_pass, "video_glitch_upscale");
+        if let Some((viewport, bind_group)) = &pass_through {
+            render_pass.set_render_pipeline(pass_through_pipeline);
+            render_pass.set_bind_group(0, bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+            render_pass.set_scissor_rect(
+                viewport.physical_position.x,
+                viewport.physical_position.y,
+                viewport.physical_size.x,
+                viewport.physical_size.y,
+            );
+        }
+        render_pass.set_render_pipeline(upscale);
+        render_pass.set_bind_group(0, &scaled.upscale_bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+        pass_span.end(&mut render_pass);
 
         Ok(())
     }
 }
 
+fn is_capturing_screenshot(world: &World, camera: &ExtractedCamera) -> bool {
+    camera
+        .target
+        .as_ref()
+        .is_some_and(|target| world.resource::<PendingScreenshots>().0.contains(target))
+}
+
+// The targets of the screenshots taken this frame, in the render world.
+#[derive(Resource, Default)]
+struct PendingScreenshots(Vec<NormalizedRenderTarget>);
+
+fn extract_pending_screenshots(
+    mut pending: ResMut<PendingScreenshots>,
+    // Bevy marks the screenshots it takes this frame as `Capturing` while
+    // extracting, either before or after this runs.
+    screenshots: Extract<Query<&Screenshot, Or<(Without<Capturing>, Added<Capturing>)>>>,
+    primary_window: Extract<Query<Entity, With<PrimaryWindow>>>,
+) {
+    let primary_window = primary_window.get_single().ok();
+    pending.0 = screenshots
+        .iter()
+        .filter_map(|screenshot| screenshot.0.normalize(primary_window))
+        .collect();
+}
+
 // This contains global data used by the render pipeline. This will be created once on startup.
 #[derive(Resource)]
 struct VideoGlitchPipeline {
     layout: BindGroupLayout,
-    sampler: Sampler,
-    pipeline_id: CachedRenderPipelineId,
+    // For view targets whose format can't be filtered, e.g. 32 bit float
+    // targets on devices without `FLOAT32_FILTERABLE`.
+    unfilterable_layout: BindGroupLayout,
+    // Indexed by `GlitchSampling::index`.
+    samplers: [Sampler; 4],
+    shader: Handle<Shader>,
+    blend: Option<BlendState>,
+    // Whether the settings are passed as push constants rather than through
+    // `VideoGlitchUniforms`.
+    push_constants: bool,
+    depth_placeholder: TextureView,
+}
+
+// The parts of the plugin configuration the render world needs.
+#[derive(Resource)]
+struct VideoGlitchConfig {
+    blend: Option<BlendState>,
+    // Only set when blending.
+    load: Option<LoadOp<LinearRgba>>,
+    push_constants: bool,
+    quality: GlitchQuality,
+    max_taps: u32,
+    max_antialias: u32,
+    passes: &'static [GlitchPass],
+    fallback: GlitchFallback,
+    warm_ldr_and_hdr: bool,
+    sampler: ImageSamplerDescriptor,
+}
+
+impl VideoGlitchConfig {
+    fn is_split(&self) -> bool {
+        self.passes.len() > 1
+    }
+}
+
+impl VideoGlitchPipeline {
+    fn layout(&self, filterable: bool) -> &BindGroupLayout {
+        if filterable {
+            &self.layout
+        } else {
+            &self.unfilterable_layout
+        }
+    }
+
+    // Unfilterable targets can only be read through a non-filtering sampler,
+    // so they get the nearest version of the camera's sampling.
+    fn sampler(&self, sampling: GlitchSampling, filterable: bool) -> &Sampler {
+        let sampling = GlitchSampling {
+            nearest: sampling.nearest || !filterable,
+            ..sampling
+        };
+        &self.samplers[sampling.index()]
+    }
+}
+
+// Whether the adapter can filter textures of `format` when sampling them.
+fn is_filterable(adapter: &RenderAdapter, format: TextureFormat) -> bool {
+    adapter
+        .get_texture_format_features(format)
+        .flags
+        .contains(TextureFormatFeatureFlags::FILTERABLE)
 }
 
 impl FromWorld for VideoGlitchPipeline {
     fn from_world(world: &mut World) -> Self {
         let render_device = world.resource::<RenderDevice>();
+        let config = world.resource::<VideoGlitchConfig>();
 
-        let layout = render_device.create_bind_group_layout(
-            "video_glitch_bind_group_layout",
-            &BindGroupLayoutEntries::sequential(
-                // The layout entries will only be visible in the fragment stage
-                ShaderStages::FRAGMENT,
-                (
-                    // The screen texture
-                    texture_2d(TextureSampleType::Float { filterable: true }),
-                    // The sampler that will be used to sample the screen texture
-                    sampler(SamplerBindingType::Filtering),
-                    // The settings uniform that will control the effect
-                    uniform_buffer::<VideoGlitchSettings>(false),
-                    uniform_buffer::<GlobalsUniform>(false),
-                ),
-            ),
+        // Push constants spare us the uniform buffer, but aren't available
+        // on the web and may be too small on some devices.
+        let push_constants = config.push_constants
+            && render_device
+                .features()
+                .contains(WgpuFeatures::PUSH_CONSTANTS)
+            && render_device.limits().max_push_constant_size as u64
+                >= VideoGlitchUniform::min_size().get();
+
+        // `GlitchSampling` only ever makes the filters nearer, so every
+        // sampler fits the binding type of the plugin's.
+        let sampler_binding = if is_filtering(&config.sampler) {
+            SamplerBindingType::Filtering
+        } else {
+            SamplerBindingType::NonFiltering
+        };
+
+        let create_layout = |label, filterable, sampler_binding| {
+            if push_constants {
+                // The settings binding is left out, the others keep their
+                // indices so the shader is the same either way.
+                render_device.create_bind_group_layout(
+                    label,
+                    &BindGroupLayoutEntries::with_indices(
+                        ShaderStages::FRAGMENT,
+                        (
+                            (0, texture_2d(TextureSampleType::Float { filterable })),
+                            (1, sampler(sampler_binding)),
+                            (3, uniform_buffer::<GlobalsUniform>(false)),
+                            (4, texture_2d(TextureSampleType::Float { filterable })),
+                            (5, texture_depth_2d()),
+                        ),
+                    ),
+                )
+            } else {
+                render_device.create_bind_group_layout(
+                    label,
+                    // Sequential, so each entry's binding is its position here,
+                    // which the `@binding`s in `video-glitch.wgsl` must follow.
+                    &BindGroupLayoutEntries::sequential(
+                        // The layout entries will only be visible in the fragment stage
+                        ShaderStages::FRAGMENT,
+                        (
+                            // The screen texture
+                            texture_2d(TextureSampleType::Float { filterable }),
+                            // The sampler that will be used to sample the screen texture
+                            sampler(sampler_binding),
+                            // The settings uniform that will control the effect
+                            // It's dynamic so each view can point at its own settings.
+                            uniform_buffer::<VideoGlitchUniform>(true),
+                            uniform_buffer::<GlobalsUniform>(false),
+                            // The mask scaling the intensity per pixel
+                            texture_2d(TextureSampleType::Float { filterable }),
+                            // The depth prepass fading the intensity with distance
+                            texture_depth_2d(),
+                        ),
+                    ),
+                )
+            }
+        };
+        let layout = create_layout("video_glitch_bind_group_layout", true, sampler_binding);
+        // Views whose target can't be filtered always use the nearest
+        // samplers, see `VideoGlitchPipeline::sampler`.
+        let unfilterable_layout = create_layout(
+            "video_glitch_unfilterable_bind_group_layout",
+            false,
+            SamplerBindingType::NonFiltering,
         );
-        // // We need to define the bind group layout used for our pipeline
-        // let layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
-        //     label: Some("video_glitch_bind_group_layout"),
-        //     entries: &[
-        //         // The screen texture
-        //         BindGroupLayoutEntry {
-        //             binding: 0,
-        //             visibility: ShaderStages::FRAGMENT,
-        //             ty: BindingType::Texture {
-        //                 sample_type: TextureSampleType::Float { filterable: true },
-        //                 view_dimension: TextureViewDimension::D2,
-        //                 multisampled: false,
-        //             },
-        //             count: None,
-        //         },
-        //         // The sampler that will be used to sample the screen texture
-        //         BindGroupLayoutEntry {
-        //             binding: 1,
-        //             visibility: ShaderStages::FRAGMENT,
-        //             ty: BindingType::Sampler(SamplerBindingType::Filtering),
-        //             count: None,
-        //         },
-        //         // The settings uniform that will control the effect
-        //         BindGroupLayoutEntry {
-        //             binding: 2,
-        //             visibility: ShaderStages::FRAGMENT,
-        //             ty: BindingType::Buffer {
-        //                 ty: bevy::render::render_resource::BufferBindingType::Uniform,
-        //                 has_dynamic_offset: false,
-        //                 min_binding_size: Some(VideoGlitchSettings::min_size()),
-        //             },
-        //             count: None,
-        //         },
-        //         // Globals
-        //         BindGroupLayoutEntry {
-        //             binding: 3,
-        //             visibility: ShaderStages::FRAGMENT,
-        //             ty: BindingType::Buffer {
-        //                 ty: bevy::render::render_resource::BufferBindingType::Uniform,
-        //                 has_dynamic_offset: false,
-        //                 min_binding_size: Some(GlobalsUniform::min_size()),
-        //             },
-        //             count: None,
-        //         },
-        //     ],
-        // });
-
-        // We can create the sampler here since it won't change at runtime and doesn't depend on the view
-        let sampler = render_device.create_sampler(&SamplerDescriptor::default());
+        let blend = config.blend;
+
+        // Bound in place of the depth prepass of views without one, which
+        // never sample it.
+        let depth_placeholder = render_device
+            .create_texture(&TextureDescriptor {
+                label: Some("video_glitch_depth_placeholder"),
+                size: default(),
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Depth32Float,
+                usage: TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            })
+            .create_view(&TextureViewDescriptor::default());
+
+        // We can create the samplers here since they won't change at runtime
+        // and a camera's `GlitchSampling` only picks between them.
+        let samplers = [0, 1, 2, 3].map(|index| {
+            let sampling = GlitchSampling {
+                nearest: index & 2 != 0,
+                clamp_to_edge: index & 1 != 0,
+            };
+            render_device.create_sampler(&sampling.apply(&config.sampler).as_wgpu())
+        });
 
         // Get the shader handle
-        // let shader = world
-        //     .resource::<AssetServer>()
-        //     .load("shaders/video-glitch.wgsl");
+        //
+        // When the shader is hot reloaded the pipeline cache recompiles the
+        // pipeline. While that happens `get_render_pipeline()` returns `None`
+        // and the node simply passes the frame through.
+        #[cfg(feature = "hot-reload")]
+        let shader = world
+            .resource::<AssetServer>()
+            .load("shaders/video-glitch.wgsl");
+        #[cfg(not(feature = "hot-reload"))]
         let shader = VIDEO_GLITCH_SHADER_HANDLE.clone();
 
-        let pipeline_id = world
-            .resource_mut::<PipelineCache>()
-            // This will add the pipeline to the cache and queue it's creation
-            .queue_render_pipeline(RenderPipelineDescriptor {
-                label: Some("video_glitch_pipeline".into()),
-                layout: vec![layout.clone()],
-                // This will setup a fullscreen triangle for the vertex state
-                vertex: fullscreen_shader_vertex_state(),
-                fragment: Some(FragmentState {
-                    shader,
-                    shader_defs: vec![],
-                    // Make sure this matches the entry point of your shader.
-                    // It can be anything as long as it matches here and in the shader.
-                    entry_point: "fragment".into(),
-                    targets: vec![Some(ColorTargetState {
-                        format: TextureFormat::bevy_default(),
-                        blend: None,
-                        write_mask: ColorWrites::ALL,
-                    })],
-                }),
-                // All of the following properties are not important for this effect so just use the default values.
-                // This struct doesn't have the Default trait implemented because not all field can have a default value.
-                primitive: PrimitiveState::default(),
-                depth_stencil: None,
-                multisample: MultisampleState::default(),
-                push_constant_ranges: vec![],
-            });
-
         Self {
             layout,
-            sampler,
-            pipeline_id,
+            unfilterable_layout,
+            samplers,
+            shader,
+            blend,
+            push_constants,
+            depth_placeholder,
         }
     }
 }
 
-// This is the component that will get passed to the shader
-#[derive(Component, Reflect, Clone, Copy, ExtractComponent, ShaderType)]
-#[reflect(Component, Default)]
-pub struct VideoGlitchSettings {
-    /// Set the intensity of this glitch effect from [0, 1]. By default it has a
-    /// value of 1.
-    pub intensity: f32,
-    /// This shader uses a color aberration matrix C in the following way: The
-    /// first column `C[0] . color` selects the primary color, which is used to
-    /// mix the other two. In practice this means one will not see the primary
-    /// color in the color aberrations but will instead see traces of the
-    /// secondary colors: `C[1] . color` and `C[2] . color`.
-    ///
-    /// The default value is an identity matrix, which specifies red as the
-    /// primary color. Typically this matrix will be a doubly stochastic matrix
-    /// meaning the columns and rows each sum to 1.
-    pub color_aberration: Mat3,
-    // WebGL2 structs must be 16 byte aligned.
+// Everything that selects a distinct pipeline for a view.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+struct VideoGlitchPipelineKey {
+    mode: VideoGlitchMode,
+    // The view's main texture format, which differs for HDR cameras. Cameras
+    // rendering to an image get their own main texture too, whatever the
+    // image's format.
+    format: TextureFormat,
+    // Whether the adapter can filter `format`. If not, the pipeline reads the
+    // view target through non-filtering bindings.
+    filterable: bool,
+    // Whether the effect is drawn into a smaller texture first, in which case
+    // the blend happens when it is upscaled.
+    scaled: bool,
+    features: GlitchFeatures,
+    // The texture taps of the view's `GlitchQuality`.
+    taps: u32,
+    pass: GlitchPass,
+    // Whether the view has `GlitchDebugDisplacement`.
+    debug_displacement: bool,
+}
 
-    #[cfg(feature = "webgl2")]
-    pub webgl2_padding: Vec2,
+// The optional parts of the effect a view uses. Unused parts are compiled out
+// of the shader.
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq)]
+struct GlitchFeatures {
+    mask: bool,
+    exclusion_rects: bool,
+    hotspots: bool,
+    interference: bool,
+    scanlines: bool,
+    depth: bool,
+    perceptual: bool,
+    // The subpixel samples per tear, 0 without antialiasing.
+    antialias_taps: u32,
+    comparison: bool,
+    noise_mode: GlitchNoiseMode,
+    wipe: bool,
 }
 
-impl Default for VideoGlitchSettings {
-    fn default() -> Self {
+impl GlitchFeatures {
+    fn new(
+        settings: &VideoGlitchUniform,
+        has_mask: bool,
+        has_depth_prepass: bool,
+        max_antialias: u32,
+    ) -> Self {
         Self {
-            intensity: 1.0,
-            color_aberration: Mat3::IDENTITY,
-            #[cfg(feature = "webgl2")]
-            webgl2_padding: Vec2::ZERO,
+            mask: has_mask,
+            exclusion_rects: settings.has_exclusion_rects(),
+            hotspots: settings.has_hotspots(),
+            interference: settings.has_interference(),
+            scanlines: settings.has_scanlines(),
+            depth: settings.has_depth() && has_depth_prepass,
+            // Without an overlay there is nothing to convert.
+            perceptual: settings.is_perceptual()
+                && (settings.has_interference() || settings.has_scanlines()),
+            // A single sample is no antialiasing.
+            antialias_taps: match settings.antialias_taps().min(max_antialias) {
+                1 => 0,
+                taps => taps,
+            },
+            comparison: settings.has_comparison(),
+            noise_mode: settings.noise_mode(),
+            wipe: settings.has_wipe(),
+        }
+    }
+
+    fn shader_defs(&self) -> Vec<ShaderDefVal> {
+        [
+            (self.mask, "MASK"),
+            (self.exclusion_rects, "EXCLUSION_RECTS"),
+            (self.hotspots, "HOTSPOTS"),
+            (self.interference, "INTERFERENCE"),
+            (self.scanlines, "SCANLINES"),
+            (self.depth, "DEPTH"),
+            (self.perceptual, "PERCEPTUAL"),
+            (self.comparison, "COMPARISON"),
+            (self.wipe, "WIPE"),
+            (self.noise_mode == GlitchNoiseMode::Value, "NOISE_VALUE"),
+            (self.noise_mode == GlitchNoiseMode::Cell, "NOISE_CELL"),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, def)| def.into())
+        .chain(
+            (self.antialias_taps > 0)
+                .then(|| ShaderDefVal::UInt("DISPLACEMENT_TAPS".into(), self.antialias_taps)),
+        )
+        .collect()
+    }
+}
+
+impl SpecializedRenderPipeline for VideoGlitchPipeline {
+    type Key = VideoGlitchPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        // Both entry points share the bind group layout, so switching modes
+        // only swaps the pipeline.
+        let entry_point = match (key.pass, key.mode) {
+            (GlitchPass::Fused, VideoGlitchMode::Full) => "fragment",
+            (GlitchPass::Fused, VideoGlitchMode::Fast) => "fragment_fast",
+            (GlitchPass::Displace, VideoGlitchMode::Full) => "fragment_displace",
+            (GlitchPass::Displace, VideoGlitchMode::Fast) => "fragment_displace_fast",
+            (GlitchPass::Color, VideoGlitchMode::Full) => "fragment_color",
+            (GlitchPass::Color, VideoGlitchMode::Fast) => "fragment_color_fast",
+        };
+        RenderPipelineDescriptor {
+            label: Some("video_glitch_pipeline".into()),
+            layout: vec![self.layout(key.filterable).clone()],
+            // This will setup a fullscreen triangle for the vertex state
+            vertex: fullscreen_shader_vertex_state(),
+            fragment: Some(FragmentState {
+                shader: self.shader.clone(),
+                shader_defs: {
+                    let mut shader_defs = key.features.shader_defs();
+                    shader_defs.push(ShaderDefVal::UInt("GLITCH_TAPS".into(), key.taps));
+                    if self.push_constants {
+                        shader_defs.push("PUSH_CONSTANTS".into());
+                    }
+                    if key.debug_displacement {
+                        shader_defs.push("DEBUG_DISPLACEMENT".into());
+                    }
+                    shader_defs
+                },
+                // Make sure this matches the entry point of your shader.
+                // It can be anything as long as it matches here and in the shader.
+                entry_point: entry_point.into(),
+                targets: vec![Some(ColorTargetState {
+                    format: key.format,
+                    blend: if key.scaled || !key.pass.is_last() {
+                        None
+                    } else {
+                        self.blend
+                    },
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            // All of the following properties are not important for this effect so just use the default values.
+            // This struct doesn't have the Default trait implemented because not all field can have a default value.
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            // Post processing always reads and writes the view's resolved,
+            // single sampled main textures, whatever the camera's MSAA
+            // setting, so this must stay at one sample.
+            multisample: MultisampleState {
+                count: 1,
+                ..default()
+            },
+            push_constant_ranges: if self.push_constants {
+                vec![PushConstantRange {
+                    stages: ShaderStages::FRAGMENT,
+                    range: 0..VideoGlitchUniform::min_size().get() as u32,
+                }]
+            } else {
+                vec![]
+            },
+            zero_initialize_workgroup_memory: false,
+        }
+    }
+}
+
+// The view's depth prepass, if it has one the shader can read. Multisampled
+// depth would need a pipeline of its own and is left out.
+fn depth_prepass_view(prepass_textures: Option<&ViewPrepassTextures>) -> Option<&TextureView> {
+    prepass_textures?
+        .depth
+        .as_ref()
+        .filter(|depth| depth.texture.texture.sample_count() == 1)
+        .map(|depth| &depth.texture.default_view)
+}
+
+fn uses_uniform_buffer(pipeline: Res<VideoGlitchPipeline>) -> bool {
+    !pipeline.push_constants
+}
+
+// The specialized pipelines the nodes should use for a view, one per pass.
+#[derive(Component)]
+struct VideoGlitchPipelineIds(Vec<(GlitchPass, CachedRenderPipelineId)>);
+
+impl VideoGlitchPipelineIds {
+    fn get(&self, pass: GlitchPass) -> Option<CachedRenderPipelineId> {
+        self.0
+            .iter()
+            .find_map(|(id_pass, id)| (*id_pass == pass).then_some(*id))
+    }
+}
+
+// The last pipeline of each view and pass that was ready to draw with.
+#[derive(Resource, Default)]
+struct ReadyVideoGlitchPipelines(HashMap<(Entity, GlitchPass), CachedRenderPipelineId>);
+
+// The state of a pipeline that may have been queued this frame, which the
+// cache's `get_render_pipeline*` panic on until it has processed its queue.
+fn render_pipeline_state(
+    pipeline_cache: &PipelineCache,
+    id: CachedRenderPipelineId,
+) -> &CachedPipelineState {
+    pipeline_cache
+        .pipelines()
+        .nth(id.id())
+        .map_or(&CachedPipelineState::Queued, |pipeline| &pipeline.state)
+}
+
+fn prepare_video_glitch_pipelines(
+    mut commands: Commands,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<VideoGlitchPipeline>>,
+    mut ready_pipelines: ResMut<ReadyVideoGlitchPipelines>,
+    video_glitch_pipeline: Res<VideoGlitchPipeline>,
+    pipeline_status: Res<SharedPipelineStatus>,
+    config: Res<VideoGlitchConfig>,
+    adapter: Res<RenderAdapter>,
+    // The formats whose sampling path has been logged.
+    mut logged_formats: Local<HashSet<TextureFormat>>,
+    // The layered views that have been warned about.
+    mut logged_layered: Local<HashSet<Entity>>,
+    views: Query<(
+        Entity,
+        &ViewTarget,
+        &VideoGlitchUniform,
+        Option<&VideoGlitchMode>,
+        Option<&VideoGlitchScale>,
+        Option<&GlitchQuality>,
+        Has<GlitchMask>,
+        Option<&ViewPrepassTextures>,
+        Has<GlitchDebugDisplacement>,
+    )>,
+) {
+    ready_pipelines
+        .0
+        .retain(|(entity, _), _| views.contains(*entity));
+
+    let mut status = VideoGlitchPipelineStatus::Idle;
+    for (
+        entity,
+        view_target,
+        settings,
+        mode,
+        scale,
+        quality,
+        has_mask,
+        prepass_textures,
+        debug_displacement,
+    ) in &views
+    {
+        // Bevy renders every view into a single layer. A view drawing several
+        // at once, e.g. both eyes of a headset with multiview, would only have
+        // its first layer glitched, so it is left as it is.
+        if view_target.main_texture().depth_or_array_layers() > 1 {
+            if logged_layered.insert(entity) {
+                warn!(
+                    "VideoGlitchPlugin: {entity} renders into a texture array, which the effect \
+                     doesn't support; give each layer a camera of its own"
+                );
+            }
+            commands.entity(entity).remove::<VideoGlitchPipelineIds>();
+            continue;
+        }
+
+        let format = view_target.main_texture_format();
+        let filterable = is_filterable(&adapter, format);
+        if logged_formats.insert(format) {
+            if filterable {
+                debug!("VideoGlitchPlugin: sampling {format:?} views with filtering");
+            } else {
+                info!(
+                    "VideoGlitchPlugin: {format:?} can't be filtered on this device, \
+                     sampling its views with the nearest filter"
+                );
+            }
+        }
+
+        let mut pipeline_ids = Vec::with_capacity(config.passes.len());
+        for &pass in config.passes {
+            let key = VideoGlitchPipelineKey {
+                mode: mode.copied().unwrap_or_default(),
+                format,
+                filterable,
+                // The split passes always run at full resolution.
+                scaled: pass == GlitchPass::Fused && scale.is_some_and(VideoGlitchScale::is_scaled),
+                features: GlitchFeatures::new(
+                    settings,
+                    has_mask,
+                    depth_prepass_view(prepass_textures).is_some(),
+                    config.max_antialias,
+                ),
+                taps: quality
+                    .unwrap_or(&config.quality)
+                    .taps()
+                    .min(config.max_taps),
+                pass,
+                debug_displacement,
+            };
+            // This will add the pipeline to the cache and queue its creation the
+            // first time a key is seen.
+            let pipeline_id = pipelines.specialize(&pipeline_cache, &video_glitch_pipeline, key);
+            if config.warm_ldr_and_hdr {
+                let other_format = if view_target.is_hdr() {
+                    TextureFormat::bevy_default()
+                } else {
+                    ViewTarget::TEXTURE_FORMAT_HDR
+                };
+                pipelines.specialize(
+                    &pipeline_cache,
+                    &video_glitch_pipeline,
+                    VideoGlitchPipelineKey {
+                        format: other_format,
+                        filterable: is_filterable(&adapter, other_format),
+                        ..key
+                    },
+                );
+            }
+
+            // Turning a part of the effect on or off compiles a new pipeline in
+            // the background. Until it is ready keep drawing with the previous
+            // one, which differs only in the parts that just changed.
+            let pipeline_id = if let CachedPipelineState::Ok(_) =
+                render_pipeline_state(&pipeline_cache, pipeline_id)
+            {
+                status = status.merge(VideoGlitchPipelineStatus::Ready);
+                ready_pipelines.0.insert((entity, pass), pipeline_id);
+                pipeline_id
+            } else {
+                status = status.merge(match render_pipeline_state(&pipeline_cache, pipeline_id) {
+                    CachedPipelineState::Err(err) => {
+                        VideoGlitchPipelineStatus::Failed(err.to_string())
+                    }
+                    _ => VideoGlitchPipelineStatus::Queued,
+                });
+                ready_pipelines
+                    .0
+                    .get(&(entity, pass))
+                    .copied()
+                    .unwrap_or(pipeline_id)
+            };
+            pipeline_ids.push((pass, pipeline_id));
+        }
+        commands
+            .entity(entity)
+            .insert(VideoGlitchPipelineIds(pipeline_ids));
+    }
+    *pipeline_status.0.lock().unwrap() = status;
+}
+
+/// Leaves a camera's frames un-glitched while a screenshot of its target is
+/// being taken with bevy's [`Screenshot`].
+///
+/// Only the captured frames are affected, so taking a screenshot every frame
+/// records a clean video.
+#[derive(Component, Reflect, Debug, Default, Clone, Copy, ExtractComponent)]
+#[reflect(Component, Default)]
+pub struct VideoGlitchHideInScreenshots;
+
+/// Shows a camera's displacement instead of the glitched scene, to see which
+/// settings tear what while tuning them.
+///
+/// Every pixel shows the shift of the tear at its center, after all of the
+/// settings and [`GlitchHit`]s combined and pixel snapping, as linear color:
+/// red is the horizontal shift, from 0 at `displacement.amount` to the left,
+/// through 0.5 for none, to 1 at as much to the right. Green is the same for
+/// the vertical shift, which stays at 0.5 as rows only tear horizontally.
+/// Blue is 1 where a pixel moves at all. Nothing else of the effect is drawn.
+///
+/// Adding or removing it compiles a new pipeline in the background.
+#[derive(Component, Reflect, Debug, Default, Clone, Copy, ExtractComponent)]
+#[reflect(Component, Default)]
+pub struct GlitchDebugDisplacement;
+
+/// Selects which fragment entry point renders the effect for a camera.
+///
+/// Add it next to [`VideoGlitchSettings`]; cameras without it use
+/// [`VideoGlitchMode::Full`].
+#[derive(
+    Component, Reflect, Debug, Default, Clone, Copy, Hash, PartialEq, Eq, ExtractComponent,
+)]
+#[reflect(Component, Default)]
+pub enum VideoGlitchMode {
+    /// The complete effect.
+    #[default]
+    Full,
+    /// A cheaper effect for mobile GPUs: row displacement and a single-tap
+    /// color aberration only.
+    Fast,
+}
+
+// The most taps `GlitchQuality::Custom` and `VideoGlitchPlugin::max_taps`
+// allow.
+const MAX_GLITCH_TAPS: u32 = 256;
+
+/// How many texture taps the effect may spend per pixel, trading quality for
+/// speed.
+///
+/// Add it to a camera to override the plugin's
+/// [`VideoGlitchPlugin::quality`]. Changing it compiles a new pipeline in the
+/// background, and the camera keeps its current look until it is ready.
+/// [`VideoGlitchMode::Fast`] always uses a single tap.
+#[derive(
+    Component, Reflect, Debug, Default, Clone, Copy, Hash, PartialEq, Eq, ExtractComponent,
+)]
+#[reflect(Component, Default)]
+pub enum GlitchQuality {
+    /// A single shifted tap shared by both secondary colors, for mobile and
+    /// the web.
+    Low,
+    /// One shifted tap per secondary color.
+    #[default]
+    Medium,
+    /// Eight taps per secondary color, smearing the color fringes smoothly
+    /// over the shift.
+    High,
+    /// This many taps, from 1 to [`VideoGlitchPlugin::max_taps`], split
+    /// between the secondary colors.
+    Custom {
+        /// The number of taps.
+        taps: u32,
+    },
+}
+
+impl GlitchQuality {
+    /// The number of shifted texture taps per pixel.
+    pub fn taps(&self) -> u32 {
+        match *self {
+            Self::Low => 1,
+            Self::Medium => 2,
+            Self::High => 16,
+            Self::Custom { taps } => taps.clamp(1, MAX_GLITCH_TAPS),
+        }
+    }
+}
+
+/// Adjustments to [`VideoGlitchPlugin::sampler`] for one camera.
+///
+/// Each combination has a sampler created up front, so changing it takes
+/// effect on the next frame.
+#[derive(
+    Component, Reflect, Debug, Default, Clone, Copy, Hash, PartialEq, Eq, ExtractComponent,
+)]
+#[reflect(Component, Default)]
+pub struct GlitchSampling {
+    /// Sample the nearest texel, keeping displaced pixel art crisp.
+    pub nearest: bool,
+    /// Clamp to the edges of the screen, so shifts past an edge don't wrap
+    /// around to the opposite one.
+    pub clamp_to_edge: bool,
+}
+
+impl GlitchSampling {
+    fn apply(&self, sampler: &ImageSamplerDescriptor) -> ImageSamplerDescriptor {
+        let mut sampler = sampler.clone();
+        if self.nearest {
+            sampler.mag_filter = ImageFilterMode::Nearest;
+            sampler.min_filter = ImageFilterMode::Nearest;
+            sampler.mipmap_filter = ImageFilterMode::Nearest;
+            // Anisotropic filtering requires linear filters.
+            sampler.anisotropy_clamp = 1;
+        }
+        if self.clamp_to_edge {
+            sampler.address_mode_u = ImageAddressMode::ClampToEdge;
+            sampler.address_mode_v = ImageAddressMode::ClampToEdge;
+            sampler.address_mode_w = ImageAddressMode::ClampToEdge;
+        }
+        sampler
+    }
+
+    fn index(&self) -> usize {
+        (self.nearest as usize) << 1 | self.clamp_to_edge as usize
+    }
+}
+
+// Whether `sampler` needs a filtering binding.
+fn is_filtering(sampler: &ImageSamplerDescriptor) -> bool {
+    [
+        sampler.mag_filter,
+        sampler.min_filter,
+        sampler.mipmap_filter,
+    ]
+    .iter()
+    .any(|mode| matches!(mode, ImageFilterMode::Linear))
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        render::{render_graph::EmptyNode, settings::WgpuSettings, RenderPlugin},
+        window::ExitCondition,
+    };
+
+    use super::*;
+
+    // An app with a render app whose 2D and 3D graphs hold just the nodes the
+    // effect is placed between by default.
+    fn app_with_render_graph() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Shader>()
+            .init_asset::<Image>();
+
+        let mut render_graph = RenderGraph::default();
+        let position = GlitchGraphPosition::default();
+        for (graph, (after, before)) in [
+            (Core2d.intern(), position.edges_2d()),
+            (Core3d.intern(), position.edges_3d()),
+        ] {
+            let mut sub_graph = RenderGraph::default();
+            sub_graph.add_node(after, EmptyNode);
+            sub_graph.add_node(before, EmptyNode);
+            sub_graph.add_node_edge(after, before);
+            render_graph.add_sub_graph(graph, sub_graph);
         }
+        let mut render_app = SubApp::new();
+        render_app.insert_resource(render_graph);
+        app.insert_sub_app(RenderApp, render_app);
+        app
+    }
+
+    fn has_effect(app: &App, graph: impl RenderSubGraph) -> bool {
+        app.sub_app(RenderApp)
+            .world()
+            .resource::<RenderGraph>()
+            .get_sub_graph(graph)
+            .unwrap()
+            .get_node_state(VideoGlitchLabel)
+            .is_ok()
+    }
+
+    // Run the app like its runner would, with the plugins finished first.
+    fn run(app: &mut App, frames: usize) {
+        app.finish();
+        app.cleanup();
+        for _ in 0..frames {
+            app.update();
+        }
+    }
+
+    #[test]
+    fn runs_without_a_render_app() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                ..default()
+            },
+            // Without backends there is no render app.
+            RenderPlugin {
+                render_creation: WgpuSettings {
+                    backends: None,
+                    ..default()
+                }
+                .into(),
+                ..default()
+            },
+            ImagePlugin::default(),
+            VideoGlitchPlugin::default(),
+        ));
+        app.world_mut()
+            .spawn((Camera3d::default(), VideoGlitchSettings::default()));
+        run(&mut app, 3);
+        assert_eq!(
+            *app.world().resource::<VideoGlitchPipelineStatus>(),
+            VideoGlitchPipelineStatus::Idle
+        );
+    }
+
+    #[test]
+    fn disables_itself_without_a_render_device() {
+        let mut app = app_with_render_graph();
+        app.add_plugins(VideoGlitchPlugin::default());
+        app.world_mut()
+            .spawn((Camera3d::default(), VideoGlitchSettings::default()));
+        run(&mut app, 3);
+        assert!(!app
+            .sub_app(RenderApp)
+            .world()
+            .contains_resource::<VideoGlitchPipeline>());
+        assert_eq!(
+            *app.world().resource::<VideoGlitchPipelineStatus>(),
+            VideoGlitchPipelineStatus::Idle
+        );
+    }
+
+    #[test]
+    fn only_2d_leaves_the_3d_graph_alone() {
+        let mut app = app_with_render_graph();
+        app.add_plugins(VideoGlitchPlugin::only_2d());
+        assert!(has_effect(&app, Core2d));
+        assert!(!has_effect(&app, Core3d));
+    }
+
+    #[test]
+    fn only_3d_leaves_the_2d_graph_alone() {
+        let mut app = app_with_render_graph();
+        app.add_plugins(VideoGlitchPlugin::only_3d());
+        assert!(!has_effect(&app, Core2d));
+        assert!(has_effect(&app, Core3d));
+    }
+
+    #[test]
+    fn adding_the_plugin_twice_keeps_one_node() {
+        let mut app = app_with_render_graph();
+        app.add_plugins(VideoGlitchPlugin::only_2d())
+            .add_plugins(VideoGlitchPlugin::default().with_split_passes());
+        run(&mut app, 2);
+
+        // The first configuration is the one used.
+        assert!(has_effect(&app, Core2d));
+        assert!(!has_effect(&app, Core3d));
+        let graph = app.sub_app(RenderApp).world().resource::<RenderGraph>();
+        let sub_graph = graph.get_sub_graph(Core2d).unwrap();
+        assert_eq!(
+            sub_graph
+                .iter_nodes()
+                .filter(|node| node.label == VideoGlitchLabel.intern())
+                .count(),
+            1
+        );
+        assert!(sub_graph.get_node_state(VideoGlitchDisplaceLabel).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "max_taps")]
+    fn rejects_too_many_taps() {
+        app_with_render_graph().add_plugins(VideoGlitchPlugin::default().with_max_taps(1000));
+    }
+
+    #[test]
+    #[should_panic(expected = "max_antialias")]
+    fn rejects_too_many_antialias_samples() {
+        app_with_render_graph().add_plugins(VideoGlitchPlugin::default().with_max_antialias(5));
+    }
+
+    #[test]
+    fn antialiasing_is_capped_by_the_plugin() {
+        let mut settings = VideoGlitchSettings::default();
+        settings.displacement.antialias = 4;
+        let uniform = VideoGlitchUniform::from(&settings);
+        let samples = |max| GlitchFeatures::new(&uniform, false, false, max).antialias_taps;
+        assert_eq!(samples(4), 4);
+        assert_eq!(samples(2), 2);
+        assert_eq!(samples(1), 0);
+        assert_eq!(samples(0), 0);
+        assert!(GlitchFeatures::new(&uniform, false, false, 2)
+            .shader_defs()
+            .contains(&ShaderDefVal::UInt("DISPLACEMENT_TAPS".into(), 2)));
+    }
+
+    #[test]
+    fn default_registers_both_graphs() {
+        let mut app = app_with_render_graph();
+        app.add_plugins(VideoGlitchPlugin::default());
+        assert!(has_effect(&app, Core2d));
+        assert!(has_effect(&app, Core3d));
     }
 }
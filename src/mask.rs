@@ -0,0 +1,264 @@
+//! Render the mask of a camera from tagged entities with a second camera.
+use bevy::{
+    core_pipeline::tonemapping::{DebandDither, Tonemapping},
+    pbr::NotShadowCaster,
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+        view::RenderLayers,
+    },
+};
+
+use crate::{GlitchMask, MASK_RENDER_LAYER};
+
+/// Turns a camera into the source of another camera's [`GlitchMask`], so only
+/// the entities tagged with [`GlitchMasked`] glitch.
+///
+/// The mask camera is made a child of `camera`, follows its projection and
+/// renders before it into a single channel image the size of its viewport,
+/// which is resized along with it. It only sees
+/// [`GlitchMaskSource::RENDER_LAYER`], where tagged entities are drawn white
+/// and [`GlitchMaskOccluder`]s black, so the parts of a tagged entity hidden
+/// behind an occluder don't glitch.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_video_glitch::{GlitchMaskSource, VideoGlitchSettings};
+/// fn setup(mut commands: Commands) {
+///     let camera = commands
+///         .spawn((Camera3d::default(), VideoGlitchSettings::default()))
+///         .id();
+///     commands.spawn((Camera3d::default(), GlitchMaskSource { camera }));
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct GlitchMaskSource {
+    /// The camera whose effect is masked.
+    pub camera: Entity,
+}
+
+impl GlitchMaskSource {
+    /// The render layer the mask is drawn on, which other cameras don't see
+    /// unless told to.
+    pub const RENDER_LAYER: usize = MASK_RENDER_LAYER;
+}
+
+/// Tags an entity with a mesh to glitch where a [`GlitchMaskSource`] masks
+/// the effect.
+#[derive(Component, Reflect, Debug, Default, Clone, Copy)]
+#[reflect(Component, Default)]
+pub struct GlitchMasked;
+
+/// Tags an entity with a mesh that hides [`GlitchMasked`] entities behind it
+/// from the mask, e.g. the walls and floor of a level.
+#[derive(Component, Reflect, Debug, Default, Clone, Copy)]
+#[reflect(Component, Default)]
+pub struct GlitchMaskOccluder;
+
+// The copy of a tagged entity drawn into the mask.
+#[derive(Component)]
+pub(crate) struct GlitchMaskTwin;
+
+pub(crate) fn update_glitch_mask_sources(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut sources: Query<(
+        Entity,
+        &GlitchMaskSource,
+        &mut Camera,
+        Option<&mut Projection>,
+        Option<&Parent>,
+    )>,
+    cameras: Query<
+        (&Camera, Option<Ref<Projection>>, Option<&GlitchMask>),
+        Without<GlitchMaskSource>,
+    >,
+) {
+    for (entity, source, mut mask_camera, projection, parent) in &mut sources {
+        let Ok((camera, camera_projection, mask)) = cameras.get(source.camera) else {
+            continue;
+        };
+        let Some(size) = camera.physical_viewport_size() else {
+            continue;
+        };
+
+        let attached = parent.map(Parent::get) == Some(source.camera);
+        if !attached {
+            commands.entity(entity).set_parent(source.camera).insert((
+                Transform::IDENTITY,
+                RenderLayers::layer(GlitchMaskSource::RENDER_LAYER),
+                // Keep the white of the mask white.
+                Tonemapping::None,
+                DebandDither::Disabled,
+            ));
+            mask_camera.clear_color = ClearColorConfig::Custom(Color::BLACK);
+        }
+        if let (Some(mut projection), Some(camera_projection)) = (projection, camera_projection) {
+            if !attached || camera_projection.is_changed() {
+                *projection = camera_projection.clone();
+            }
+        }
+        // Render the mask before the camera reads it.
+        if mask_camera.order >= camera.order {
+            mask_camera.order = camera.order - 1;
+        }
+
+        let size = Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        };
+        // A mask the camera had before is replaced rather than drawn over.
+        let mask = mask.filter(
+            |mask| matches!(&mask_camera.target, RenderTarget::Image(image) if *image == mask.0),
+        );
+        match mask.and_then(|mask| images.get_mut(&mask.0)) {
+            Some(image) if image.texture_descriptor.size == size => {}
+            // The camera picks up the new size of its target on its own.
+            Some(image) => image.resize(size),
+            None => {
+                let mut image = Image::new_fill(
+                    size,
+                    TextureDimension::D2,
+                    &[0],
+                    TextureFormat::R8Unorm,
+                    // Kept in the main world to be resized in place.
+                    RenderAssetUsages::default(),
+                );
+                image.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING
+                    | TextureUsages::COPY_DST
+                    | TextureUsages::RENDER_ATTACHMENT;
+                let handle = images.add(image);
+                mask_camera.target = RenderTarget::Image(handle.clone());
+                commands.entity(source.camera).insert(GlitchMask(handle));
+            }
+        }
+    }
+}
+
+pub(crate) fn sync_glitch_mask_twins(
+    mut commands: Commands,
+    mut materials: Option<ResMut<Assets<StandardMaterial>>>,
+    mut twin_materials: Local<Option<[Handle<StandardMaterial>; 2]>>,
+    tagged: Query<
+        (Entity, &Mesh3d, Has<GlitchMasked>),
+        Or<(Added<GlitchMasked>, Added<GlitchMaskOccluder>)>,
+    >,
+    mut untagged_masked: RemovedComponents<GlitchMasked>,
+    mut untagged_occluders: RemovedComponents<GlitchMaskOccluder>,
+    children: Query<&Children>,
+    twins: Query<(), With<GlitchMaskTwin>>,
+) {
+    for entity in untagged_masked.read().chain(untagged_occluders.read()) {
+        for child in children.get(entity).into_iter().flatten() {
+            if twins.contains(*child) {
+                commands.entity(*child).despawn_recursive();
+            }
+        }
+    }
+
+    if tagged.is_empty() {
+        return;
+    }
+    let Some(materials) = materials.as_mut() else {
+        return;
+    };
+    let [masked_material, occluder_material] = twin_materials.get_or_insert_with(|| {
+        [Color::WHITE, Color::BLACK].map(|color| {
+            materials.add(StandardMaterial {
+                base_color: color,
+                unlit: true,
+                ..default()
+            })
+        })
+    });
+    for (entity, mesh, masked) in &tagged {
+        commands.entity(entity).with_children(|parent| {
+            parent.spawn((
+                mesh.clone(),
+                MeshMaterial3d(if masked {
+                    masked_material.clone()
+                } else {
+                    occluder_material.clone()
+                }),
+                RenderLayers::layer(GlitchMaskSource::RENDER_LAYER),
+                NotShadowCaster,
+                GlitchMaskTwin,
+            ));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::render::camera::Viewport;
+
+    use super::*;
+
+    // Drop the images extracted to a render world that it alone keeps, like
+    // bevy's extraction does.
+    fn extract_images(mut images: ResMut<Assets<Image>>) {
+        let extracted: Vec<_> = images
+            .iter()
+            .filter(|(_, image)| !image.asset_usage.contains(RenderAssetUsages::MAIN_WORLD))
+            .map(|(id, _)| id)
+            .collect();
+        for id in extracted {
+            images.remove(id);
+        }
+    }
+
+    fn viewport(width: u32, height: u32) -> Option<Viewport> {
+        Some(Viewport {
+            physical_size: UVec2::new(width, height),
+            ..default()
+        })
+    }
+
+    // A camera masked by a mask source, and the source.
+    fn app() -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Image>()
+            .add_systems(Update, (update_glitch_mask_sources, extract_images).chain());
+        let camera = app
+            .world_mut()
+            .spawn(Camera {
+                viewport: viewport(64, 32),
+                ..default()
+            })
+            .id();
+        app.world_mut()
+            .spawn((Camera::default(), GlitchMaskSource { camera }));
+        (app, camera)
+    }
+
+    fn mask(app: &App, camera: Entity) -> Handle<Image> {
+        app.world().get::<GlitchMask>(camera).unwrap().0.clone()
+    }
+
+    #[test]
+    fn the_mask_is_kept_between_frames() {
+        let (mut app, camera) = app();
+        app.update();
+        let first = mask(&app, camera);
+        app.update();
+        assert_eq!(mask(&app, camera), first);
+        assert!(app.world().resource::<Assets<Image>>().contains(&first));
+    }
+
+    #[test]
+    fn the_mask_is_resized_in_place() {
+        let (mut app, camera) = app();
+        app.update();
+        let first = mask(&app, camera);
+        app.world_mut().get_mut::<Camera>(camera).unwrap().viewport = viewport(32, 16);
+        app.update();
+        assert_eq!(mask(&app, camera), first);
+        let images = app.world().resource::<Assets<Image>>();
+        assert_eq!(images.get(&first).unwrap().size(), UVec2::new(32, 16));
+    }
+}
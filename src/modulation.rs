@@ -0,0 +1,265 @@
+//! Combine everything driving a camera's intensity in a fixed way, whatever
+//! order the drivers run in.
+use bevy::prelude::*;
+
+use crate::GlitchModulationSet;
+
+/// How a [`GlitchModulator`] changes a camera's intensity this frame.
+///
+/// Every modulator on a camera is combined with its own intensity, after its
+/// [`VideoGlitchLayers`] are, as
+///
+/// ```text
+/// (max(intensity, floors...) + offsets...) * multipliers...
+/// ```
+///
+/// clamped to [0, 1], before the [`GlitchResponse`] curve. Floors combine by
+/// their maximum, offsets add up and multipliers multiply, so the result
+/// doesn't depend on the order modulators run in. E.g. an intensity of 0.1
+/// with a `Floor(0.6)` burst, a `Floor(0.4)` burst, an `Offset(0.2)` and a
+/// `Multiply(0.5)` becomes (0.6 + 0.2) * 0.5 = 0.4.
+///
+/// [`VideoGlitchLayers`]: crate::VideoGlitchLayers
+/// [`GlitchResponse`]: crate::GlitchResponse
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+pub enum GlitchModulation {
+    /// Raise the intensity to at least this much, e.g. for a burst.
+    Floor(f32),
+    /// Add this to the intensity, e.g. for a driver following the game.
+    Offset(f32),
+    /// Scale the intensity, e.g. for a global fade.
+    Multiply(f32),
+}
+
+/// A component on a camera that changes its intensity every frame without
+/// touching its [`VideoGlitchSettings`].
+///
+/// Register it with [`GlitchModulatorAppExt::add_glitch_modulator`]. Systems
+/// updating a modulator belong in [`GlitchModulationSet`], after which every
+/// modulator is read once and combined as [`GlitchModulation`] describes.
+/// [`GlitchAudioDriver`] and [`GlitchFromMotion`] are modulators too.
+///
+/// [`VideoGlitchSettings`]: crate::VideoGlitchSettings
+/// [`GlitchAudioDriver`]: crate::GlitchAudioDriver
+/// [`GlitchFromMotion`]: crate::GlitchFromMotion
+pub trait GlitchModulator: Component {
+    /// The name listed in [`GlitchEffective::modulators`]. The type's name by
+    /// default.
+    ///
+    /// [`GlitchEffective::modulators`]: crate::GlitchEffective::modulators
+    fn name(&self) -> &'static str {
+        let name = std::any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name)
+    }
+
+    /// How the modulator changes the camera's intensity this frame, or `None`
+    /// to leave it alone.
+    fn modulation(&self) -> Option<GlitchModulation>;
+}
+
+/// Registers [`GlitchModulator`]s with an [`App`].
+pub trait GlitchModulatorAppExt {
+    /// Apply every `M` to the intensity of the camera it is on.
+    fn add_glitch_modulator<M: GlitchModulator>(&mut self) -> &mut Self;
+}
+
+impl GlitchModulatorAppExt for App {
+    fn add_glitch_modulator<M: GlitchModulator>(&mut self) -> &mut Self {
+        self.add_systems(
+            PostUpdate,
+            collect_glitch_modulation::<M>.in_set(GlitchModulatorSystems::Collect),
+        )
+    }
+}
+
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+pub(crate) enum GlitchModulatorSystems {
+    Collect,
+    Combine,
+}
+
+pub(crate) fn configure_glitch_modulators(app: &mut App) {
+    app.configure_sets(
+        PostUpdate,
+        (
+            GlitchModulatorSystems::Collect,
+            GlitchModulatorSystems::Combine,
+        )
+            .chain()
+            .after(GlitchModulationSet),
+    )
+    .add_systems(
+        PostUpdate,
+        combine_glitch_modulations.in_set(GlitchModulatorSystems::Combine),
+    );
+}
+
+/// The modulators of a camera, combined.
+///
+/// Public only because the settings' extraction queries it; the module is
+/// private.
+#[derive(Component, Debug, Clone, Default)]
+pub struct GlitchModulations {
+    // This frame's modulations by modulator name, until they are combined.
+    pending: Vec<(&'static str, GlitchModulation)>,
+    pub(crate) combined: CombinedModulation,
+    pub(crate) names: Vec<&'static str>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct CombinedModulation {
+    floor: f32,
+    offset: f32,
+    multiplier: f32,
+}
+
+impl Default for CombinedModulation {
+    fn default() -> Self {
+        Self {
+            floor: 0.0,
+            offset: 0.0,
+            multiplier: 1.0,
+        }
+    }
+}
+
+impl CombinedModulation {
+    pub(crate) fn apply(&self, intensity: f32) -> f32 {
+        ((intensity.max(self.floor) + self.offset) * self.multiplier).clamp(0.0, 1.0)
+    }
+}
+
+fn collect_glitch_modulation<M: GlitchModulator>(
+    mut commands: Commands,
+    mut cameras: Query<(Entity, &M, Option<&mut GlitchModulations>)>,
+) {
+    for (entity, modulator, modulations) in &mut cameras {
+        let Some(modulation) = modulator.modulation() else {
+            continue;
+        };
+        let pending = (modulator.name(), modulation);
+        match modulations {
+            // Only the combined result counts as a change.
+            Some(mut modulations) => modulations.bypass_change_detection().pending.push(pending),
+            // Other modulators on the camera may add it this frame as well.
+            None => {
+                commands
+                    .entity(entity)
+                    .entry::<GlitchModulations>()
+                    .or_default()
+                    .and_modify(move |mut modulations| {
+                        modulations.bypass_change_detection().pending.push(pending);
+                    });
+            }
+        }
+    }
+}
+
+fn combine_glitch_modulations(mut cameras: Query<&mut GlitchModulations>) {
+    for mut modulations in &mut cameras {
+        let mut pending = std::mem::take(&mut modulations.bypass_change_detection().pending);
+        // Sum in a fixed order, so rounding doesn't depend on system order
+        // either.
+        pending.sort_by_key(|(name, _)| *name);
+
+        let mut combined = CombinedModulation::default();
+        for (_, modulation) in &pending {
+            match *modulation {
+                GlitchModulation::Floor(floor) => combined.floor = combined.floor.max(floor),
+                GlitchModulation::Offset(offset) => combined.offset += offset,
+                GlitchModulation::Multiply(multiplier) => combined.multiplier *= multiplier,
+            }
+        }
+        let names: Vec<_> = pending.into_iter().map(|(name, _)| name).collect();
+        if combined != modulations.combined || names != modulations.names {
+            modulations.combined = combined;
+            modulations.names = names;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! modulators {
+        ($($name:ident => $modulation:ident),* $(,)?) => {$(
+            #[derive(Component)]
+            struct $name(f32);
+
+            impl GlitchModulator for $name {
+                fn modulation(&self) -> Option<GlitchModulation> {
+                    Some(GlitchModulation::$modulation(self.0))
+                }
+            }
+        )*};
+    }
+
+    modulators! {
+        Burst => Floor,
+        Flash => Floor,
+        Drive => Offset,
+        Fade => Multiply,
+    }
+
+    type Register = fn(&mut App) -> &mut App;
+    const BURST: Register = App::add_glitch_modulator::<Burst>;
+    const FLASH: Register = App::add_glitch_modulator::<Flash>;
+    const DRIVE: Register = App::add_glitch_modulator::<Drive>;
+    const FADE: Register = App::add_glitch_modulator::<Fade>;
+
+    // An app combining the modulators, registered in `order`.
+    fn app(order: &[Register]) -> App {
+        let mut app = App::new();
+        configure_glitch_modulators(&mut app);
+        for register in order {
+            register(&mut app);
+        }
+        app
+    }
+
+    fn modulate(app: &mut App, camera: impl Bundle) -> (CombinedModulation, Vec<&'static str>) {
+        let camera = app.world_mut().spawn(camera).id();
+        app.update();
+        let modulations = app.world().get::<GlitchModulations>(camera).unwrap();
+        (modulations.combined, modulations.names.clone())
+    }
+
+    #[test]
+    fn floors_then_offsets_then_multipliers() {
+        let mut app = app(&[FADE, DRIVE, FLASH, BURST]);
+        let (combined, names) = modulate(&mut app, (Burst(0.6), Flash(0.4), Drive(0.2), Fade(0.5)));
+        // The example of `GlitchModulation`'s docs.
+        assert!((combined.apply(0.1) - 0.4).abs() < 1e-6);
+        // An intensity above the floors is kept.
+        assert!((combined.apply(0.7) - 0.45).abs() < 1e-6);
+        assert_eq!(names, ["Burst", "Drive", "Fade", "Flash"]);
+    }
+
+    #[test]
+    fn the_result_is_clamped() {
+        let mut app = app(&[DRIVE, FADE]);
+        let (combined, _) = modulate(&mut app, Drive(0.8));
+        assert_eq!(combined.apply(0.5), 1.0);
+        let (combined, _) = modulate(&mut app, Fade(-1.0));
+        assert_eq!(combined.apply(0.5), 0.0);
+        let (combined, _) = modulate(&mut app, (Drive(-0.5), Fade(2.0)));
+        assert_eq!(combined.apply(0.25), 0.0);
+    }
+
+    #[test]
+    fn the_order_of_the_systems_does_not_matter() {
+        let camera = || (Burst(0.3), Flash(0.35), Drive(0.1), Fade(0.9));
+        let forwards = modulate(&mut app(&[BURST, FLASH, DRIVE, FADE]), camera());
+        let backwards = modulate(&mut app(&[FADE, DRIVE, FLASH, BURST]), camera());
+        assert_eq!(forwards, backwards);
+    }
+
+    #[test]
+    fn cameras_without_modulations_are_left_alone() {
+        let mut app = app(&[BURST]);
+        let camera = app.world_mut().spawn_empty().id();
+        app.update();
+        assert!(app.world().get::<GlitchModulations>(camera).is_none());
+    }
+}
@@ -0,0 +1,178 @@
+//! Drive a camera's intensity from how fast it moves and turns.
+use bevy::prelude::*;
+
+use crate::{GlitchModulation, GlitchModulator, GlitchResponse};
+
+/// Maps a speed onto the intensity it adds.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Default)]
+pub struct GlitchSpeedMapping {
+    /// The speed at and below which nothing is added.
+    pub threshold: f32,
+    /// The speed at and above which `max` is added.
+    pub full: f32,
+    /// The intensity added at `full` speed.
+    pub max: f32,
+    /// The curve from `threshold` to `full`.
+    pub response: GlitchResponse,
+}
+
+impl Default for GlitchSpeedMapping {
+    fn default() -> Self {
+        Self {
+            threshold: 0.0,
+            full: 1.0,
+            max: 0.0,
+            response: GlitchResponse::Linear,
+        }
+    }
+}
+
+impl GlitchSpeedMapping {
+    /// The intensity added at `speed`.
+    pub fn apply(&self, speed: f32) -> f32 {
+        let x = (speed - self.threshold) / (self.full - self.threshold).max(f32::EPSILON);
+        self.response.apply(x) * self.max
+    }
+}
+
+/// Adds to the intensity of a camera from how fast it moves and turns, e.g.
+/// for a dash or teleport ability, as a [`GlitchModulator`].
+///
+/// The speeds are measured from the camera's [`GlobalTransform`] from frame
+/// to frame, in world units and radians per second of virtual time, and
+/// smoothed. The driver adds `baseline` plus what the `linear` and `angular`
+/// mappings add for them to the intensity of the camera's
+/// [`VideoGlitchSettings`]. 2D cameras turn about the z axis, and
+/// their world units are usually pixels.
+///
+/// A frame moving the camera further than `max_distance` or turning it
+/// further than `max_turn` is taken as a cut, e.g. a snap behind a loading
+/// screen, and measured as standing still. Set the camera's own intensity to
+/// 0 to have the driver own it.
+///
+/// [`VideoGlitchSettings`]: crate::VideoGlitchSettings
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component, Default)]
+pub struct GlitchFromMotion {
+    /// The intensity added for the speed, in world units per second.
+    pub linear: GlitchSpeedMapping,
+    /// The intensity added for the turning speed, in radians per second.
+    pub angular: GlitchSpeedMapping,
+    /// The intensity added at rest.
+    pub baseline: f32,
+    /// How quickly the speeds come through, in seconds to cover about two
+    /// thirds of a change. 0 uses each frame's speeds as they are.
+    pub smoothing: f32,
+    /// The furthest distance a single frame may move the camera before it
+    /// counts as a cut. Unlimited by default.
+    pub max_distance: f32,
+    /// The largest angle, in radians, a single frame may turn the camera
+    /// before it counts as a cut. Unlimited by default.
+    pub max_turn: f32,
+    #[reflect(ignore)]
+    previous: Option<(Vec3, Quat)>,
+    speed: f32,
+    turn_speed: f32,
+}
+
+impl Default for GlitchFromMotion {
+    fn default() -> Self {
+        Self {
+            linear: GlitchSpeedMapping {
+                threshold: 2.0,
+                full: 20.0,
+                max: 1.0,
+                response: GlitchResponse::Linear,
+            },
+            angular: GlitchSpeedMapping {
+                threshold: 1.0,
+                full: 8.0,
+                max: 1.0,
+                response: GlitchResponse::Linear,
+            },
+            baseline: 0.0,
+            smoothing: 0.1,
+            max_distance: f32::INFINITY,
+            max_turn: f32::INFINITY,
+            previous: None,
+            speed: 0.0,
+            turn_speed: 0.0,
+        }
+    }
+}
+
+impl GlitchFromMotion {
+    /// These settings adding `baseline` to the intensity at rest.
+    pub fn with_baseline(mut self, baseline: f32) -> Self {
+        self.baseline = baseline;
+        self
+    }
+
+    /// These settings taking a frame moving the camera further than
+    /// `max_distance` as a cut.
+    pub fn with_max_distance(mut self, max_distance: f32) -> Self {
+        self.max_distance = max_distance;
+        self
+    }
+
+    /// The smoothed speed, in world units per second.
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// The smoothed turning speed, in radians per second.
+    pub fn turn_speed(&self) -> f32 {
+        self.turn_speed
+    }
+
+    /// The intensity added for the current speeds.
+    pub fn intensity(&self) -> f32 {
+        self.baseline + self.linear.apply(self.speed) + self.angular.apply(self.turn_speed)
+    }
+
+    fn advance(&mut self, transform: &GlobalTransform, delta: f32) {
+        let (translation, rotation) = (transform.translation(), transform.rotation());
+        let Some((previous_translation, previous_rotation)) =
+            self.previous.replace((translation, rotation))
+        else {
+            return;
+        };
+        // Nothing moves while virtual time is paused.
+        if delta <= 0.0 {
+            return;
+        }
+
+        let distance = translation.distance(previous_translation);
+        let turn = rotation.angle_between(previous_rotation);
+        let (speed, turn_speed) = if distance > self.max_distance || turn > self.max_turn {
+            (0.0, 0.0)
+        } else {
+            (distance / delta, turn / delta)
+        };
+        // Exponential smoothing, which takes the same time to settle however
+        // the time is split into frames.
+        let blend = if self.smoothing > 0.0 {
+            1.0 - (-delta / self.smoothing).exp()
+        } else {
+            1.0
+        };
+        self.speed += (speed - self.speed) * blend;
+        self.turn_speed += (turn_speed - self.turn_speed) * blend;
+    }
+}
+
+impl GlitchModulator for GlitchFromMotion {
+    fn modulation(&self) -> Option<GlitchModulation> {
+        Some(GlitchModulation::Offset(self.intensity()))
+    }
+}
+
+pub(crate) fn drive_glitch_from_motion(
+    time: Res<Time>,
+    mut cameras: Query<(&GlobalTransform, &mut GlitchFromMotion)>,
+) {
+    for (transform, mut motion) in &mut cameras {
+        motion.advance(transform, time.delta_secs());
+    }
+}
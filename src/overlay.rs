@@ -0,0 +1,222 @@
+//! Glitch a camera's output without touching its render graph, by drawing it
+//! through a second camera.
+use bevy::{
+    core_pipeline::tonemapping::{DebandDither, Tonemapping},
+    prelude::*,
+    render::{
+        camera::{RenderTarget, ScalingMode},
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+        view::RenderLayers,
+    },
+};
+
+use crate::{NoVideoGlitch, VideoGlitchSettings, OVERLAY_FIRST_RENDER_LAYER};
+
+/// A camera drawing another camera's output through the effect, see
+/// [`spawn_glitch_overlay`].
+#[derive(Component, Debug)]
+pub struct GlitchOverlay {
+    source: Entity,
+    image: Handle<Image>,
+    layer: usize,
+    // The source camera's target before the overlay took it over.
+    previous_target: RenderTarget,
+    // Whether the settings were taken from the source camera, to give them
+    // back, and whether it was kept from the plugin's default settings.
+    took_settings: bool,
+    added_no_glitch: bool,
+}
+
+impl GlitchOverlay {
+    /// The render layer of the first overlay's image. Each overlay draws its
+    /// image on a layer of its own from here on, which other cameras don't
+    /// see unless told to.
+    pub const FIRST_RENDER_LAYER: usize = OVERLAY_FIRST_RENDER_LAYER;
+
+    /// The camera whose output is drawn.
+    pub fn source(&self) -> Entity {
+        self.source
+    }
+
+    /// The image the source camera renders into.
+    pub fn image(&self) -> &Handle<Image> {
+        &self.image
+    }
+}
+
+/// Show `source_camera`'s output through the effect without adding the effect
+/// to its render graph, e.g. when another post processing crate owns the
+/// order of its nodes. Returns the overlay camera.
+///
+/// The source camera is set to render into an image, which a second camera
+/// draws to the source's original target, filling it, with the effect. The
+/// image is kept at the size of that target as it is resized. The overlay
+/// camera takes the source's [`VideoGlitchSettings`], or the defaults without
+/// any; the settings and every other glitch component, e.g. a
+/// [`GlitchClock`], go on the returned entity from then on. The source gets
+/// [`NoVideoGlitch`] meanwhile.
+///
+/// The overlay camera renders with an order one above the source's and
+/// doesn't tonemap, so the source's colors are shown as they are.
+/// [`despawn_glitch_overlay`] puts everything back.
+///
+/// [`GlitchClock`]: crate::GlitchClock
+pub fn spawn_glitch_overlay(commands: &mut Commands, source_camera: Entity) -> Entity {
+    let overlay = commands.spawn_empty().id();
+    commands.queue(move |world: &mut World| setup_glitch_overlay(world, source_camera, overlay));
+    overlay
+}
+
+/// Stop drawing a camera through an overlay from [`spawn_glitch_overlay`]:
+/// the source camera renders to its original target again and gets the
+/// overlay's settings back if it had settings, and the overlay is despawned.
+pub fn despawn_glitch_overlay(commands: &mut Commands, overlay: Entity) {
+    commands.queue(move |world: &mut World| teardown_glitch_overlay(world, overlay));
+}
+
+fn overlay_image(size: UVec2) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: size.x.max(1),
+            height: size.y.max(1),
+            ..default()
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage =
+        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+    image
+}
+
+fn setup_glitch_overlay(world: &mut World, source: Entity, overlay: Entity) {
+    let Some(camera) = world.get::<Camera>(source) else {
+        warn!("Cannot draw {source} through a glitch overlay, it is not a camera");
+        if let Ok(overlay) = world.get_entity_mut(overlay) {
+            overlay.despawn_recursive();
+        }
+        return;
+    };
+    let order = camera.order;
+    let previous_target = camera.target.clone();
+    // Unknown until the camera has been updated once, the image is resized
+    // then.
+    let size = camera.physical_target_size().unwrap_or(UVec2::ONE);
+
+    let used: Vec<usize> = world
+        .query::<&GlitchOverlay>()
+        .iter(world)
+        .map(|overlay| overlay.layer)
+        .collect();
+    let layer = (GlitchOverlay::FIRST_RENDER_LAYER..)
+        .find(|layer| !used.contains(layer))
+        .unwrap();
+
+    let image = world
+        .resource_mut::<Assets<Image>>()
+        .add(overlay_image(size));
+    let mut source_camera = world.entity_mut(source);
+    let settings = source_camera.take::<VideoGlitchSettings>();
+    // The overlay glitches the image, the source camera must not as well.
+    let added_no_glitch = !source_camera.contains::<NoVideoGlitch>();
+    source_camera.insert(NoVideoGlitch);
+    source_camera.get_mut::<Camera>().unwrap().target = RenderTarget::Image(image.clone());
+
+    let Ok(mut overlay) = world.get_entity_mut(overlay) else {
+        // Despawned before it was set up.
+        return;
+    };
+    let layers = RenderLayers::layer(layer);
+    overlay
+        .insert((
+            GlitchOverlay {
+                source,
+                image: image.clone(),
+                layer,
+                previous_target: previous_target.clone(),
+                took_settings: settings.is_some(),
+                added_no_glitch,
+            },
+            Camera2d,
+            Camera {
+                order: order + 1,
+                target: previous_target,
+                clear_color: ClearColorConfig::Custom(Color::NONE),
+                ..default()
+            },
+            // The image fills the viewport whatever its size.
+            OrthographicProjection {
+                scaling_mode: ScalingMode::Fixed {
+                    width: 1.0,
+                    height: 1.0,
+                },
+                ..OrthographicProjection::default_2d()
+            },
+            // Leave the colors of the source as they are.
+            Tonemapping::None,
+            DebandDither::Disabled,
+            settings.unwrap_or_default(),
+            layers.clone(),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Sprite {
+                    image,
+                    custom_size: Some(Vec2::ONE),
+                    ..default()
+                },
+                layers,
+            ));
+        });
+}
+
+fn teardown_glitch_overlay(world: &mut World, overlay: Entity) {
+    let Ok(mut entity) = world.get_entity_mut(overlay) else {
+        return;
+    };
+    let Some(glitch_overlay) = entity.take::<GlitchOverlay>() else {
+        return;
+    };
+    let settings = entity.take::<VideoGlitchSettings>();
+    entity.despawn_recursive();
+
+    let Ok(mut source) = world.get_entity_mut(glitch_overlay.source) else {
+        return;
+    };
+    if let Some(mut camera) = source.get_mut::<Camera>() {
+        camera.target = glitch_overlay.previous_target;
+    }
+    if let (true, Some(settings)) = (glitch_overlay.took_settings, settings) {
+        source.insert(settings);
+    }
+    if glitch_overlay.added_no_glitch {
+        source.remove::<NoVideoGlitch>();
+    }
+}
+
+/// Keep the images of the overlays at the size of their targets.
+pub(crate) fn resize_glitch_overlays(
+    mut images: ResMut<Assets<Image>>,
+    overlays: Query<(&GlitchOverlay, &Camera)>,
+) {
+    for (overlay, camera) in &overlays {
+        let Some(size) = camera.physical_target_size() else {
+            continue;
+        };
+        let size = size.max(UVec2::ONE);
+        // Only touch the image when its size changes, which reallocates it.
+        if images
+            .get(&overlay.image)
+            .is_some_and(|image| image.size() != size)
+        {
+            images.get_mut(&overlay.image).unwrap().resize(Extent3d {
+                width: size.x,
+                height: size.y,
+                ..default()
+            });
+        }
+    }
+}
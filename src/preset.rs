@@ -0,0 +1,180 @@
+//! Saving and loading [`VideoGlitchSettings`] as RON presets.
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::{Path, PathBuf};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+use thiserror::Error;
+
+use crate::VideoGlitchSettings;
+
+/// A [`VideoGlitchSettings`] preset loaded from a `.glitch.ron` file.
+#[derive(Asset, TypePath, Debug, Clone, Deref)]
+pub struct GlitchPreset(pub VideoGlitchSettings);
+
+/// Errors from reading or writing presets.
+#[derive(Debug, Error)]
+pub enum GlitchPresetError {
+    /// The file could not be read or written.
+    #[error("could not access the preset file: {0}")]
+    Io(#[from] std::io::Error),
+    /// The file isn't a valid preset.
+    #[error("could not parse the preset: {0}")]
+    Parse(#[from] ron::de::SpannedError),
+    /// The settings could not be encoded.
+    #[error("could not encode the preset: {0}")]
+    Encode(#[from] ron::Error),
+}
+
+/// Loads [`GlitchPreset`]s from `.glitch.ron` files.
+#[derive(Default)]
+pub struct GlitchPresetLoader;
+
+impl AssetLoader for GlitchPresetLoader {
+    type Asset = GlitchPreset;
+    type Settings = ();
+    type Error = GlitchPresetError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(GlitchPreset(ron::de::from_bytes(&bytes)?))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["glitch.ron"]
+    }
+}
+
+impl VideoGlitchSettings {
+    /// Encode these settings as a RON preset.
+    pub fn to_ron(&self) -> Result<String, GlitchPresetError> {
+        Ok(ron::ser::to_string_pretty(
+            self,
+            ron::ser::PrettyConfig::default(),
+        )?)
+    }
+
+    /// Decode settings from a RON preset.
+    pub fn from_ron(ron: &str) -> Result<Self, GlitchPresetError> {
+        Ok(ron::de::from_str(ron)?)
+    }
+
+    /// Write these settings to a preset file at `path`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save_ron(&self, path: impl AsRef<Path>) -> Result<(), GlitchPresetError> {
+        std::fs::write(path, self.to_ron()?)?;
+        Ok(())
+    }
+
+    /// Read settings from the preset file at `path`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_ron(path: impl AsRef<Path>) -> Result<Self, GlitchPresetError> {
+        Self::from_ron(&std::fs::read_to_string(path)?)
+    }
+}
+
+/// Send this event to save the [`VideoGlitchSettings`] of a camera to a
+/// preset file, e.g. after tuning them live in an inspector.
+///
+/// Failures are logged. Use a `.glitch.ron` extension to load the file back
+/// as a [`GlitchPreset`].
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Event, Debug, Clone)]
+pub struct SaveGlitchPreset(pub Entity, pub PathBuf);
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn save_glitch_presets(
+    mut events: EventReader<SaveGlitchPreset>,
+    settings: Query<&VideoGlitchSettings>,
+) {
+    for SaveGlitchPreset(camera, path) in events.read() {
+        let Ok(settings) = settings.get(*camera) else {
+            warn!("Cannot save glitch preset: {camera:?} has no VideoGlitchSettings");
+            continue;
+        };
+        match settings.save_ron(path) {
+            Ok(()) => info!("Saved glitch preset to {}", path.display()),
+            Err(err) => error!("Cannot save glitch preset to {}: {err}", path.display()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ColorSettings, DepthSettings, DisplacementSettings, GlitchColorSpace, GlitchNoiseMode,
+        GlitchResponse, OverlaySettings,
+    };
+
+    use super::*;
+
+    #[test]
+    fn round_trips_through_ron() {
+        let settings = VideoGlitchSettings {
+            intensity: 0.7,
+            response: GlitchResponse::Power(2.5),
+            displacement: DisplacementSettings {
+                amount: 0.4,
+                speed: 3.0,
+                pixel_snap: true,
+                antialias: 3,
+                noise_mode: GlitchNoiseMode::Cell,
+            },
+            color: ColorSettings {
+                aberration: Mat3::from_cols(Vec3::Y, Vec3::Z, Vec3::X),
+                aberration_offset: 0.1,
+                aberration_cycle_speed: 1.5,
+                aberration_scope: 0.25,
+                aberration_angle: 1.0,
+            },
+            overlay: OverlaySettings {
+                interference: 0.6,
+                scanlines: 0.05,
+                color_space: GlitchColorSpace::Perceptual,
+            },
+            depth: DepthSettings {
+                near: 2.0,
+                far: 30.0,
+                invert: true,
+            },
+            effect_fps: 12.0,
+        };
+        let ron = settings.to_ron().unwrap();
+        assert_eq!(VideoGlitchSettings::from_ron(&ron).unwrap(), settings);
+    }
+
+    #[test]
+    fn fields_added_later_default() {
+        let mut settings = VideoGlitchSettings::from_ron(
+            "(
+                intensity: 0.5,
+                displacement: (amount: 0.25, speed: 2.0),
+                color: (
+                    aberration: (1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0),
+                    aberration_offset: 0.05,
+                ),
+                overlay: (interference: 0.3, scanlines: 0.15),
+            )",
+        )
+        .unwrap();
+        assert_eq!(settings.intensity, 0.5);
+        settings.intensity = 1.0;
+        assert_eq!(settings, VideoGlitchSettings::default());
+    }
+
+    #[test]
+    fn reports_invalid_presets() {
+        assert!(matches!(
+            VideoGlitchSettings::from_ron("(intensity: \"high\")"),
+            Err(GlitchPresetError::Parse(_))
+        ));
+    }
+}
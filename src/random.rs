@@ -0,0 +1,263 @@
+//! Generate varied but plausible settings, e.g. for procedural channels of
+//! corrupted TV.
+use std::ops::Range;
+
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{aberration::CHANNEL_PERMUTATIONS, GlitchColorSpace, VideoGlitchSettings};
+
+/// The family of looks [`VideoGlitchSettings::random`] draws from.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GlitchFlavor {
+    /// Worn tape and weak reception: soft tears, wide color fringes,
+    /// interference and scanlines, mixed in perceptual space.
+    Analog,
+    /// A corrupted stream: hard, pixel snapped tears at a low frame rate and
+    /// swapped color channels, with little interference.
+    Digital,
+    /// A hint of trouble, light enough to leave on during play.
+    Mild,
+    /// Everything at once, for moments the picture should fall apart.
+    Extreme,
+}
+
+/// The fields of the settings [`VideoGlitchSettings::randomize_in_place`]
+/// changes.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Intensity,
+    Amount,
+    Speed,
+    PixelSnap,
+    EffectFps,
+    Aberration,
+    AberrationOffset,
+    Interference,
+    Scanlines,
+}
+
+const ALL_FIELDS: [Field; 9] = [
+    Field::Intensity,
+    Field::Amount,
+    Field::Speed,
+    Field::PixelSnap,
+    Field::EffectFps,
+    Field::Aberration,
+    Field::AberrationOffset,
+    Field::Interference,
+    Field::Scanlines,
+];
+
+// The ranges each flavor samples from. Every range lies within the valid
+// values of its field.
+struct FlavorRanges {
+    intensity: Range<f32>,
+    amount: Range<f32>,
+    speed: Range<f32>,
+    // The chance of snapping to whole pixels.
+    pixel_snap: f64,
+    // Picked from evenly, 0 for continuous time.
+    effect_fps: &'static [f32],
+    // How far the aberration matrix moves from the identity towards a
+    // permutation of the channels.
+    aberration_mix: Range<f32>,
+    aberration_offset: Range<f32>,
+    interference: Range<f32>,
+    scanlines: Range<f32>,
+    color_space: GlitchColorSpace,
+    // The fields that make the flavor, which `randomize_in_place` changes.
+    signature: &'static [Field],
+}
+
+impl GlitchFlavor {
+    fn ranges(self) -> FlavorRanges {
+        use Field::*;
+        match self {
+            Self::Analog => FlavorRanges {
+                intensity: 0.2..0.6,
+                amount: 0.05..0.2,
+                speed: 0.5..2.0,
+                pixel_snap: 0.0,
+                effect_fps: &[0.0, 25.0, 30.0],
+                aberration_mix: 0.0..0.3,
+                aberration_offset: 0.03..0.08,
+                interference: 0.2..0.5,
+                scanlines: 0.1..0.3,
+                color_space: GlitchColorSpace::Perceptual,
+                signature: &[Speed, AberrationOffset, Interference, Scanlines],
+            },
+            Self::Digital => FlavorRanges {
+                intensity: 0.3..0.8,
+                amount: 0.15..0.4,
+                speed: 2.0..8.0,
+                pixel_snap: 0.7,
+                effect_fps: &[12.0, 15.0, 24.0, 30.0],
+                aberration_mix: 0.3..1.0,
+                aberration_offset: 0.01..0.04,
+                interference: 0.0..0.15,
+                scanlines: 0.0..0.1,
+                color_space: GlitchColorSpace::Linear,
+                signature: &[Amount, Speed, PixelSnap, EffectFps, Aberration],
+            },
+            Self::Mild => FlavorRanges {
+                intensity: 0.05..0.25,
+                amount: 0.02..0.1,
+                speed: 0.5..1.5,
+                pixel_snap: 0.0,
+                effect_fps: &[0.0],
+                aberration_mix: 0.0..0.2,
+                aberration_offset: 0.005..0.02,
+                interference: 0.0..0.1,
+                scanlines: 0.0..0.1,
+                color_space: GlitchColorSpace::Linear,
+                signature: &[Intensity, Amount],
+            },
+            Self::Extreme => FlavorRanges {
+                intensity: 0.7..1.0,
+                amount: 0.3..0.6,
+                speed: 3.0..10.0,
+                pixel_snap: 0.3,
+                effect_fps: &[0.0, 0.0, 15.0, 30.0],
+                aberration_mix: 0.2..1.0,
+                aberration_offset: 0.05..0.12,
+                interference: 0.4..0.8,
+                scanlines: 0.2..0.5,
+                color_space: GlitchColorSpace::Perceptual,
+                signature: &ALL_FIELDS,
+            },
+        }
+    }
+}
+
+impl VideoGlitchSettings {
+    /// Random settings of the given `flavor`, different every call but always
+    /// valid: every value lies in its range and the aberration matrix is
+    /// doubly stochastic.
+    ///
+    /// The response curve, depth fade and antialiasing keep their defaults.
+    pub fn random(rng: &mut impl Rng, flavor: GlitchFlavor) -> Self {
+        let mut settings = Self::default();
+        let ranges = flavor.ranges();
+        settings.overlay.color_space = ranges.color_space;
+        for field in ALL_FIELDS {
+            settings.sample(rng, &ranges, field);
+        }
+        settings
+    }
+
+    /// Sample again only the fields that make `flavor`, e.g. the overlay of
+    /// [`GlitchFlavor::Analog`], and leave the rest as they are, to vary
+    /// settings without losing their character.
+    pub fn randomize_in_place(&mut self, rng: &mut impl Rng, flavor: GlitchFlavor) {
+        let ranges = flavor.ranges();
+        for field in ranges.signature {
+            self.sample(rng, &ranges, *field);
+        }
+    }
+
+    fn sample(&mut self, rng: &mut impl Rng, ranges: &FlavorRanges, field: Field) {
+        match field {
+            Field::Intensity => self.intensity = rng.gen_range(ranges.intensity.clone()),
+            Field::Amount => self.displacement.amount = rng.gen_range(ranges.amount.clone()),
+            Field::Speed => self.displacement.speed = rng.gen_range(ranges.speed.clone()),
+            Field::PixelSnap => self.displacement.pixel_snap = rng.gen_bool(ranges.pixel_snap),
+            Field::EffectFps => {
+                self.effect_fps = *ranges.effect_fps.choose(rng).unwrap_or(&0.0);
+            }
+            Field::Aberration => {
+                // A mix of doubly stochastic matrices is doubly stochastic.
+                let mix = rng.gen_range(ranges.aberration_mix.clone());
+                let permutation = *CHANNEL_PERMUTATIONS
+                    .choose(rng)
+                    .expect("there are permutations");
+                self.color.aberration = Mat3::IDENTITY * (1.0 - mix) + permutation * mix;
+            }
+            Field::AberrationOffset => {
+                self.color.aberration_offset = rng.gen_range(ranges.aberration_offset.clone());
+            }
+            Field::Interference => {
+                self.overlay.interference = rng.gen_range(ranges.interference.clone());
+            }
+            Field::Scanlines => self.overlay.scanlines = rng.gen_range(ranges.scanlines.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    const FLAVORS: [GlitchFlavor; 4] = [
+        GlitchFlavor::Analog,
+        GlitchFlavor::Digital,
+        GlitchFlavor::Mild,
+        GlitchFlavor::Extreme,
+    ];
+
+    // Panic unless every value of `settings` lies in its range and the
+    // aberration matrix is doubly stochastic.
+    fn validate(settings: &VideoGlitchSettings) {
+        let unit = 0.0..=1.0;
+        assert!(unit.contains(&settings.intensity), "{settings:?}");
+        assert!(
+            unit.contains(&settings.overlay.interference),
+            "{settings:?}"
+        );
+        assert!(unit.contains(&settings.overlay.scanlines), "{settings:?}");
+        assert!(settings.displacement.amount >= 0.0, "{settings:?}");
+        assert!(settings.displacement.speed >= 0.0, "{settings:?}");
+        assert!(settings.color.aberration_offset >= 0.0, "{settings:?}");
+        assert!(settings.effect_fps >= 0.0, "{settings:?}");
+
+        let matrix = settings.color.aberration;
+        for i in 0..3 {
+            let column = matrix.col(i);
+            let row = matrix.row(i);
+            assert!((column.element_sum() - 1.0).abs() < 1e-5, "{matrix}");
+            assert!((row.element_sum() - 1.0).abs() < 1e-5, "{matrix}");
+            assert!(column.cmpge(Vec3::ZERO).all(), "{matrix}");
+        }
+    }
+
+    #[test]
+    fn random_settings_are_valid() {
+        let mut rng = StdRng::seed_from_u64(0x611c);
+        for flavor in FLAVORS {
+            for _ in 0..256 {
+                validate(&VideoGlitchSettings::random(&mut rng, flavor));
+            }
+        }
+    }
+
+    #[test]
+    fn randomizing_in_place_stays_valid_and_keeps_the_rest() {
+        let mut rng = StdRng::seed_from_u64(0x611c);
+        for flavor in FLAVORS {
+            let mut settings = VideoGlitchSettings::random(&mut rng, GlitchFlavor::Mild);
+            for _ in 0..64 {
+                let before = settings;
+                settings.randomize_in_place(&mut rng, flavor);
+                validate(&settings);
+                // The overlay's color space isn't part of any signature.
+                assert_eq!(settings.overlay.color_space, before.overlay.color_space);
+                if flavor == GlitchFlavor::Mild {
+                    assert_eq!(settings.overlay, before.overlay);
+                    assert_eq!(settings.color, before.color);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn the_same_seed_gives_the_same_settings() {
+        for flavor in FLAVORS {
+            assert_eq!(
+                VideoGlitchSettings::random(&mut StdRng::seed_from_u64(7), flavor),
+                VideoGlitchSettings::random(&mut StdRng::seed_from_u64(7), flavor)
+            );
+        }
+    }
+}
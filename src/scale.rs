@@ -0,0 +1,122 @@
+//! Run the effect at a fraction of a camera's resolution and upscale the
+//! result.
+use bevy::{
+    core_pipeline::blit::{BlitPipeline, BlitPipelineKey},
+    prelude::*,
+    render::{
+        extract_component::ExtractComponent,
+        render_resource::{
+            BindGroup, BindGroupEntries, CachedRenderPipelineId, Extent3d, PipelineCache,
+            SpecializedRenderPipelines, TextureDescriptor, TextureDimension, TextureUsages,
+        },
+        renderer::RenderDevice,
+        texture::{CachedTexture, TextureCache},
+        view::ViewTarget,
+    },
+};
+
+use crate::VideoGlitchConfig;
+
+/// Runs the effect for a camera at a fraction of its resolution.
+///
+/// The effect is drawn into a smaller texture and then stretched back over the
+/// camera's output, which makes the pass several times cheaper on large
+/// targets. The glitched image is degraded on purpose, so the lost detail
+/// rarely shows. Values of 1 and above, and cameras without this component,
+/// run at full resolution.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, ExtractComponent)]
+#[reflect(Component, Default)]
+pub struct VideoGlitchScale(pub f32);
+
+impl VideoGlitchScale {
+    /// Half the width and height of the camera's output.
+    pub const HALF: Self = Self(0.5);
+    /// A quarter of the width and height of the camera's output.
+    pub const QUARTER: Self = Self(0.25);
+
+    pub(crate) fn is_scaled(&self) -> bool {
+        self.0 < 1.0
+    }
+}
+
+impl Default for VideoGlitchScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// The intermediate texture of a view running the effect below full
+/// resolution, and what's needed to stretch it back.
+#[derive(Component)]
+pub(crate) struct ScaledGlitchTarget {
+    pub(crate) texture: CachedTexture,
+    pub(crate) upscale_bind_group: BindGroup,
+    pub(crate) upscale_pipeline: CachedRenderPipelineId,
+    // Copies the parts of the main texture outside of the camera's viewport,
+    // which the upscale must leave alone.
+    pub(crate) pass_through_pipeline: CachedRenderPipelineId,
+}
+
+pub(crate) fn prepare_scaled_glitch_targets(
+    mut commands: Commands,
+    mut texture_cache: ResMut<TextureCache>,
+    render_device: Res<RenderDevice>,
+    pipeline_cache: Res<PipelineCache>,
+    blit_pipeline: Res<BlitPipeline>,
+    mut blit_pipelines: ResMut<SpecializedRenderPipelines<BlitPipeline>>,
+    config: Res<VideoGlitchConfig>,
+    views: Query<(Entity, &ViewTarget, &VideoGlitchScale)>,
+) {
+    for (entity, view_target, scale) in &views {
+        // The split passes always run at full resolution.
+        if !scale.is_scaled() || config.is_split() {
+            continue;
+        }
+
+        // The texture cache hands the same texture back every frame for as
+        // long as the size stays the same, and frees it once it goes unused.
+        let size = view_target.main_texture().size();
+        let format = view_target.main_texture_format();
+        let texture = texture_cache.get(
+            &render_device,
+            TextureDescriptor {
+                label: Some("video_glitch_scaled_texture"),
+                size: Extent3d {
+                    width: ((size.width as f32 * scale.0) as u32).max(1),
+                    height: ((size.height as f32 * scale.0) as u32).max(1),
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format,
+                usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+        );
+
+        let upscale_bind_group = render_device.create_bind_group(
+            "video_glitch_upscale_bind_group",
+            &blit_pipeline.texture_bind_group,
+            &BindGroupEntries::sequential((&texture.default_view, &blit_pipeline.sampler)),
+        );
+        let mut blit = |blend_state| {
+            blit_pipelines.specialize(
+                &pipeline_cache,
+                &blit_pipeline,
+                BlitPipelineKey {
+                    texture_format: format,
+                    blend_state,
+                    samples: 1,
+                },
+            )
+        };
+        commands.entity(entity).insert(ScaledGlitchTarget {
+            texture,
+            upscale_bind_group,
+            // The effect is blended into the output as it is upscaled.
+            upscale_pipeline: blit(config.blend),
+            pass_through_pipeline: blit(None),
+        });
+    }
+}
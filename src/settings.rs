@@ -0,0 +1,1032 @@
+//! The settings controlling the effect and their GPU representation.
+use bevy::{
+    ecs::query::QueryItem,
+    prelude::*,
+    render::{extract_component::ExtractComponent, render_resource::ShaderType},
+};
+
+#[cfg(doc)]
+use crate::ColorAberration;
+use crate::{
+    hotspot::GlitchHotspotsInView, modulation::GlitchModulations, volume::GlitchVolumesAround,
+    GlitchClock, GlitchHit, VideoGlitchLayers, MAX_GLITCH_HOTSPOTS,
+};
+
+/// Add this component to a camera to apply the effect to it.
+///
+/// It requires a [`Camera`], so it belongs next to a [`Camera2d`] or
+/// [`Camera3d`]; on its own it makes a camera without a render graph, which
+/// draws nothing.
+///
+/// The settings are grouped by the part of the effect they control. Moving
+/// from 0.2, `color_aberration` is now [`ColorSettings::aberration`] at
+/// `settings.color.aberration`; `intensity` stays where it was.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "presets", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component, Default)]
+#[require(Camera)]
+pub struct VideoGlitchSettings {
+    /// Set the intensity of this glitch effect from [0, 1]. By default it has a
+    /// value of 1. At 0 the camera's pass is skipped altogether, so leaving the
+    /// component on an idle camera costs nothing on the GPU.
+    pub intensity: f32,
+    /// How `intensity` maps to the strength of the effect. Linear by default.
+    #[cfg_attr(feature = "presets", serde(default))]
+    pub response: GlitchResponse,
+    /// How the rows of the image are torn.
+    pub displacement: DisplacementSettings,
+    /// How colors separate along the tears.
+    pub color: ColorSettings,
+    /// Interference and scanlines drawn over the image.
+    pub overlay: OverlaySettings,
+    /// How the effect fades with the distance from the camera.
+    #[cfg_attr(feature = "presets", serde(default))]
+    pub depth: DepthSettings,
+    /// Hold the effect's pattern for each frame at this frame rate, e.g. 30
+    /// for the chunky cadence of video artifacts on a 144 Hz display. The time
+    /// driving the effect, after a [`GlitchClock`] or pause has scaled it, is
+    /// held to the start of each frame. 0, the default, animates continuously.
+    #[cfg_attr(feature = "presets", serde(default))]
+    pub effect_fps: f32,
+}
+
+impl Default for VideoGlitchSettings {
+    fn default() -> Self {
+        Self {
+            intensity: 1.0,
+            response: default(),
+            displacement: default(),
+            color: default(),
+            overlay: default(),
+            depth: default(),
+            effect_fps: 0.0,
+        }
+    }
+}
+
+/// How [`VideoGlitchSettings::intensity`] maps to the strength of the effect.
+///
+/// The curve is applied to the camera's final intensity, after combining its
+/// [`VideoGlitchLayers`]. The same sweep with each curve:
+///
+/// | intensity     | 0.25 | 0.5  | 0.75 |
+/// |---------------|------|------|------|
+/// | `Linear`      | 0.25 | 0.5  | 0.75 |
+/// | `Smoothstep`  | 0.16 | 0.5  | 0.84 |
+/// | `Exponential` | 0.03 | 0.12 | 0.36 |
+/// | `Power(0.5)`  | 0.5  | 0.71 | 0.87 |
+#[derive(Reflect, Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "presets", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Default)]
+pub enum GlitchResponse {
+    /// The intensity as it is.
+    #[default]
+    Linear,
+    /// Eases in and out, keeping the ends of the range calmer.
+    Smoothstep,
+    /// Stays subtle for most of the range and ramps up near the top.
+    Exponential,
+    /// `intensity.powf(exponent)`. Exponents below 1 make low intensities
+    /// more visible, above 1 less.
+    Power(f32),
+}
+
+impl GlitchResponse {
+    /// Map an intensity in [0, 1] through the curve.
+    pub fn apply(&self, intensity: f32) -> f32 {
+        let x = intensity.clamp(0.0, 1.0);
+        match *self {
+            Self::Linear => x,
+            Self::Smoothstep => x * x * (3.0 - 2.0 * x),
+            Self::Exponential => (4.0 * x).exp_m1() / 4.0f32.exp_m1(),
+            Self::Power(exponent) => x.powf(exponent),
+        }
+    }
+}
+
+/// How the rows of the image are torn.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "presets", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Default)]
+pub struct DisplacementSettings {
+    /// How far rows are shifted horizontally, in UV units at full noise. By
+    /// default it has a value of 0.25.
+    pub amount: f32,
+    /// How fast the noise driving the tears changes. By default it has a value
+    /// of 2.
+    pub speed: f32,
+    /// Shift rows and colors by whole pixels of the camera's target only, and
+    /// make every pixel row one noise cell, so low resolution pixel art stays
+    /// crisp. Off by default.
+    #[cfg_attr(feature = "presets", serde(default))]
+    pub pixel_snap: bool,
+    /// Soften the edges of the tears with this many subpixel samples, from 2
+    /// to 4, where they shimmer once the output is scaled. 0, the default,
+    /// turns it off and costs nothing. [`VideoGlitchMode::Fast`] ignores it.
+    ///
+    /// [`VideoGlitchMode::Fast`]: crate::VideoGlitchMode::Fast
+    #[cfg_attr(feature = "presets", serde(default))]
+    pub antialias: u32,
+    /// The noise the tears are made of. [`GlitchNoiseMode::Hash`] by default.
+    #[cfg_attr(feature = "presets", serde(default))]
+    pub noise_mode: GlitchNoiseMode,
+}
+
+impl Default for DisplacementSettings {
+    fn default() -> Self {
+        Self {
+            amount: 0.25,
+            speed: 2.0,
+            pixel_snap: false,
+            antialias: 0,
+            noise_mode: default(),
+        }
+    }
+}
+
+// The most subpixel samples per tear, one per jitter offset in the shader.
+pub(crate) const MAX_ANTIALIAS: u32 = 4;
+
+/// The character of the noise tearing the image, set in
+/// [`DisplacementSettings::noise_mode`].
+///
+/// Every mode covers about as much of the image at the same settings, so
+/// presets don't need retuning when it changes. Only the selected mode is
+/// compiled into a camera's shader.
+#[derive(Reflect, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "presets", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Default)]
+pub enum GlitchNoiseMode {
+    /// The effect's original noise: harsh, digital bands of torn rows.
+    #[default]
+    Hash,
+    /// Value noise along the rows, smoothly interpolated in time, for the
+    /// wobble of a worn tape.
+    Value,
+    /// Coarse blocks of the screen that each tear on their own and jump from
+    /// step to step, for chunky corruption of a broken stream.
+    Cell,
+}
+
+/// How colors separate along the tears.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "presets", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Default)]
+pub struct ColorSettings {
+    /// This shader uses a color aberration matrix C in the following way: The
+    /// first column `C[0] . color` selects the primary color, which is used to
+    /// mix the other two. In practice this means one will not see the primary
+    /// color in the color aberrations but will instead see traces of the
+    /// secondary colors: `C[1] . color` and `C[2] . color`.
+    ///
+    /// The default value is an identity matrix, which specifies red as the
+    /// primary color. Typically this matrix will be a doubly stochastic matrix
+    /// meaning the columns and rows each sum to 1.
+    ///
+    /// See [`ColorAberration`] for matrices that stay visible with color
+    /// vision deficiencies.
+    pub aberration: Mat3,
+    /// How far the secondary colors are shifted, in UV units at full noise. By
+    /// default it has a value of 0.05.
+    pub aberration_offset: f32,
+    /// Cycle which channel is primary through red, green and blue and back,
+    /// this many times a second, by smoothly rotating the channels of
+    /// `aberration`. A doubly stochastic matrix stays doubly stochastic while
+    /// it rotates, so the brightness doesn't pump. By default it has a value
+    /// of 0, which uses `aberration` exactly as it is.
+    #[cfg_attr(feature = "presets", serde(default))]
+    pub aberration_cycle_speed: f32,
+    /// Where colors separate, from 0, the default, along every row with noise
+    /// to 1 only on rows torn by at least a pixel, so untorn parts of the
+    /// image show no fringes and the aberration reads as damage to the signal
+    /// rather than a flaw of the lens. Whatever tears a row counts, hotspots
+    /// and hits included.
+    #[cfg_attr(feature = "presets", serde(default))]
+    pub aberration_scope: f32,
+    /// The direction the secondary colors are shifted in, in radians
+    /// counterclockwise from the right: vertical at a quarter turn, diagonal
+    /// in between. Animate it for swirling fringes. By default it has a value
+    /// of 0, which shifts them along the rows.
+    #[cfg_attr(feature = "presets", serde(default))]
+    pub aberration_angle: f32,
+}
+
+impl Default for ColorSettings {
+    fn default() -> Self {
+        Self {
+            aberration: Mat3::IDENTITY,
+            aberration_offset: 0.05,
+            aberration_cycle_speed: 0.0,
+            aberration_scope: 0.0,
+            aberration_angle: 0.0,
+        }
+    }
+}
+
+/// Interference and scanlines drawn over the image.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "presets", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Default)]
+pub struct OverlaySettings {
+    /// How much random interference is mixed into torn rows. By default it has
+    /// a value of 0.3.
+    pub interference: f32,
+    /// How much torn rows are darkened by the line pattern. By default it has
+    /// a value of 0.15.
+    pub scanlines: f32,
+    /// The color space the interference and scanlines are mixed in. Linear by
+    /// default.
+    #[cfg_attr(feature = "presets", serde(default))]
+    pub color_space: GlitchColorSpace,
+}
+
+impl Default for OverlaySettings {
+    fn default() -> Self {
+        Self {
+            interference: 0.3,
+            scanlines: 0.15,
+            color_space: default(),
+        }
+    }
+}
+
+/// The color space [`OverlaySettings`] are mixed in.
+///
+/// Bevy's main textures hold linear color whatever the camera, with sRGB
+/// formats for LDR cameras and float formats for HDR ones, so both behave the
+/// same here. In linear space interference nearly disappears in the shadows;
+/// analog noise, and most references of it, look like `Perceptual`.
+#[derive(Reflect, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "presets", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Default)]
+pub enum GlitchColorSpace {
+    /// Mix with the linear color as it is, as the effect always has.
+    #[default]
+    Linear,
+    /// Mix with gamma encoded color, which spreads the overlay evenly over
+    /// dark and bright parts of the image. Costs two conversions per pixel.
+    Perceptual,
+}
+
+/// How the effect fades with the distance from the camera, e.g. to dissolve
+/// distant scenery while nearby objects stay readable.
+///
+/// The intensity is multiplied by 0 at `near` and closer, and by 1 at `far`
+/// and beyond, ramping linearly in between. This reads the camera's depth
+/// prepass, so it needs a [`DepthPrepass`](bevy::core_pipeline::prepass::DepthPrepass)
+/// on a 3D camera with a perspective projection and without MSAA. Other
+/// cameras glitch evenly.
+#[derive(Reflect, Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "presets", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Default)]
+pub struct DepthSettings {
+    /// Where the effect starts, in world units from the camera. By default it
+    /// has a value of 0.
+    pub near: f32,
+    /// Where the effect reaches full intensity, in world units from the
+    /// camera. The effect only fades with depth when this is beyond `near`; by
+    /// default it has a value of 0, which leaves it off.
+    pub far: f32,
+    /// Fade the effect out with distance instead, so close objects glitch and
+    /// the distance stays clean. Off by default.
+    pub invert: bool,
+}
+
+/// The maximum number of [`GlitchExclusionRects`] per camera.
+pub const MAX_EXCLUSION_RECTS: usize = 4;
+
+/// Areas of a camera's viewport that stay un-glitched, e.g. to keep subtitles
+/// and critical HUD readouts legible.
+///
+/// The rects are in UV space, from (0, 0) at the top left to (1, 1) at the
+/// bottom right of the viewport, or of the full image for a camera with a
+/// [`SubCameraView`], so that tiles of it agree on where the rects are. The
+/// effect fades in over a small feathered border around each rect. Only the
+/// first [`MAX_EXCLUSION_RECTS`] rects are used.
+///
+/// [`SubCameraView`]: bevy::render::camera::SubCameraView
+#[derive(Component, Reflect, Debug, Clone, Default)]
+#[reflect(Component, Default)]
+pub struct GlitchExclusionRects(pub Vec<Rect>);
+
+pub(crate) fn warn_truncated_exclusion_rects(
+    rects: Query<(Entity, &GlitchExclusionRects), Changed<GlitchExclusionRects>>,
+) {
+    for (entity, rects) in &rects {
+        if rects.0.len() > MAX_EXCLUSION_RECTS {
+            warn!(
+                "{entity:?} has {} GlitchExclusionRects but only the first {MAX_EXCLUSION_RECTS} are used",
+                rects.0.len()
+            );
+        }
+    }
+}
+
+/// Drive a camera's effect from this time, in seconds, instead of bevy's
+/// elapsed time, e.g. to render footage frame by frame or to scrub through the
+/// effect in a tool.
+///
+/// The effect has no state carried between frames, so the same time always
+/// renders the same image, going backwards included.
+#[derive(Component, Reflect, Debug, Clone, Copy, Default)]
+#[reflect(Component, Default)]
+pub struct GlitchTimeOverride(pub f32);
+
+/// Show a camera's source untouched left of this viewport U coordinate, from
+/// 0 to 1, and the glitched image right of it, with a thin line between them,
+/// to compare the two while tuning or for side by side captures.
+///
+/// Nothing of the effect applies on the clean side. Remove the component to
+/// glitch the whole viewport again.
+#[derive(Component, Reflect, Debug, Clone, Copy, Default)]
+#[reflect(Component, Default)]
+pub struct GlitchComparisonSplit(pub f32);
+
+/// Sweep a boundary of corruption across a camera's viewport, e.g. as a scene
+/// transition: ahead of it the image is clean, behind it the effect applies
+/// at the camera's intensity, and optionally fades to a flat color.
+///
+/// The effect ramps in over `edge_width` behind the boundary, and the `fill`
+/// over as much again behind that. Tween `progress` from 0, where the whole
+/// viewport is untouched and the pass is skipped, to 1, where the boundary
+/// has left the viewport and all of it is glitched or filled.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component, Default)]
+pub struct GlitchWipe {
+    /// How far the wipe has swept, from 0 to 1.
+    pub progress: f32,
+    /// The direction the boundary moves in on screen, in radians
+    /// counterclockwise from the right. By default it has a value of 0, which
+    /// sweeps from left to right.
+    pub angle: f32,
+    /// The width of the ramp from clean to glitched, as a fraction of the
+    /// viewport height. By default it has a value of 0.25.
+    pub edge_width: f32,
+    /// The color the viewport fades to behind the glitch, for a transition to
+    /// another scene. Its alpha sets how opaque the fill gets. `None`, the
+    /// default, leaves the glitched image.
+    pub fill: Option<Color>,
+}
+
+impl Default for GlitchWipe {
+    fn default() -> Self {
+        Self {
+            progress: 0.0,
+            angle: 0.0,
+            edge_width: 0.25,
+            fill: None,
+        }
+    }
+}
+
+/// A grayscale mask scaling the effect's intensity per pixel.
+///
+/// The red channel of the image is stretched over the camera's viewport: where
+/// it is white the effect applies fully, where it is black not at all. The
+/// image's first row is at the top of the viewport on every platform, the same
+/// way the image would appear in UI. Swap the handle, or modify the image, to
+/// animate the mask.
+#[derive(Component, Reflect, Debug, Clone, Default, ExtractComponent)]
+#[reflect(Component, Default)]
+pub struct GlitchMask(pub Handle<Image>);
+
+// encase's `ShaderType` derive leaves const checks behind that newer compilers
+// report as dead code, so the uniform lives in a module that allows it.
+#[allow(dead_code)]
+mod uniform_layout {
+    use super::*;
+
+    /// The flat, tightly packed form of [`VideoGlitchSettings`] the shader reads.
+    ///
+    /// It is produced from the settings when they are extracted to the render
+    /// world and must match `VideoGlitchSettings` in `video-glitch.wgsl`.
+    #[derive(Component, Clone, Copy, ShaderType)]
+    pub struct VideoGlitchUniform {
+        pub(super) intensity: f32,
+        pub(super) speed: f32,
+        pub(super) displacement: f32,
+        pub(super) aberration_offset: f32,
+        // The columns of `ColorSettings::aberration`, with its cycle speed in the
+        // first column's w and the aberration scope in the second's. The third's
+        // w is the full image U left of which the source is shown untouched,
+        // negative without a `GlitchComparisonSplit`.
+        pub(super) color_aberration: [Vec4; 3],
+        pub(super) interference: f32,
+        pub(super) scanlines: f32,
+        // How far a `GlitchWipe` has swept, negative without one.
+        pub(super) wipe_progress: f32,
+        // The `*_FLAG` bits, and the fields packed from `*_SHIFT`.
+        pub(super) flags: u32,
+        pub(super) exclusion_rects: [Vec4; MAX_EXCLUSION_RECTS],
+        // The camera's viewport in texture UV as (origin, size).
+        pub(super) viewport: Vec4,
+        // The time from a `GlitchTimeOverride` or `GlitchClock`.
+        pub(super) time: f32,
+        // The frame rate the time is held to, 0 for continuous time.
+        pub(super) effect_fps: f32,
+        // The depth fade's distances in multiples of the near plane of the
+        // camera's perspective projection, which linearizes depth, or both 0 for
+        // other projections.
+        pub(super) depth_near: f32,
+        pub(super) depth_far: f32,
+        // The camera's viewport within the full image of its `SubCameraView` in
+        // full image UV as (origin, size), the whole image without one.
+        pub(super) sub_view: Vec4,
+        // The `GlitchHotspot`s in view as (full image UV, radius as a fraction of
+        // the full image height, strength).
+        pub(super) hotspots: [Vec4; MAX_GLITCH_HOTSPOTS],
+        // The falloff of each hotspot as packed unorm8s.
+        pub(super) hotspot_falloff: u32,
+        // The direction a `GlitchWipe` sweeps in, in radians.
+        pub(super) wipe_angle: f32,
+        // The width of a `GlitchWipe`'s edge, as a fraction of the full image
+        // height.
+        pub(super) wipe_edge: f32,
+        // The fill color of a `GlitchWipe` as packed sRGBA8, 0 without one.
+        pub(super) wipe_fill: u32,
+    }
+}
+pub use uniform_layout::VideoGlitchUniform;
+
+// The bits of `VideoGlitchUniform::flags`: snap offsets to whole pixels, use
+// `time` instead of the globals' time, fade the effect out with distance and
+// shift rows to the left, away from a `GlitchHit` from the right.
+const PIXEL_SNAP_FLAG: u32 = 1 << 0;
+const USE_TIME_FLAG: u32 = 1 << 1;
+const DEPTH_INVERT_FLAG: u32 = 1 << 2;
+const TEAR_LEFT_FLAG: u32 = 1 << 3;
+// Where `flags` holds the number of exclusion rects, in 4 bits.
+const EXCLUSION_COUNT_SHIFT: u32 = 4;
+// The next bits of `flags` are only read on the CPU to select the shader: the
+// subpixel samples per tear in 8 bits, 0 without antialiasing, a bit to mix
+// the overlay in perceptual space and the `GlitchNoiseMode` in 2 bits.
+const ANTIALIAS_SHIFT: u32 = 8;
+const PERCEPTUAL_FLAG: u32 = 1 << 16;
+const NOISE_SHIFT: u32 = 17;
+// The top 13 bits of `flags` hold `ColorSettings::aberration_angle` as steps
+// of a turn, which leaves the rest of the uniform to fit in push constants.
+const ABERRATION_ANGLE_SHIFT: u32 = 19;
+const ABERRATION_ANGLE_STEPS: f32 = (1 << (32 - ABERRATION_ANGLE_SHIFT)) as f32;
+
+// The size `VideoGlitchSettings` has in `video-glitch.wgsl`. Adding, removing
+// or reordering a field here fails the assertions below until the shader's
+// struct, and this size, are updated to match. The tests compare the offset of
+// every field with the shader's.
+const WGSL_SETTINGS_SIZE: u64 = 256;
+
+const _: () = assert!(
+    <VideoGlitchUniform as bevy::render::render_resource::encase::ShaderSize>::SHADER_SIZE.get()
+        == WGSL_SETTINGS_SIZE,
+    "VideoGlitchUniform no longer matches VideoGlitchSettings in video-glitch.wgsl"
+);
+
+// WebGL2 requires uniform structs to be a multiple of 16 bytes. The layout
+// above meets that on every target without padding; keep it that way.
+const _: () = assert!(
+    WGSL_SETTINGS_SIZE.is_multiple_of(16),
+    "VideoGlitchUniform needs padding to a multiple of 16 bytes for WebGL2"
+);
+
+impl From<&VideoGlitchSettings> for VideoGlitchUniform {
+    fn from(settings: &VideoGlitchSettings) -> Self {
+        let taps = match settings.displacement.antialias {
+            0 | 1 => 0,
+            taps => taps.min(MAX_ANTIALIAS),
+        };
+        let mut flags =
+            (taps << ANTIALIAS_SHIFT) | ((settings.displacement.noise_mode as u32) << NOISE_SHIFT);
+        if settings.displacement.pixel_snap {
+            flags |= PIXEL_SNAP_FLAG;
+        }
+        if settings.depth.invert {
+            flags |= DEPTH_INVERT_FLAG;
+        }
+        if settings.overlay.color_space == GlitchColorSpace::Perceptual {
+            flags |= PERCEPTUAL_FLAG;
+        }
+        let turns = (settings.color.aberration_angle / std::f32::consts::TAU).rem_euclid(1.0);
+        flags |= ((turns * ABERRATION_ANGLE_STEPS).round() as u32 % ABERRATION_ANGLE_STEPS as u32)
+            << ABERRATION_ANGLE_SHIFT;
+        Self {
+            intensity: settings.response.apply(settings.intensity),
+            speed: settings.displacement.speed,
+            displacement: settings.displacement.amount,
+            aberration_offset: settings.color.aberration_offset,
+            color_aberration: [
+                settings
+                    .color
+                    .aberration
+                    .x_axis
+                    .extend(settings.color.aberration_cycle_speed),
+                settings
+                    .color
+                    .aberration
+                    .y_axis
+                    .extend(settings.color.aberration_scope.clamp(0.0, 1.0)),
+                settings.color.aberration.z_axis.extend(-1.0),
+            ],
+            interference: settings.overlay.interference,
+            scanlines: settings.overlay.scanlines,
+            wipe_progress: -1.0,
+            flags,
+            exclusion_rects: [Vec4::ZERO; MAX_EXCLUSION_RECTS],
+            viewport: Vec4::new(0.0, 0.0, 1.0, 1.0),
+            time: 0.0,
+            effect_fps: settings.effect_fps.max(0.0),
+            depth_near: settings.depth.near,
+            depth_far: settings.depth.far,
+            sub_view: Vec4::new(0.0, 0.0, 1.0, 1.0),
+            hotspots: [Vec4::ZERO; MAX_GLITCH_HOTSPOTS],
+            hotspot_falloff: 0,
+            wipe_angle: 0.0,
+            wipe_edge: 0.0,
+            wipe_fill: 0,
+        }
+    }
+}
+
+impl VideoGlitchUniform {
+    /// Whether the effect leaves every pixel as it is.
+    pub(crate) fn is_noop(&self) -> bool {
+        let unglitched = self.intensity <= 0.0 && !self.has_hotspots();
+        let clean = if self.has_wipe() {
+            // A wipe that hasn't started covers nothing.
+            self.wipe_progress <= 0.0 || (unglitched && self.wipe_fill >> 24 == 0)
+        } else {
+            unglitched
+        };
+        clean && !self.has_comparison()
+    }
+
+    pub(crate) fn intensity(&self) -> f32 {
+        self.intensity
+    }
+
+    /// The time driving the effect given the globals' time, as `effect_time`
+    /// in `video-glitch.wgsl` computes it.
+    pub(crate) fn effect_time(&self, globals_time: f32) -> f32 {
+        let mut time = if self.flags & USE_TIME_FLAG != 0 {
+            self.time
+        } else {
+            globals_time
+        };
+        if self.effect_fps > 0.0 {
+            time = (time * self.effect_fps).floor() / self.effect_fps;
+        }
+        time * self.speed
+    }
+
+    /// Map a viewport UV to the UV of the full image, as `full_uv` in
+    /// `video-glitch.wgsl` does.
+    pub(crate) fn full_uv(&self, uv: Vec2) -> Vec2 {
+        self.sub_view.xy() + uv * self.sub_view.zw()
+    }
+
+    pub(crate) fn has_comparison(&self) -> bool {
+        self.color_aberration[2].w >= 0.0
+    }
+
+    pub(crate) fn has_wipe(&self) -> bool {
+        self.wipe_progress >= 0.0
+    }
+
+    pub(crate) fn has_hotspots(&self) -> bool {
+        self.hotspots.iter().any(|hotspot| hotspot.w > 0.0)
+    }
+
+    pub(crate) fn has_exclusion_rects(&self) -> bool {
+        (self.flags >> EXCLUSION_COUNT_SHIFT) & 0xf > 0
+    }
+
+    pub(crate) fn has_interference(&self) -> bool {
+        self.interference != 0.0
+    }
+
+    pub(crate) fn has_scanlines(&self) -> bool {
+        self.scanlines != 0.0
+    }
+
+    pub(crate) fn antialias_taps(&self) -> u32 {
+        (self.flags >> ANTIALIAS_SHIFT) & 0xff
+    }
+
+    pub(crate) fn is_perceptual(&self) -> bool {
+        self.flags & PERCEPTUAL_FLAG != 0
+    }
+
+    pub(crate) fn noise_mode(&self) -> GlitchNoiseMode {
+        match (self.flags >> NOISE_SHIFT) & 0b11 {
+            1 => GlitchNoiseMode::Value,
+            2 => GlitchNoiseMode::Cell,
+            _ => GlitchNoiseMode::Hash,
+        }
+    }
+
+    /// Whether the effect fades with depth, where the view has a depth
+    /// prepass.
+    pub(crate) fn has_depth(&self) -> bool {
+        self.depth_far > self.depth_near
+    }
+
+    fn with_exclusion_rects(mut self, rects: &GlitchExclusionRects) -> Self {
+        let rects = &rects.0[..rects.0.len().min(MAX_EXCLUSION_RECTS)];
+        for (uniform, rect) in self.exclusion_rects.iter_mut().zip(rects) {
+            *uniform = rect.min.extend(rect.max.x).extend(rect.max.y);
+        }
+        self.flags |= (rects.len() as u32) << EXCLUSION_COUNT_SHIFT;
+        self
+    }
+
+    fn with_hotspots(mut self, in_view: &GlitchHotspotsInView) -> Self {
+        self.hotspots = in_view.hotspots;
+        self.hotspot_falloff = u32::from_le_bytes(
+            in_view
+                .falloff
+                .extend(0.0)
+                .to_array()
+                .map(|falloff| (falloff.clamp(0.0, 1.0) * 255.0).round() as u8),
+        );
+        self
+    }
+
+    fn with_comparison_split(mut self, split: &GlitchComparisonSplit) -> Self {
+        self.color_aberration[2].w = split.0.clamp(0.0, 1.0);
+        self
+    }
+
+    fn with_wipe(mut self, wipe: &GlitchWipe) -> Self {
+        self.wipe_progress = wipe.progress.clamp(0.0, 1.0);
+        self.wipe_angle = wipe.angle;
+        // The shader's ramps need some width.
+        self.wipe_edge = wipe.edge_width.max(1e-4);
+        self.wipe_fill = wipe
+            .fill
+            .map_or(0, |fill| u32::from_le_bytes(fill.to_srgba().to_u8_array()));
+        self
+    }
+
+    fn with_hit(mut self, hit: &GlitchHit) -> Self {
+        if hit.tears_left() {
+            self.flags |= TEAR_LEFT_FLAG;
+        }
+        self
+    }
+
+    fn with_time(mut self, time: f32) -> Self {
+        self.time = time;
+        self.flags |= USE_TIME_FLAG;
+        self
+    }
+
+    fn with_viewport(mut self, camera: &Camera) -> Self {
+        if let (Some(target_size), Some(viewport)) =
+            (camera.physical_target_size(), &camera.viewport)
+        {
+            let target_size = target_size.as_vec2();
+            let origin = viewport.physical_position.as_vec2() / target_size;
+            let size = viewport.physical_size.as_vec2() / target_size;
+            self.viewport = Vec4::new(origin.x, origin.y, size.x, size.y);
+        }
+        self
+    }
+
+    // Tiles of a larger image share the full image's coordinates, so that
+    // they stitch seamlessly. The offsets are in viewport widths and are
+    // scaled to stay the same size on the full image.
+    fn with_sub_view(mut self, camera: &Camera) -> Self {
+        let sub_view = sub_view_uv(camera);
+        self.sub_view = sub_view
+            .min
+            .extend(sub_view.width())
+            .extend(sub_view.height());
+        self.displacement /= sub_view.width();
+        self.aberration_offset /= sub_view.width();
+        self
+    }
+
+    fn with_projection(mut self, projection: Option<&Projection>) -> Self {
+        match projection {
+            Some(Projection::Perspective(perspective)) if perspective.near > 0.0 => {
+                self.depth_near /= perspective.near;
+                self.depth_far /= perspective.near;
+            }
+            // Depth can't be linearized, so the effect doesn't fade with it.
+            _ => {
+                self.depth_near = 0.0;
+                self.depth_far = 0.0;
+            }
+        }
+        self
+    }
+}
+
+/// The camera's viewport within the full image of its [`SubCameraView`], in
+/// the UV of the full image. The whole image without a sub view.
+///
+/// [`SubCameraView`]: bevy::render::camera::SubCameraView
+pub(crate) fn sub_view_uv(camera: &Camera) -> Rect {
+    match &camera.sub_camera_view {
+        Some(sub_view)
+            if sub_view.full_size.cmpgt(UVec2::ZERO).all()
+                && sub_view.size.cmpgt(UVec2::ZERO).all() =>
+        {
+            let full_size = sub_view.full_size.as_vec2();
+            let min = sub_view.offset / full_size;
+            Rect::from_corners(min, min + sub_view.size.as_vec2() / full_size)
+        }
+        _ => Rect::new(0.0, 0.0, 1.0, 1.0),
+    }
+}
+
+impl VideoGlitchSettings {
+    /// The settings a camera ends up with: its layers combined, the volumes
+    /// around it blended in, with the intensity its modulators give it.
+    pub(crate) fn effective(
+        settings: Option<&Self>,
+        layers: Option<&VideoGlitchLayers>,
+        volumes: Option<&GlitchVolumesAround>,
+        modulations: Option<&GlitchModulations>,
+    ) -> Option<Self> {
+        let mut effective = match layers {
+            None => *settings?,
+            // The camera's own settings are the bottom layer.
+            Some(layers) => Self::combine(
+                settings
+                    .map(|settings| (settings, 1.0))
+                    .into_iter()
+                    .chain(layers.0.iter().map(|layer| (&layer.settings, layer.weight))),
+            )?,
+        };
+        for (volume, weight) in volumes.iter().flat_map(|volumes| &volumes.volumes) {
+            effective = effective.lerp(volume, *weight);
+        }
+        if let Some(modulations) = modulations {
+            effective.intensity = modulations.combined.apply(effective.intensity);
+        }
+        Some(effective)
+    }
+}
+
+impl ExtractComponent for VideoGlitchSettings {
+    type QueryData = (
+        Option<&'static Self>,
+        Option<&'static VideoGlitchLayers>,
+        Option<&'static GlitchExclusionRects>,
+        Option<&'static GlitchTimeOverride>,
+        Option<&'static GlitchClock>,
+        Option<&'static Camera>,
+        Option<&'static Projection>,
+        Option<&'static GlitchHotspotsInView>,
+        Option<&'static GlitchHit>,
+        Option<&'static GlitchComparisonSplit>,
+        Option<&'static GlitchModulations>,
+        Option<&'static GlitchWipe>,
+        Option<&'static GlitchVolumesAround>,
+    );
+    type QueryFilter = Or<(With<Self>, With<VideoGlitchLayers>)>;
+    type Out = VideoGlitchUniform;
+
+    fn extract_component(
+        (
+            settings,
+            layers,
+            exclusion_rects,
+            time_override,
+            clock,
+            camera,
+            projection,
+            hotspots,
+            hit,
+            comparison_split,
+            modulations,
+            wipe,
+            volumes,
+        ): QueryItem<'_, Self::QueryData>,
+    ) -> Option<Self::Out> {
+        let mut uniform: VideoGlitchUniform =
+            (&Self::effective(settings, layers, volumes, modulations)?).into();
+        if let Some(exclusion_rects) = exclusion_rects {
+            uniform = uniform.with_exclusion_rects(exclusion_rects);
+        }
+        // An explicit time wins over the camera's clock.
+        if let Some(time) = time_override
+            .map(|time| time.0)
+            .or(clock.map(GlitchClock::elapsed))
+        {
+            uniform = uniform.with_time(time);
+        }
+        if let Some(camera) = camera {
+            uniform = uniform.with_viewport(camera).with_sub_view(camera);
+        }
+        uniform = uniform.with_projection(projection);
+        if let Some(hotspots) = hotspots {
+            uniform = uniform.with_hotspots(hotspots);
+        }
+        if let Some(hit) = hit {
+            uniform = uniform.with_hit(hit);
+        }
+        if let Some(comparison_split) = comparison_split {
+            uniform = uniform.with_comparison_split(comparison_split);
+        }
+        if let Some(wipe) = wipe {
+            uniform = uniform.with_wipe(wipe);
+        }
+        Some(uniform)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::render::camera::SubCameraView;
+
+    use super::*;
+
+    // A quarter of a 200 by 100 image, at `offset`.
+    fn tile(offset: Vec2) -> Camera {
+        Camera {
+            sub_camera_view: Some(SubCameraView {
+                full_size: UVec2::new(200, 100),
+                offset,
+                size: UVec2::new(100, 50),
+            }),
+            ..default()
+        }
+    }
+
+    #[test]
+    fn uniform_layout_matches_the_shader() {
+        // Only the struct, the rest of the shader needs bevy's preprocessor.
+        let source = include_str!("../assets/shaders/video-glitch.wgsl");
+        let start = source.find("struct VideoGlitchSettings {").unwrap();
+        let end = start + source[start..].find("\n}").unwrap() + 2;
+        let module = naga::front::wgsl::parse_str(&source[start..end]).unwrap();
+        let (_, ty) = module
+            .types
+            .iter()
+            .find(|(_, ty)| ty.name.as_deref() == Some("VideoGlitchSettings"))
+            .unwrap();
+        let naga::TypeInner::Struct { members, span } = &ty.inner else {
+            panic!("VideoGlitchSettings is not a struct");
+        };
+
+        // Every field holds different values, so reading one at the wrong
+        // offset fails.
+        let v = |i: f32| Vec4::new(i, i + 0.25, i + 0.5, i + 0.75);
+        let uniform = VideoGlitchUniform {
+            intensity: 1.0,
+            speed: 2.0,
+            displacement: 3.0,
+            aberration_offset: 4.0,
+            color_aberration: [v(5.0), v(6.0), v(7.0)],
+            interference: 8.0,
+            scanlines: 9.0,
+            wipe_progress: 10.0,
+            flags: 11,
+            exclusion_rects: [v(12.0), v(13.0), v(14.0), v(15.0)],
+            viewport: v(16.0),
+            time: 17.0,
+            effect_fps: 18.0,
+            depth_near: 19.0,
+            depth_far: 20.0,
+            sub_view: v(21.0),
+            hotspots: [v(22.0), v(23.0), v(24.0)],
+            hotspot_falloff: 25,
+            wipe_angle: 26.0,
+            wipe_edge: 27.0,
+            wipe_fill: 28,
+        };
+        let mut buffer =
+            bevy::render::render_resource::encase::UniformBuffer::new(Vec::<u8>::new());
+        buffer.write(&uniform).unwrap();
+        let bytes = buffer.into_inner();
+
+        let float = |value: f32| value.to_le_bytes().to_vec();
+        let uint = |value: u32| value.to_le_bytes().to_vec();
+        let vec4s = |values: &[Vec4]| -> Vec<u8> {
+            values
+                .iter()
+                .flat_map(Vec4::to_array)
+                .flat_map(f32::to_le_bytes)
+                .collect()
+        };
+        let fields = [
+            ("intensity", float(uniform.intensity)),
+            ("speed", float(uniform.speed)),
+            ("displacement", float(uniform.displacement)),
+            ("aberration_offset", float(uniform.aberration_offset)),
+            ("color_aberration", vec4s(&uniform.color_aberration)),
+            ("interference", float(uniform.interference)),
+            ("scanlines", float(uniform.scanlines)),
+            ("wipe_progress", float(uniform.wipe_progress)),
+            ("flags", uint(uniform.flags)),
+            ("exclusion_rects", vec4s(&uniform.exclusion_rects)),
+            ("viewport", vec4s(&[uniform.viewport])),
+            ("time", float(uniform.time)),
+            ("effect_fps", float(uniform.effect_fps)),
+            ("depth_near", float(uniform.depth_near)),
+            ("depth_far", float(uniform.depth_far)),
+            ("sub_view", vec4s(&[uniform.sub_view])),
+            ("hotspots", vec4s(&uniform.hotspots)),
+            ("hotspot_falloff", uint(uniform.hotspot_falloff)),
+            ("wipe_angle", float(uniform.wipe_angle)),
+            ("wipe_edge", float(uniform.wipe_edge)),
+            ("wipe_fill", uint(uniform.wipe_fill)),
+        ];
+        let names: Vec<_> = members.iter().filter_map(|m| m.name.as_deref()).collect();
+        assert_eq!(names, fields.each_ref().map(|(name, _)| *name));
+        for (member, (name, value)) in members.iter().zip(&fields) {
+            let offset = member.offset as usize;
+            assert_eq!(
+                bytes[offset..offset + value.len()],
+                value[..],
+                "{name} is at a different offset in the shader"
+            );
+        }
+        assert_eq!(*span as u64, WGSL_SETTINGS_SIZE);
+        assert_eq!(bytes.len() as u64, WGSL_SETTINGS_SIZE);
+    }
+
+    #[test]
+    // For the checks `ShaderType`'s derive leaves behind, as on the uniform.
+    #[allow(dead_code)]
+    fn default_settings_keep_the_old_uniforms_bytes() {
+        use bevy::render::render_resource::encase::UniformBuffer;
+
+        // The uniform before the settings were split from it. Its fields kept
+        // their offsets, the new fields fill its padding.
+        #[derive(ShaderType)]
+        struct OldUniform {
+            intensity: f32,
+            color_aberration: Mat3,
+        }
+        let mut old = UniformBuffer::new(Vec::<u8>::new());
+        old.write(&OldUniform {
+            intensity: 1.0,
+            color_aberration: Mat3::IDENTITY,
+        })
+        .unwrap();
+        let old = old.into_inner();
+        let uniform = VideoGlitchUniform::from(&VideoGlitchSettings::default());
+        let mut new = UniformBuffer::new(Vec::<u8>::new());
+        new.write(&uniform).unwrap();
+        let new = new.into_inner();
+
+        assert_eq!(old.len(), 64);
+        assert_eq!(new[0..4], old[0..4]);
+        for column in [16, 32, 48] {
+            assert_eq!(new[column..column + 12], old[column..column + 12]);
+        }
+
+        // The constants the old shader used in place of the new fields.
+        assert_eq!(uniform.speed, 2.0);
+        assert_eq!(uniform.displacement, 0.25);
+        assert_eq!(uniform.aberration_offset, 0.05);
+        assert_eq!(uniform.interference, 0.3);
+        assert_eq!(uniform.scanlines, 0.15);
+    }
+
+    #[test]
+    fn tiles_stitch_into_the_full_image() {
+        let settings = VideoGlitchSettings::default();
+        let whole = VideoGlitchUniform::from(&settings).with_sub_view(&Camera::default());
+        let [top_left, top_right, bottom_left, bottom_right] = [
+            Vec2::ZERO,
+            Vec2::new(100.0, 0.0),
+            Vec2::new(0.0, 50.0),
+            Vec2::new(100.0, 50.0),
+        ]
+        .map(|offset| VideoGlitchUniform::from(&settings).with_sub_view(&tile(offset)));
+
+        // Neighbouring tiles meet where the full image continues.
+        for t in [0.0, 0.3, 1.0] {
+            assert_eq!(
+                top_left.full_uv(Vec2::new(1.0, t)),
+                top_right.full_uv(Vec2::new(0.0, t))
+            );
+            assert_eq!(
+                top_left.full_uv(Vec2::new(t, 1.0)),
+                bottom_left.full_uv(Vec2::new(t, 0.0))
+            );
+            assert_eq!(
+                bottom_left.full_uv(Vec2::new(1.0, t)),
+                bottom_right.full_uv(Vec2::new(0.0, t))
+            );
+        }
+        // Together they cover the image the whole camera would render.
+        assert_eq!(top_left.full_uv(Vec2::ZERO), whole.full_uv(Vec2::ZERO));
+        assert_eq!(bottom_right.full_uv(Vec2::ONE), whole.full_uv(Vec2::ONE));
+        assert_eq!(
+            bottom_right.full_uv(Vec2::new(0.5, 0.5)),
+            whole.full_uv(Vec2::new(0.75, 0.75))
+        );
+
+        // Offsets in viewport widths tear each tile as far as the full image.
+        for tile in [top_left, top_right, bottom_left, bottom_right] {
+            assert_eq!(tile.displacement * tile.sub_view.z, whole.displacement);
+            assert_eq!(
+                tile.aberration_offset * tile.sub_view.z,
+                whole.aberration_offset
+            );
+        }
+    }
+}
@@ -0,0 +1,296 @@
+//! Follow the bursts the shader tears the screen with on the CPU, so gameplay
+//! can react to them.
+//!
+//! The functions here mirror the noise in `video-glitch.wgsl` step by step,
+//! in the same order and at the same precision. They are kept in lockstep
+//! with the shader: a change to one is a change to the other.
+use bevy::{prelude::*, render::extract_component::ExtractComponent};
+
+use crate::{
+    hotspot::GlitchHotspotsInView, modulation::GlitchModulations, volume::GlitchVolumesAround,
+    GlitchClock, GlitchComparisonSplit, GlitchExclusionRects, GlitchHit, GlitchNoiseMode,
+    GlitchTimeOverride, GlitchWipe, VideoGlitchLayers, VideoGlitchSettings,
+};
+
+/// The columns and rows of the viewport [`GlitchSignal`] samples the noise
+/// at, at the centers of the cells.
+const SIGNAL_CELLS: UVec2 = UVec2::new(8, 16);
+
+/// The cells per unit of time and row of the noise, and across the viewport,
+/// for [`GlitchNoiseMode::Cell`]. `CELL_SIZE` in the shader.
+const CELL_SIZE: Vec3 = Vec3::new(4.0, 40.0, 8.0);
+
+/// Add this to a camera to have it filled in every frame with how much of the
+/// screen the effect's bursts tear, e.g. to shake the screen, crackle a sound
+/// or rumble a controller in sync with them. Changing it has no effect.
+///
+/// The bursts are the large noise waves of the shader, evaluated at the same
+/// time for a grid of 8 by 16 cells of the viewport. Only the camera's own
+/// intensity is taken into account: hotspots, hits, masks, exclusion rects,
+/// depth and wipes, which only change where the bursts show, are left out.
+///
+/// The shader's hash noise takes the sine of large values, which GPUs compute
+/// less precisely than CPUs, so for [`GlitchNoiseMode::Value`] and
+/// [`GlitchNoiseMode::Cell`] the cells may occasionally differ near the edges
+/// of a burst. The default mode matches.
+///
+/// It is written in [`PostUpdate`] once every [`GlitchModulator`] has been
+/// combined.
+///
+/// [`GlitchModulator`]: crate::GlitchModulator
+#[derive(Component, Reflect, Debug, Clone, Copy, Default, PartialEq)]
+#[reflect(Component, Default)]
+pub struct GlitchSignal {
+    /// How strongly the bursts tear the screen, from 0 to 1: the strength of
+    /// the bursts averaged over the cells, times the camera's intensity.
+    pub activity: f32,
+    /// How many of the cells a burst tears.
+    pub cells_active: u32,
+}
+
+pub(crate) fn update_glitch_signals(
+    time: Res<Time>,
+    mut cameras: Query<(
+        &mut GlitchSignal,
+        (
+            Option<&VideoGlitchSettings>,
+            Option<&VideoGlitchLayers>,
+            Option<&GlitchExclusionRects>,
+            Option<&GlitchTimeOverride>,
+            Option<&GlitchClock>,
+            Option<&Camera>,
+            Option<&Projection>,
+            Option<&GlitchHotspotsInView>,
+            Option<&GlitchHit>,
+            Option<&GlitchComparisonSplit>,
+            Option<&GlitchModulations>,
+            Option<&GlitchWipe>,
+            Option<&GlitchVolumesAround>,
+        ),
+    )>,
+) {
+    for (mut signal, inputs) in &mut cameras {
+        // The same extraction the render world does, so the time and
+        // intensity match what is uploaded.
+        let Some(uniform) = VideoGlitchSettings::extract_component(inputs) else {
+            signal.set_if_neq(GlitchSignal::default());
+            continue;
+        };
+        let effect_time = uniform.effect_time(time.elapsed_secs_wrapped());
+        let noise_mode = uniform.noise_mode();
+
+        let mut total = 0.0;
+        let mut cells_active = 0;
+        for row in 0..SIGNAL_CELLS.y {
+            for column in 0..SIGNAL_CELLS.x {
+                let uv = uniform
+                    .full_uv((UVec2::new(column, row).as_vec2() + 0.5) / SIGNAL_CELLS.as_vec2());
+                let burst = burst(noise_mode, uv, effect_time);
+                if burst > 0.0 {
+                    cells_active += 1;
+                }
+                total += burst;
+            }
+        }
+        let cells = (SIGNAL_CELLS.x * SIGNAL_CELLS.y) as f32;
+        signal.set_if_neq(GlitchSignal {
+            activity: (total / cells * uniform.intensity()).clamp(0.0, 1.0),
+            cells_active: if uniform.intensity() > 0.0 {
+                cells_active
+            } else {
+                0
+            },
+        });
+    }
+}
+
+// The large, incidental noise waves of `row_noise` at a full image UV, from 0
+// outside of a burst to 1.
+fn burst(mode: GlitchNoiseMode, uv: Vec2, time: f32) -> f32 {
+    (noise_field(mode, Vec2::new(time, uv.y * 0.3), uv.x) - 0.3).max(0.0) * (1.0 / 0.7)
+}
+
+// The shader's `noise_field`, with the mode picked at runtime rather than by
+// shader defs.
+fn noise_field(mode: GlitchNoiseMode, p: Vec2, x: f32) -> f32 {
+    match mode {
+        GlitchNoiseMode::Hash => snoise(p),
+        GlitchNoiseMode::Value => value_noise(p),
+        GlitchNoiseMode::Cell => cell_noise(p, x),
+    }
+}
+
+// WGSL's `fract`, which glam's differs from for negative values.
+fn fract(x: f32) -> f32 {
+    x - x.floor()
+}
+
+fn fract3(x: Vec3) -> Vec3 {
+    x - x.floor()
+}
+
+fn mod289(x: Vec3) -> Vec3 {
+    x - (x * (1.0 / 289.0)).floor() * 289.0
+}
+
+fn mod289v2(x: Vec2) -> Vec2 {
+    x - (x * (1.0 / 289.0)).floor() * 289.0
+}
+
+fn permute(x: Vec3) -> Vec3 {
+    mod289((x * 34.0 + 1.0) * x)
+}
+
+fn snoise(v: Vec2) -> f32 {
+    const C: Vec4 = Vec4::new(
+        0.21132487,  // (3.0-sqrt(3.0))/6.0
+        0.36602542,  // 0.5*(sqrt(3.0)-1.0)
+        -0.57735026, // -1.0 + 2.0 * C.x
+        0.024390243, // 1.0 / 41.0
+    );
+
+    // First corner
+    let mut i = (v + v.dot(Vec2::splat(C.y))).floor();
+    let x0 = v - i + i.dot(Vec2::splat(C.x));
+
+    // Other corners
+    let i1 = if x0.x > x0.y {
+        Vec2::new(1.0, 0.0)
+    } else {
+        Vec2::new(0.0, 1.0)
+    };
+    let mut x12 = Vec4::new(x0.x, x0.y, x0.x, x0.y) + Vec4::new(C.x, C.x, C.z, C.z);
+    x12.x -= i1.x;
+    x12.y -= i1.y;
+
+    // Permutations
+    i = mod289v2(i);
+    let p = permute(permute(i.y + Vec3::new(0.0, i1.y, 1.0)) + i.x + Vec3::new(0.0, i1.x, 1.0));
+
+    let mut m = (Vec3::splat(0.5)
+        - Vec3::new(x0.dot(x0), x12.xy().dot(x12.xy()), x12.zw().dot(x12.zw())))
+    .max(Vec3::ZERO);
+    m = m * m;
+    m = m * m;
+
+    // Gradients: 41 points uniformly over a line, mapped onto a diamond.
+    let x = 2.0 * fract3(p * C.w) - 1.0;
+    let h = x.abs() - 0.5;
+    let ox = (x + 0.5).floor();
+    let a0 = x - ox;
+
+    // Normalise gradients implicitly by scaling m
+    m *= 1.7928429 - 0.85373473 * (a0 * a0 + h * h);
+
+    // Compute final noise value at P
+    let g = Vec3::new(
+        a0.x * x0.x + h.x * x0.y,
+        a0.y * x12.x + h.y * x12.y,
+        a0.z * x12.z + h.z * x12.w,
+    );
+    130.0 * m.dot(g)
+}
+
+fn rand(co: Vec2) -> f32 {
+    fract((co.dot(Vec2::new(12.9898, 78.233))).sin() * 43758.547)
+}
+
+fn value_noise(p: Vec2) -> f32 {
+    let i = p.floor();
+    let f = p - i;
+    let u = f * f * (3.0 - 2.0 * f);
+    let a = rand(i).lerp(rand(i + Vec2::new(1.0, 0.0)), u.x);
+    let b = rand(i + Vec2::new(0.0, 1.0)).lerp(rand(i + Vec2::new(1.0, 1.0)), u.x);
+    a.lerp(b, u.y) * 2.0 - 1.0
+}
+
+fn cell_noise(p: Vec2, x: f32) -> f32 {
+    let cell = (p.extend(x) * CELL_SIZE).floor();
+    let co = cell.xy() + cell.z * Vec2::new(37.0, 17.0);
+    rand(co) + rand(co + Vec2::new(0.5, 0.25)) - 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Computed in double precision from the original GLSL of the noise, and
+    // for `rand` rounding every step to single precision like the shader.
+    const SNOISE: [(Vec2, f32); 5] = [
+        (Vec2::new(0.0, 0.0), 0.0),
+        (Vec2::new(0.5, 0.25), -0.21835527),
+        (Vec2::new(1.3, -2.7), -0.30233229),
+        (Vec2::new(12.34, 5.67), 0.42518259),
+        (Vec2::new(100.1, 0.3), 0.62987198),
+    ];
+    const RAND: [(Vec2, f32); 3] = [
+        (Vec2::new(1.0, 2.0), 539.0 / 1024.0),
+        (Vec2::new(0.0, 0.0), 0.0),
+        (Vec2::new(3.0, -7.0), 267.0 / 1024.0),
+    ];
+    const VALUE_NOISE: [(Vec2, f32); 2] = [
+        (Vec2::new(0.5, 0.5), -0.12939453),
+        (Vec2::new(2.25, -1.75), 0.16321564),
+    ];
+
+    #[test]
+    fn simplex_noise_matches_the_reference() {
+        for (p, expected) in SNOISE {
+            let noise = snoise(p);
+            assert!((noise - expected).abs() < 1e-4, "snoise({p}) = {noise}");
+        }
+    }
+
+    #[test]
+    fn hash_noise_matches_the_reference() {
+        // Large sines amplify the last bit of the rounding.
+        for (co, expected) in RAND {
+            let noise = rand(co);
+            assert!((noise - expected).abs() < 1e-2, "rand({co}) = {noise}");
+        }
+        for (p, expected) in VALUE_NOISE {
+            let noise = value_noise(p);
+            assert!(
+                (noise - expected).abs() < 1e-2,
+                "value_noise({p}) = {noise}"
+            );
+        }
+    }
+
+    #[test]
+    fn value_noise_hits_the_hash_at_whole_points() {
+        for p in [Vec2::new(1.0, 2.0), Vec2::new(-4.0, 9.0)] {
+            assert_eq!(value_noise(p), rand(p) * 2.0 - 1.0);
+        }
+    }
+
+    #[test]
+    fn cell_noise_is_constant_within_a_cell() {
+        let corner = Vec2::new(1.0, 0.5) / CELL_SIZE.xy();
+        let inside = corner + Vec2::splat(0.1) / CELL_SIZE.xy();
+        assert_eq!(cell_noise(corner, 0.0), cell_noise(inside, 0.05));
+        assert_ne!(
+            cell_noise(corner, 0.0),
+            cell_noise(corner + 1.0 / CELL_SIZE.xy(), 0.0)
+        );
+    }
+
+    #[test]
+    fn bursts_are_fractions() {
+        for mode in [
+            GlitchNoiseMode::Hash,
+            GlitchNoiseMode::Value,
+            GlitchNoiseMode::Cell,
+        ] {
+            let mut bursting = 0;
+            for i in 0..400 {
+                let uv = Vec2::new(i as f32 % 20.0, (i / 20) as f32) / 20.0;
+                let burst = burst(mode, uv, i as f32 * 0.37);
+                assert!((0.0..=1.0).contains(&burst), "{mode:?} {burst}");
+                bursting += (burst > 0.0) as u32;
+            }
+            // Bursts come and go.
+            assert!(0 < bursting && bursting < 400, "{mode:?} {bursting}");
+        }
+    }
+}
@@ -0,0 +1,95 @@
+//! Report what a camera's effect ends up as after everything driving it.
+use bevy::{prelude::*, render::extract_component::ExtractComponent};
+
+use crate::{
+    hotspot::GlitchHotspotsInView, modulation::GlitchModulations, volume::GlitchVolumesAround,
+    GlitchClock, GlitchComparisonSplit, GlitchDebugDisplacement, GlitchExclusionRects,
+    GlitchFreeze, GlitchHit, GlitchTimeOverride, GlitchWipe, VideoGlitchLayers,
+    VideoGlitchSettings,
+};
+
+/// Add this to a camera to have it filled in every frame with what its effect
+/// ends up as, for debugging HUDs and tests. Changing it has no effect.
+///
+/// It is written in [`PostUpdate`] once every [`GlitchModulator`] has been
+/// combined.
+///
+/// [`GlitchModulator`]: crate::GlitchModulator
+#[derive(Component, Reflect, Debug, Clone, Default)]
+#[reflect(Component, Default)]
+pub struct GlitchEffective {
+    /// The settings after combining the camera's [`VideoGlitchLayers`],
+    /// [`GlitchVolume`]s and modulators, with the intensity before its
+    /// response curve.
+    ///
+    /// [`GlitchVolume`]: crate::GlitchVolume
+    pub settings: VideoGlitchSettings,
+    /// The intensity the shader gets, after the response curve, before the
+    /// hotspots add to it.
+    pub intensity: f32,
+    /// What changed the effect this frame: the names of the camera's
+    /// modulators, and `VideoGlitchLayers`, `GlitchVolume`, `GlitchHit` and
+    /// `GlitchHotspot` when they did.
+    pub modulators: Vec<&'static str>,
+    /// Whether the effect's pass is skipped, as it would leave the frame
+    /// unchanged.
+    pub skipped: bool,
+}
+
+pub(crate) fn update_glitch_effective(
+    mut cameras: Query<(
+        &mut GlitchEffective,
+        (
+            Option<&VideoGlitchSettings>,
+            Option<&VideoGlitchLayers>,
+            Option<&GlitchExclusionRects>,
+            Option<&GlitchTimeOverride>,
+            Option<&GlitchClock>,
+            Option<&Camera>,
+            Option<&Projection>,
+            Option<&GlitchHotspotsInView>,
+            Option<&GlitchHit>,
+            Option<&GlitchComparisonSplit>,
+            Option<&GlitchModulations>,
+            Option<&GlitchWipe>,
+            Option<&GlitchVolumesAround>,
+        ),
+        Has<GlitchFreeze>,
+        Has<GlitchDebugDisplacement>,
+    )>,
+) {
+    for (mut effective, inputs, frozen, debug_displacement) in &mut cameras {
+        let (settings, layers, _, _, _, _, _, hotspots, hit, _, modulations, _, volumes) = inputs;
+        // The same extraction the render world does, so the result matches
+        // what is uploaded.
+        let Some(uniform) = VideoGlitchSettings::extract_component(inputs) else {
+            continue;
+        };
+
+        let mut modulators = Vec::new();
+        if layers.is_some() {
+            modulators.push("VideoGlitchLayers");
+        }
+        if volumes.is_some_and(|volumes| !volumes.volumes.is_empty()) {
+            modulators.push("GlitchVolume");
+        }
+        if let Some(modulations) = modulations {
+            modulators.extend(&modulations.names);
+        }
+        if hit.is_some() {
+            modulators.push("GlitchHit");
+        }
+        if hotspots.is_some_and(|hotspots| hotspots.hotspots.iter().any(|hotspot| hotspot.w > 0.0))
+        {
+            modulators.push("GlitchHotspot");
+        }
+
+        *effective = GlitchEffective {
+            settings: VideoGlitchSettings::effective(settings, layers, volumes, modulations)
+                .unwrap_or_default(),
+            intensity: uniform.intensity(),
+            modulators,
+            skipped: uniform.is_noop() && !frozen && !debug_displacement,
+        };
+    }
+}
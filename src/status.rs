@@ -0,0 +1,116 @@
+//! Report from the render world whether the effect's pipelines are ready.
+use std::sync::{Arc, Mutex};
+
+use bevy::prelude::*;
+
+/// Whether the effect is drawing, readable in the main world.
+///
+/// Pipelines compile in the background the first time a camera needs them,
+/// and cameras show the [`crate::GlitchFallback`] until then, the scene
+/// without the effect by default. A loading screen, or a cinematic that opens
+/// on the effect, can wait for [`VideoGlitchPipelineStatus::is_ready`].
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+pub enum VideoGlitchPipelineStatus {
+    /// No camera uses the effect, or there is no renderer to draw it.
+    #[default]
+    Idle,
+    /// The pipeline of at least one camera is still compiling.
+    Queued,
+    /// Every camera with the effect is drawing it.
+    Ready,
+    /// The pipeline of at least one camera failed to compile, with the
+    /// error. Those cameras render without the effect.
+    Failed(String),
+}
+
+impl VideoGlitchPipelineStatus {
+    /// Whether every camera with the effect is drawing it.
+    pub fn is_ready(&self) -> bool {
+        *self == Self::Ready
+    }
+
+    // The status of several cameras is that of the one furthest from ready.
+    pub(crate) fn merge(self, other: Self) -> Self {
+        fn rank(status: &VideoGlitchPipelineStatus) -> u8 {
+            match status {
+                VideoGlitchPipelineStatus::Idle => 0,
+                VideoGlitchPipelineStatus::Ready => 1,
+                VideoGlitchPipelineStatus::Queued => 2,
+                VideoGlitchPipelineStatus::Failed(_) => 3,
+            }
+        }
+        if rank(&other) > rank(&self) {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+/// Sent once when a pipeline of the effect fails to compile, e.g. because of
+/// an error in the shader, with the error. The error is logged as well.
+#[derive(Event, Debug, Clone)]
+pub struct VideoGlitchPipelineError(pub String);
+
+/// The status as last seen by the render world, shared by both worlds.
+#[derive(Resource, Clone, Default)]
+pub(crate) struct SharedPipelineStatus(pub(crate) Arc<Mutex<VideoGlitchPipelineStatus>>);
+
+pub(crate) fn update_pipeline_status(
+    shared: Res<SharedPipelineStatus>,
+    mut status: ResMut<VideoGlitchPipelineStatus>,
+    mut errors: EventWriter<VideoGlitchPipelineError>,
+) {
+    let shared = shared.0.lock().unwrap().clone();
+    if !status.set_if_neq(shared) {
+        return;
+    }
+    if let VideoGlitchPipelineStatus::Failed(error) = &*status {
+        error!("VideoGlitchPlugin: the effect's pipeline failed to compile: {error}");
+        errors.send(VideoGlitchPipelineError(error.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_status_furthest_from_ready_wins() {
+        use VideoGlitchPipelineStatus::*;
+        let failed = Failed("error".into());
+        assert_eq!(Idle.merge(Ready), Ready);
+        assert_eq!(Ready.merge(Queued), Queued);
+        assert_eq!(Queued.merge(Ready), Queued);
+        assert_eq!(Queued.merge(failed.clone()), failed);
+        assert_eq!(failed.clone().merge(Ready), failed);
+    }
+
+    #[test]
+    fn failures_are_reported_once() {
+        let mut app = App::new();
+        let shared = SharedPipelineStatus::default();
+        app.init_resource::<VideoGlitchPipelineStatus>()
+            .add_event::<VideoGlitchPipelineError>()
+            .insert_resource(shared.clone())
+            .add_systems(Update, update_pipeline_status);
+
+        // As the render world does when the shader doesn't compile.
+        *shared.0.lock().unwrap() = VideoGlitchPipelineStatus::Failed("broken shader".into());
+        let mut errors = Vec::new();
+        for _ in 0..3 {
+            app.update();
+            errors.extend(
+                app.world_mut()
+                    .resource_mut::<Events<VideoGlitchPipelineError>>()
+                    .drain()
+                    .map(|error| error.0),
+            );
+        }
+        assert_eq!(
+            *app.world().resource::<VideoGlitchPipelineStatus>(),
+            VideoGlitchPipelineStatus::Failed("broken shader".into())
+        );
+        assert_eq!(errors, ["broken shader"]);
+    }
+}
@@ -0,0 +1,383 @@
+//! Extract each view's settings uniform every frame, but only upload it to the
+//! GPU when it changes.
+use bevy::{
+    prelude::*,
+    render::{
+        extract_component::ExtractComponent,
+        render_resource::{
+            encase, BindingResource, Buffer, BufferBinding, BufferDescriptor, BufferUsages,
+            ShaderType,
+        },
+        renderer::{RenderDevice, RenderQueue},
+        sync_world::RenderEntity,
+        Extract,
+    },
+    utils::HashMap,
+};
+
+use crate::{
+    hotspot::GlitchHotspotsInView, modulation::GlitchModulations, volume::GlitchVolumesAround,
+    GlitchClock, GlitchComparisonSplit, GlitchExclusionRects, GlitchHit, GlitchTimeOverride,
+    GlitchWipe, VideoGlitchLayers, VideoGlitchSettings, VideoGlitchUniform,
+};
+
+/// Marks the views whose uniform differs from the one uploaded last frame.
+#[derive(Component)]
+pub(crate) struct VideoGlitchUniformChanged;
+
+/// The dynamic offset of a view's uniform in [`VideoGlitchUniforms`].
+#[derive(Component, Clone, Copy)]
+pub(crate) struct VideoGlitchUniformOffset(pub(crate) u32);
+
+/// Extract the settings of each camera into its [`VideoGlitchUniform`] and
+/// mark the ones that changed since the last extraction.
+///
+/// The uniforms go on the cameras' render entities, which persist between
+/// frames, so the marker and the uniform are also taken off again.
+pub(crate) fn extract_video_glitch_uniforms(
+    mut commands: Commands,
+    mut previous_len: Local<usize>,
+    views: Extract<
+        Query<
+            (
+                Entity,
+                RenderEntity,
+                Option<Ref<VideoGlitchSettings>>,
+                Option<Ref<VideoGlitchLayers>>,
+                Option<Ref<GlitchExclusionRects>>,
+                Option<Ref<GlitchTimeOverride>>,
+                Option<Ref<GlitchClock>>,
+                Option<Ref<Camera>>,
+                Option<Ref<Projection>>,
+                Option<Ref<GlitchHotspotsInView>>,
+                Option<Ref<GlitchHit>>,
+                Option<Ref<GlitchComparisonSplit>>,
+                Option<Ref<GlitchModulations>>,
+                Option<Ref<GlitchWipe>>,
+                Option<Ref<GlitchVolumesAround>>,
+            ),
+            Or<(With<VideoGlitchSettings>, With<VideoGlitchLayers>)>,
+        >,
+    >,
+    mut removed_settings: Extract<RemovedComponents<VideoGlitchSettings>>,
+    mut removed_layers: Extract<RemovedComponents<VideoGlitchLayers>>,
+    mut removed_rects: Extract<RemovedComponents<GlitchExclusionRects>>,
+    mut removed_time_overrides: Extract<RemovedComponents<GlitchTimeOverride>>,
+    mut removed_clocks: Extract<RemovedComponents<GlitchClock>>,
+    mut removed_hits: Extract<RemovedComponents<GlitchHit>>,
+    mut removed_comparison_splits: Extract<RemovedComponents<GlitchComparisonSplit>>,
+    mut removed_modulations: Extract<RemovedComponents<GlitchModulations>>,
+    mut removed_wipes: Extract<RemovedComponents<GlitchWipe>>,
+    mut removed_volumes: Extract<RemovedComponents<GlitchVolumesAround>>,
+    render_entities: Extract<Query<RenderEntity>>,
+) {
+    // Removing one of the inputs changes the uniform without changing any of
+    // the components that are left.
+    let removed: Vec<Entity> = removed_settings
+        .read()
+        .chain(removed_layers.read())
+        .chain(removed_rects.read())
+        .chain(removed_time_overrides.read())
+        .chain(removed_clocks.read())
+        .chain(removed_hits.read())
+        .chain(removed_comparison_splits.read())
+        .chain(removed_modulations.read())
+        .chain(removed_wipes.read())
+        .chain(removed_volumes.read())
+        .collect();
+
+    let mut changed = Vec::with_capacity(*previous_len);
+    let mut unchanged = Vec::with_capacity(*previous_len);
+    for (
+        entity,
+        render_entity,
+        settings,
+        layers,
+        rects,
+        time_override,
+        clock,
+        camera,
+        projection,
+        hotspots,
+        hit,
+        comparison_split,
+        modulations,
+        wipe,
+        volumes,
+    ) in &views
+    {
+        let is_changed = settings.as_ref().is_some_and(|c| c.is_changed())
+            || layers.as_ref().is_some_and(|c| c.is_changed())
+            || rects.as_ref().is_some_and(|c| c.is_changed())
+            || time_override.as_ref().is_some_and(|c| c.is_changed())
+            || clock.as_ref().is_some_and(|c| c.is_changed())
+            || camera.as_ref().is_some_and(|c| c.is_changed())
+            || projection.as_ref().is_some_and(|c| c.is_changed())
+            || hotspots.as_ref().is_some_and(|c| c.is_changed())
+            || hit.as_ref().is_some_and(|c| c.is_changed())
+            || comparison_split.as_ref().is_some_and(|c| c.is_changed())
+            || modulations.as_ref().is_some_and(|c| c.is_changed())
+            || wipe.as_ref().is_some_and(|c| c.is_changed())
+            || volumes.as_ref().is_some_and(|c| c.is_changed())
+            || removed.contains(&entity);
+        let Some(uniform) = VideoGlitchSettings::extract_component((
+            settings.as_deref(),
+            layers.as_deref(),
+            rects.as_deref(),
+            time_override.as_deref(),
+            clock.as_deref(),
+            camera.as_deref(),
+            projection.as_deref(),
+            hotspots.as_deref(),
+            hit.as_deref(),
+            comparison_split.as_deref(),
+            modulations.as_deref(),
+            wipe.as_deref(),
+            volumes.as_deref(),
+        )) else {
+            commands
+                .entity(render_entity)
+                .remove::<(VideoGlitchUniform, VideoGlitchUniformChanged)>();
+            continue;
+        };
+        if is_changed {
+            changed.push((render_entity, (uniform, VideoGlitchUniformChanged)));
+        } else {
+            commands
+                .entity(render_entity)
+                .remove::<VideoGlitchUniformChanged>();
+            unchanged.push((render_entity, uniform));
+        }
+    }
+    // Cameras that lost the effect altogether keep their render entity.
+    for entity in removed {
+        if views.contains(entity) {
+            continue;
+        }
+        if let Ok(render_entity) = render_entities.get(entity) {
+            commands
+                .entity(render_entity)
+                .remove::<(VideoGlitchUniform, VideoGlitchUniformChanged)>();
+        }
+    }
+    *previous_len = changed.len() + unchanged.len();
+    commands.insert_or_spawn_batch(changed);
+    commands.insert_or_spawn_batch(unchanged);
+}
+
+/// The uniform buffer holding every view's [`VideoGlitchUniform`], in the
+/// render world.
+///
+/// Each view keeps its slot in the buffer for as long as it has the effect,
+/// so a view whose settings don't change isn't written again.
+#[derive(Resource, Default)]
+pub struct VideoGlitchUniforms {
+    buffer: Option<Buffer>,
+    // The distance between slots, the uniform's size rounded up to the
+    // device's dynamic offset alignment.
+    stride: u32,
+    capacity: u32,
+    slots: HashMap<Entity, u32>,
+    // Slots below `len` that views have given up.
+    free: Vec<u32>,
+    len: u32,
+    writes: u64,
+}
+
+impl VideoGlitchUniforms {
+    /// How many uniforms have been written to the GPU so far. With unchanging
+    /// settings this stops growing after each view's first frame.
+    pub fn writes(&self) -> u64 {
+        self.writes
+    }
+
+    pub(crate) fn buffer(&self) -> Option<&Buffer> {
+        self.buffer.as_ref()
+    }
+
+    pub(crate) fn binding(&self) -> Option<BindingResource<'_>> {
+        Some(BindingResource::Buffer(BufferBinding {
+            buffer: self.buffer.as_ref()?,
+            offset: 0,
+            size: Some(VideoGlitchUniform::min_size()),
+        }))
+    }
+
+    fn slot(&mut self, view: Entity) -> (u32, bool) {
+        if let Some(slot) = self.slots.get(&view) {
+            return (*slot, false);
+        }
+        let slot = self.free.pop().unwrap_or_else(|| {
+            self.len += 1;
+            self.len - 1
+        });
+        self.slots.insert(view, slot);
+        (slot, true)
+    }
+}
+
+/// Write the uniforms of new and changed views to the GPU, and give each view
+/// its [`VideoGlitchUniformOffset`].
+pub(crate) fn prepare_video_glitch_uniforms(
+    mut commands: Commands,
+    mut uniforms: ResMut<VideoGlitchUniforms>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    views: Query<(Entity, &VideoGlitchUniform, Has<VideoGlitchUniformChanged>)>,
+) {
+    let uniforms = &mut *uniforms;
+    uniforms.slots.retain(|view, slot| {
+        let keep = views.contains(*view);
+        if !keep {
+            uniforms.free.push(*slot);
+        }
+        keep
+    });
+
+    let mut new_views = Vec::new();
+    for (entity, _, _) in &views {
+        if let (_, true) = uniforms.slot(entity) {
+            new_views.push(entity);
+        }
+    }
+
+    // Growing the buffer loses its contents, so everything is written again.
+    let reallocated = uniforms.buffer.is_none() || uniforms.len > uniforms.capacity;
+    if reallocated {
+        let alignment = render_device.limits().min_uniform_buffer_offset_alignment;
+        uniforms.stride = (VideoGlitchUniform::min_size().get() as u32).next_multiple_of(alignment);
+        uniforms.capacity = uniforms.len.max(1).next_power_of_two();
+        uniforms.buffer = Some(render_device.create_buffer(&BufferDescriptor {
+            label: Some("video_glitch_uniforms"),
+            size: uniforms.stride as u64 * uniforms.capacity as u64,
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        }));
+    }
+    let Some(buffer) = &uniforms.buffer else {
+        return;
+    };
+
+    for (entity, uniform, changed) in &views {
+        let offset = uniforms.slots[&entity] * uniforms.stride;
+        if reallocated || changed || new_views.contains(&entity) {
+            let mut bytes = encase::UniformBuffer::new(Vec::<u8>::new());
+            bytes.write(uniform).unwrap();
+            render_queue.write_buffer(buffer, offset as u64, &bytes.into_inner());
+            uniforms.writes += 1;
+        }
+        commands
+            .entity(entity)
+            .insert(VideoGlitchUniformOffset(offset));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::schedule::ExecutorKind,
+        render::{sync_world::RenderEntity, MainWorld},
+    };
+
+    use super::*;
+
+    // The main world and a render world extracting from it, as the render app
+    // does once a frame.
+    struct Worlds {
+        main: World,
+        render: World,
+        extract: Schedule,
+    }
+
+    impl Worlds {
+        fn new() -> Self {
+            let mut extract = Schedule::default();
+            extract
+                .set_executor_kind(ExecutorKind::SingleThreaded)
+                .add_systems(extract_video_glitch_uniforms);
+            Self {
+                main: World::new(),
+                render: World::new(),
+                extract,
+            }
+        }
+
+        // A camera with `settings` in the main world and its render entity.
+        fn spawn(&mut self, settings: VideoGlitchSettings) -> (Entity, Entity) {
+            let render_entity = self.render.spawn_empty().id();
+            let entity = self
+                .main
+                .spawn((settings, RenderEntity::from(render_entity)))
+                .id();
+            (entity, render_entity)
+        }
+
+        fn extract(&mut self) {
+            self.render.init_resource::<MainWorld>();
+            std::mem::swap(&mut self.main, &mut self.render.resource_mut::<MainWorld>());
+            self.extract.run(&mut self.render);
+            let mut main_world = self.render.remove_resource::<MainWorld>().unwrap();
+            std::mem::swap(&mut self.main, &mut main_world);
+            self.main.clear_trackers();
+        }
+
+        fn changed(&self, render_entity: Entity) -> bool {
+            self.render
+                .entity(render_entity)
+                .contains::<VideoGlitchUniformChanged>()
+        }
+    }
+
+    #[test]
+    fn only_changed_uniforms_are_marked() {
+        let mut worlds = Worlds::new();
+        let (camera, render_camera) = worlds.spawn(VideoGlitchSettings::default());
+
+        worlds.extract();
+        assert!(worlds.changed(render_camera));
+
+        // Nothing changed, so nothing needs writing.
+        for _ in 0..2 {
+            worlds.extract();
+            assert!(!worlds.changed(render_camera));
+            assert!(worlds
+                .render
+                .entity(render_camera)
+                .contains::<VideoGlitchUniform>());
+        }
+
+        worlds
+            .main
+            .get_mut::<VideoGlitchSettings>(camera)
+            .unwrap()
+            .intensity = 0.5;
+        worlds.extract();
+        assert!(worlds.changed(render_camera));
+        assert_eq!(
+            worlds
+                .render
+                .get::<VideoGlitchUniform>(render_camera)
+                .unwrap()
+                .intensity(),
+            0.5
+        );
+    }
+
+    #[test]
+    fn removing_the_settings_removes_the_uniform() {
+        let mut worlds = Worlds::new();
+        let (camera, render_camera) = worlds.spawn(VideoGlitchSettings::default());
+        worlds.extract();
+
+        worlds
+            .main
+            .entity_mut(camera)
+            .remove::<VideoGlitchSettings>();
+        worlds.extract();
+        assert!(!worlds
+            .render
+            .entity(render_camera)
+            .contains::<VideoGlitchUniform>());
+        assert!(!worlds.changed(render_camera));
+    }
+}
@@ -0,0 +1,157 @@
+//! Glitch the cameras inside areas of the world.
+use bevy::prelude::*;
+
+use crate::{bake::GlitchBakeCamera, VideoGlitchLayers, VideoGlitchSettings};
+
+/// An area of the world that blends its own glitch settings into those of
+/// every camera with the effect inside of it, like a post processing volume,
+/// e.g. a room full of corrupted machinery.
+///
+/// The shape is centered on the entity and moves, turns and scales with its
+/// [`GlobalTransform`]. Inside it a camera's settings are replaced by the
+/// volume's; within `blend_distance` outside of it they are blended towards
+/// them, linearly with the distance, so walking in and out fades the look.
+///
+/// Overlapping volumes are applied from the lowest `priority` to the
+/// highest, each blended over the result of the ones before, starting from
+/// the camera's own settings and [`VideoGlitchLayers`].
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component, Default)]
+#[require(Transform)]
+pub struct GlitchVolume {
+    /// The area in which the settings apply fully.
+    pub shape: GlitchVolumeShape,
+    /// How far outside of the shape the settings fade in, in world units. By
+    /// default it has a value of 1.
+    pub blend_distance: f32,
+    /// The order overlapping volumes are applied in, higher ones on top. By
+    /// default it has a value of 0.
+    pub priority: i32,
+    /// The settings the volume blends in.
+    pub settings: VideoGlitchSettings,
+}
+
+impl Default for GlitchVolume {
+    fn default() -> Self {
+        Self {
+            shape: default(),
+            blend_distance: 1.0,
+            priority: 0,
+            settings: default(),
+        }
+    }
+}
+
+/// The shape of a [`GlitchVolume`], in the entity's local space.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Default)]
+pub enum GlitchVolumeShape {
+    /// A box with these half extents along each axis.
+    Box(Vec3),
+    /// A sphere with this radius.
+    Sphere(f32),
+}
+
+impl Default for GlitchVolumeShape {
+    fn default() -> Self {
+        Self::Box(Vec3::splat(0.5))
+    }
+}
+
+impl GlitchVolumeShape {
+    /// The point of the shape closest to `point`, both in local space.
+    fn closest_point(&self, point: Vec3) -> Vec3 {
+        match *self {
+            Self::Box(half_size) => point.clamp(-half_size, half_size),
+            Self::Sphere(radius) => point.clamp_length_max(radius),
+        }
+    }
+}
+
+impl GlitchVolume {
+    /// How much of the volume's settings apply at `position` in the world,
+    /// from 0 outside of its blend distance to 1 inside of it.
+    pub fn weight(&self, transform: &GlobalTransform, position: Vec3) -> f32 {
+        let affine = transform.affine();
+        let local = affine.inverse().transform_point3(position);
+        // Measured in the world, so the blend distance doesn't scale with the
+        // volume.
+        let closest = affine.transform_point3(self.shape.closest_point(local));
+        let distance = closest.distance(position);
+        if self.blend_distance > 0.0 {
+            (1.0 - distance / self.blend_distance).max(0.0)
+        } else if distance <= f32::EPSILON {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// The volumes around a camera, in the order they are applied.
+///
+/// Public only because the settings' extraction queries it; the module is
+/// private.
+#[derive(Component, Debug, Clone, Default)]
+pub struct GlitchVolumesAround {
+    // Which volumes apply how much, to tell whether anything changed.
+    applied: Vec<(Entity, f32)>,
+    pub(crate) volumes: Vec<(VideoGlitchSettings, f32)>,
+}
+
+/// Find the volumes every camera with the effect is in.
+pub(crate) fn find_glitch_volumes(
+    mut commands: Commands,
+    volumes: Query<(Entity, Ref<GlitchVolume>, &GlobalTransform)>,
+    mut cameras: Query<
+        (Entity, &GlobalTransform, Option<&mut GlitchVolumesAround>),
+        (
+            With<Camera>,
+            Or<(With<VideoGlitchSettings>, With<VideoGlitchLayers>)>,
+            Without<GlitchBakeCamera>,
+        ),
+    >,
+) {
+    for (entity, camera_transform, around) in &mut cameras {
+        if volumes.is_empty() && around.is_none() {
+            continue;
+        }
+        let position = camera_transform.translation();
+        let mut applied: Vec<_> = volumes
+            .iter()
+            .filter_map(|(volume_entity, volume, transform)| {
+                let weight = volume.weight(transform, position);
+                (weight > 0.0).then_some((volume.priority, volume_entity, weight, volume))
+            })
+            .collect();
+        // Entities break ties, so equal priorities don't flicker between
+        // orders.
+        applied.sort_by_key(|(priority, entity, ..)| (*priority, *entity));
+
+        let changed = applied.iter().any(|(.., volume)| volume.is_changed())
+            || !around.as_ref().is_some_and(|around| {
+                around.applied.iter().copied().eq(applied
+                    .iter()
+                    .map(|(_, entity, weight, _)| (*entity, *weight)))
+            });
+        if !changed {
+            continue;
+        }
+        let updated = GlitchVolumesAround {
+            applied: applied
+                .iter()
+                .map(|(_, entity, weight, _)| (*entity, *weight))
+                .collect(),
+            volumes: applied
+                .iter()
+                .map(|(.., weight, volume)| (volume.settings, *weight))
+                .collect(),
+        };
+        match around {
+            Some(mut around) => *around = updated,
+            None => {
+                commands.entity(entity).insert(updated);
+            }
+        }
+    }
+}
@@ -0,0 +1,616 @@
+//! A compact, quantized encoding of the settings for replicating them over
+//! the network every tick.
+use bevy::prelude::*;
+
+use crate::{
+    aberration::CHANNEL_PERMUTATIONS, ColorAberration, ColorSettings, ColorVisionDeficiency,
+    DepthSettings, DisplacementSettings, GlitchColorSpace, GlitchNoiseMode, GlitchResponse,
+    OverlaySettings, VideoGlitchSettings,
+};
+
+/// The size of [`VideoGlitchSettings::to_wire`]'s encoding, in bytes.
+pub const GLITCH_WIRE_SIZE: usize = 43;
+
+// The ranges fields that aren't fractions are quantized over. Values outside
+// are clamped.
+const AMOUNT: (f32, f32) = (0.0, 2.0);
+const SPEED: (f32, f32) = (0.0, 64.0);
+const ABERRATION_OFFSET: (f32, f32) = (0.0, 1.0);
+const CYCLE_SPEED: (f32, f32) = (-16.0, 16.0);
+const EFFECT_FPS: (f32, f32) = (0.0, 240.0);
+const DEPTH: (f32, f32) = (0.0, 1024.0);
+const EXPONENT: (f32, f32) = (0.0, 16.0);
+const MATRIX: (f32, f32) = (-2.0, 2.0);
+const UNIT: (f32, f32) = (0.0, 1.0);
+// Angles are sent as their direction, within one turn.
+const TURN: (f32, f32) = (0.0, std::f32::consts::TAU);
+
+// Where each part of the encoding starts: the fractions as bytes from 0, the
+// other floats as 16 bits from 4, in the order of the table on `to_wire`, and
+// the aberration angle after the largest encoding of the matrix.
+const FLAGS: usize = 18;
+const RESPONSE: usize = 19;
+const ABERRATION: usize = 22;
+const ABERRATION_ANGLE: usize = 41;
+
+// How far a blend towards a preset may be from the matrix it encodes.
+const PRESET_TOLERANCE: f32 = 0.002;
+
+// The tag of an aberration matrix sent as is.
+const RAW_ABERRATION: u8 = u8::MAX;
+
+// The bits of the flags byte: snap to pixels, invert the depth fade and mix
+// the overlay in perceptual space, then the noise mode in 2 bits and the
+// antialiasing samples in 3.
+const PIXEL_SNAP_BIT: u8 = 1 << 0;
+const DEPTH_INVERT_BIT: u8 = 1 << 1;
+const PERCEPTUAL_BIT: u8 = 1 << 2;
+const NOISE_SHIFT: u8 = 3;
+const ANTIALIAS_SHIFT: u8 = 5;
+
+impl VideoGlitchSettings {
+    /// Encode the settings in [`GLITCH_WIRE_SIZE`] bytes, for sending them
+    /// every tick, e.g. to spectators of a cutscene. Decode them with
+    /// [`Self::from_wire`].
+    ///
+    /// Fractions are sent as a byte and the other floats as 16 bits over a
+    /// fixed range, outside of which they are clamped. The aberration matrix
+    /// is sent as three bytes when it is a blend from the identity towards a
+    /// permutation of the channels or a [`ColorAberration::cvd_safe`] matrix,
+    /// and as 16 bits per element otherwise. A round trip changes each field
+    /// by at most:
+    ///
+    /// | field                                   | range     | error |
+    /// |-----------------------------------------|-----------|-------|
+    /// | `intensity`                             | 0 to 1    | 0.002 |
+    /// | `overlay.interference`, `scanlines`     | 0 to 1    | 0.002 |
+    /// | `color.aberration_scope`                | 0 to 1    | 0.002 |
+    /// | `displacement.amount`                   | 0 to 2    | 2e-5  |
+    /// | `displacement.speed`                    | 0 to 64   | 5e-4  |
+    /// | `color.aberration_offset`               | 0 to 1    | 8e-6  |
+    /// | `color.aberration_cycle_speed`          | -16 to 16 | 3e-4  |
+    /// | `effect_fps`                            | 0 to 240  | 0.002 |
+    /// | `depth.near`, `depth.far`               | 0 to 1024 | 0.008 |
+    /// | the exponent of `GlitchResponse::Power` | 0 to 16   | 2e-4  |
+    /// | `color.aberration`, per element         | -2 to 2   | 0.002 |
+    /// | `color.aberration_angle`                | any       | 5e-5  |
+    ///
+    /// `color.aberration_angle` arrives as the same direction within one
+    /// turn, from 0 to τ.
+    /// `displacement.antialias` is clamped to 7. The flags, modes and
+    /// response curve are exact. The layout of the bytes is part of the
+    /// crate's API and only changes with a breaking release.
+    pub fn to_wire(&self) -> [u8; GLITCH_WIRE_SIZE] {
+        let mut wire = [0; GLITCH_WIRE_SIZE];
+        wire[0] = quantize_u8(self.intensity, UNIT);
+        wire[1] = quantize_u8(self.overlay.interference, UNIT);
+        wire[2] = quantize_u8(self.overlay.scanlines, UNIT);
+        wire[3] = quantize_u8(self.color.aberration_scope, UNIT);
+        write_u16(&mut wire, 4, self.displacement.amount, AMOUNT);
+        write_u16(&mut wire, 6, self.displacement.speed, SPEED);
+        write_u16(
+            &mut wire,
+            8,
+            self.color.aberration_offset,
+            ABERRATION_OFFSET,
+        );
+        write_u16(
+            &mut wire,
+            10,
+            self.color.aberration_cycle_speed,
+            CYCLE_SPEED,
+        );
+        write_u16(&mut wire, 12, self.effect_fps, EFFECT_FPS);
+        write_u16(&mut wire, 14, self.depth.near, DEPTH);
+        write_u16(&mut wire, 16, self.depth.far, DEPTH);
+
+        let mut flags = 0;
+        if self.displacement.pixel_snap {
+            flags |= PIXEL_SNAP_BIT;
+        }
+        if self.depth.invert {
+            flags |= DEPTH_INVERT_BIT;
+        }
+        if self.overlay.color_space == GlitchColorSpace::Perceptual {
+            flags |= PERCEPTUAL_BIT;
+        }
+        flags |= match self.displacement.noise_mode {
+            GlitchNoiseMode::Hash => 0,
+            GlitchNoiseMode::Value => 1,
+            GlitchNoiseMode::Cell => 2,
+        } << NOISE_SHIFT;
+        flags |= (self.displacement.antialias.min(7) as u8) << ANTIALIAS_SHIFT;
+        wire[FLAGS] = flags;
+
+        let (tag, exponent) = match self.response {
+            GlitchResponse::Linear => (0, 0.0),
+            GlitchResponse::Smoothstep => (1, 0.0),
+            GlitchResponse::Exponential => (2, 0.0),
+            GlitchResponse::Power(exponent) => (3, exponent),
+        };
+        wire[RESPONSE] = tag;
+        write_u16(&mut wire, RESPONSE + 1, exponent, EXPONENT);
+
+        write_u16(
+            &mut wire,
+            ABERRATION_ANGLE,
+            self.color.aberration_angle.rem_euclid(TURN.1),
+            TURN,
+        );
+
+        match preset_blend(self.color.aberration) {
+            Some((preset, blend)) => {
+                wire[ABERRATION] = preset;
+                write_u16(&mut wire, ABERRATION + 1, blend, UNIT);
+            }
+            None => {
+                wire[ABERRATION] = RAW_ABERRATION;
+                for (index, value) in self
+                    .color
+                    .aberration
+                    .to_cols_array()
+                    .into_iter()
+                    .enumerate()
+                {
+                    write_u16(&mut wire, ABERRATION + 1 + 2 * index, value, MATRIX);
+                }
+            }
+        }
+        wire
+    }
+
+    /// Decode settings encoded with [`Self::to_wire`], or `None` if the bytes
+    /// aren't an encoding of settings.
+    pub fn from_wire(wire: &[u8; GLITCH_WIRE_SIZE]) -> Option<Self> {
+        let read = |at, range| read_u16(wire, at, range);
+        let flags = wire[FLAGS];
+        let noise_mode = match (flags >> NOISE_SHIFT) & 0b11 {
+            0 => GlitchNoiseMode::Hash,
+            1 => GlitchNoiseMode::Value,
+            2 => GlitchNoiseMode::Cell,
+            _ => return None,
+        };
+        let response = match wire[RESPONSE] {
+            0 => GlitchResponse::Linear,
+            1 => GlitchResponse::Smoothstep,
+            2 => GlitchResponse::Exponential,
+            3 => GlitchResponse::Power(read(RESPONSE + 1, EXPONENT)),
+            _ => return None,
+        };
+        let aberration = match wire[ABERRATION] {
+            RAW_ABERRATION => Mat3::from_cols_array(&std::array::from_fn(|index| {
+                read(ABERRATION + 1 + 2 * index, MATRIX)
+            })),
+            preset => {
+                let target = aberration_presets().get(preset as usize).copied()?;
+                blend(target, read(ABERRATION + 1, UNIT))
+            }
+        };
+
+        Some(Self {
+            intensity: dequantize_u8(wire[0], UNIT),
+            response,
+            displacement: DisplacementSettings {
+                amount: read(4, AMOUNT),
+                speed: read(6, SPEED),
+                pixel_snap: flags & PIXEL_SNAP_BIT != 0,
+                antialias: (flags >> ANTIALIAS_SHIFT) as u32,
+                noise_mode,
+            },
+            color: ColorSettings {
+                aberration,
+                aberration_offset: read(8, ABERRATION_OFFSET),
+                aberration_cycle_speed: read(10, CYCLE_SPEED),
+                aberration_scope: dequantize_u8(wire[3], UNIT),
+                aberration_angle: read(ABERRATION_ANGLE, TURN),
+            },
+            overlay: OverlaySettings {
+                interference: dequantize_u8(wire[1], UNIT),
+                scanlines: dequantize_u8(wire[2], UNIT),
+                color_space: if flags & PERCEPTUAL_BIT != 0 {
+                    GlitchColorSpace::Perceptual
+                } else {
+                    GlitchColorSpace::Linear
+                },
+            },
+            depth: DepthSettings {
+                near: read(14, DEPTH),
+                far: read(16, DEPTH),
+                invert: flags & DEPTH_INVERT_BIT != 0,
+            },
+            effect_fps: read(12, EFFECT_FPS),
+        })
+    }
+
+    /// Encode the settings as the bytes that differ from `baseline`, an
+    /// encoding from [`Self::to_wire`] both ends already have, e.g. the last
+    /// one acknowledged. Unchanged settings take 6 bytes, and every changed
+    /// byte of the encoding one more. Decode them with
+    /// [`Self::from_wire_delta`].
+    pub fn to_wire_delta(&self, baseline: &[u8; GLITCH_WIRE_SIZE]) -> Vec<u8> {
+        let wire = self.to_wire();
+        let mut mask = [0u8; DELTA_MASK_SIZE];
+        let mut changed = Vec::new();
+        for (index, (byte, base)) in wire.iter().zip(baseline).enumerate() {
+            if byte != base {
+                mask[index / 8] |= 1 << (index % 8);
+                changed.push(*byte);
+            }
+        }
+        let mut delta = mask.to_vec();
+        delta.extend(changed);
+        delta
+    }
+
+    /// Decode settings encoded with [`Self::to_wire_delta`] against the same
+    /// `baseline`, or `None` if the bytes aren't such an encoding.
+    pub fn from_wire_delta(baseline: &[u8; GLITCH_WIRE_SIZE], delta: &[u8]) -> Option<Self> {
+        let (mask, mut changed) = delta.split_at_checked(DELTA_MASK_SIZE)?;
+        let mut wire = *baseline;
+        for (index, byte) in wire.iter_mut().enumerate() {
+            if mask[index / 8] & (1 << (index % 8)) != 0 {
+                let (first, rest) = changed.split_first()?;
+                *byte = *first;
+                changed = rest;
+            }
+        }
+        if !changed.is_empty() {
+            return None;
+        }
+        Self::from_wire(&wire)
+    }
+}
+
+// One bit per byte of the encoding.
+const DELTA_MASK_SIZE: usize = GLITCH_WIRE_SIZE.div_ceil(8);
+
+// The matrices an aberration can be a blend towards, by index on the wire.
+// The identity comes first, blending towards it leaves it as it is.
+fn aberration_presets() -> [Mat3; 9] {
+    let [a, b, c, d, e] = CHANNEL_PERMUTATIONS;
+    [
+        Mat3::IDENTITY,
+        a,
+        b,
+        c,
+        d,
+        e,
+        ColorAberration::cvd_safe(ColorVisionDeficiency::Protanopia),
+        ColorAberration::cvd_safe(ColorVisionDeficiency::Deuteranopia),
+        ColorAberration::cvd_safe(ColorVisionDeficiency::Tritanopia),
+    ]
+}
+
+fn blend(target: Mat3, amount: f32) -> Mat3 {
+    Mat3::IDENTITY * (1.0 - amount) + target * amount
+}
+
+// The preset and blend that reproduce `matrix` within `PRESET_TOLERANCE`
+// once quantized, if any. The blend takes 16 bits, the elements of some
+// presets are too far from the identity for a byte to be precise enough.
+fn preset_blend(matrix: Mat3) -> Option<(u8, f32)> {
+    let offset = (matrix - Mat3::IDENTITY).to_cols_array();
+    aberration_presets()
+        .into_iter()
+        .enumerate()
+        .find_map(|(preset, target)| {
+            // The blend closest to the matrix, by least squares.
+            let direction = (target - Mat3::IDENTITY).to_cols_array();
+            let length = direction.iter().map(|x| x * x).sum::<f32>();
+            let amount = if length > 0.0 {
+                direction
+                    .iter()
+                    .zip(offset)
+                    .map(|(d, o)| d * o)
+                    .sum::<f32>()
+                    / length
+            } else {
+                0.0
+            };
+            let amount = dequantize_u16(quantize_u16(amount, UNIT), UNIT);
+            let decoded = blend(target, amount);
+            (decoded - matrix)
+                .to_cols_array()
+                .iter()
+                .all(|error| error.abs() <= PRESET_TOLERANCE)
+                .then_some((preset as u8, amount))
+        })
+}
+
+fn quantize_u8(value: f32, (min, max): (f32, f32)) -> u8 {
+    ((value.clamp(min, max) - min) / (max - min) * u8::MAX as f32).round() as u8
+}
+
+fn dequantize_u8(value: u8, (min, max): (f32, f32)) -> f32 {
+    min + value as f32 / u8::MAX as f32 * (max - min)
+}
+
+fn quantize_u16(value: f32, (min, max): (f32, f32)) -> u16 {
+    ((value.clamp(min, max) - min) / (max - min) * u16::MAX as f32).round() as u16
+}
+
+fn dequantize_u16(value: u16, (min, max): (f32, f32)) -> f32 {
+    min + value as f32 / u16::MAX as f32 * (max - min)
+}
+
+fn write_u16(wire: &mut [u8], at: usize, value: f32, range: (f32, f32)) {
+    wire[at..at + 2].copy_from_slice(&quantize_u16(value, range).to_le_bytes());
+}
+
+fn read_u16(wire: &[u8], at: usize, range: (f32, f32)) -> f32 {
+    dequantize_u16(u16::from_le_bytes([wire[at], wire[at + 1]]), range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Settings with every field somewhere in its range, a different place for
+    // each `step`.
+    fn settings(step: u32) -> VideoGlitchSettings {
+        let mut field = 0.0;
+        let mut next = |(min, max): (f32, f32)| {
+            field += 1.0;
+            min + (step as f32 * 0.618_034 * field).fract() * (max - min)
+        };
+        VideoGlitchSettings {
+            intensity: next(UNIT),
+            response: GlitchResponse::Power(next(EXPONENT)),
+            displacement: DisplacementSettings {
+                amount: next(AMOUNT),
+                speed: next(SPEED),
+                pixel_snap: step.is_multiple_of(2),
+                antialias: step % 8,
+                noise_mode: [
+                    GlitchNoiseMode::Hash,
+                    GlitchNoiseMode::Value,
+                    GlitchNoiseMode::Cell,
+                ][step as usize % 3],
+            },
+            color: ColorSettings {
+                aberration: Mat3::from_cols_array(&std::array::from_fn(|_| next(MATRIX))),
+                aberration_offset: next(ABERRATION_OFFSET),
+                aberration_cycle_speed: next(CYCLE_SPEED),
+                aberration_scope: next(UNIT),
+                aberration_angle: next(TURN),
+            },
+            overlay: OverlaySettings {
+                interference: next(UNIT),
+                scanlines: next(UNIT),
+                color_space: if step.is_multiple_of(3) {
+                    GlitchColorSpace::Perceptual
+                } else {
+                    GlitchColorSpace::Linear
+                },
+            },
+            depth: DepthSettings {
+                near: next(DEPTH),
+                far: next(DEPTH),
+                invert: step.is_multiple_of(5),
+            },
+            effect_fps: next(EFFECT_FPS),
+        }
+    }
+
+    fn assert_close(name: &str, sent: f32, received: f32, bound: f32) {
+        assert!(
+            (sent - received).abs() <= bound,
+            "{name}: sent {sent}, received {received}"
+        );
+    }
+
+    // The bounds of the table on `to_wire`.
+    fn assert_round_trip(sent: &VideoGlitchSettings, received: &VideoGlitchSettings) {
+        assert_close("intensity", sent.intensity, received.intensity, 0.002);
+        assert_close(
+            "interference",
+            sent.overlay.interference,
+            received.overlay.interference,
+            0.002,
+        );
+        assert_close(
+            "scanlines",
+            sent.overlay.scanlines,
+            received.overlay.scanlines,
+            0.002,
+        );
+        assert_close(
+            "aberration_scope",
+            sent.color.aberration_scope,
+            received.color.aberration_scope,
+            0.002,
+        );
+        assert_close(
+            "amount",
+            sent.displacement.amount,
+            received.displacement.amount,
+            2e-5,
+        );
+        assert_close(
+            "speed",
+            sent.displacement.speed,
+            received.displacement.speed,
+            5e-4,
+        );
+        assert_close(
+            "aberration_offset",
+            sent.color.aberration_offset,
+            received.color.aberration_offset,
+            8e-6,
+        );
+        assert_close(
+            "aberration_cycle_speed",
+            sent.color.aberration_cycle_speed,
+            received.color.aberration_cycle_speed,
+            3e-4,
+        );
+        assert_close("effect_fps", sent.effect_fps, received.effect_fps, 0.002);
+        assert_close("near", sent.depth.near, received.depth.near, 0.008);
+        assert_close("far", sent.depth.far, received.depth.far, 0.008);
+        match (sent.response, received.response) {
+            (GlitchResponse::Power(sent), GlitchResponse::Power(received)) => {
+                assert_close("exponent", sent, received, 2e-4);
+            }
+            (sent, received) => assert_eq!(sent, received),
+        }
+        for (sent, received) in sent
+            .color
+            .aberration
+            .to_cols_array()
+            .into_iter()
+            .zip(received.color.aberration.to_cols_array())
+        {
+            assert_close("aberration", sent, received, 0.002);
+        }
+        assert_close(
+            "aberration_angle",
+            sent.color.aberration_angle.rem_euclid(TURN.1),
+            received.color.aberration_angle,
+            5e-5,
+        );
+
+        assert_eq!(
+            sent.displacement.pixel_snap,
+            received.displacement.pixel_snap
+        );
+        assert_eq!(
+            sent.displacement.antialias.min(7),
+            received.displacement.antialias
+        );
+        assert_eq!(
+            sent.displacement.noise_mode,
+            received.displacement.noise_mode
+        );
+        assert_eq!(sent.overlay.color_space, received.overlay.color_space);
+        assert_eq!(sent.depth.invert, received.depth.invert);
+    }
+
+    #[test]
+    fn round_trips_within_the_documented_bounds() {
+        for step in 0..1000 {
+            let sent = settings(step);
+            let received = VideoGlitchSettings::from_wire(&sent.to_wire()).unwrap();
+            assert_round_trip(&sent, &received);
+        }
+        let sent = VideoGlitchSettings::default();
+        assert_round_trip(
+            &sent,
+            &VideoGlitchSettings::from_wire(&sent.to_wire()).unwrap(),
+        );
+    }
+
+    #[test]
+    fn blends_towards_presets_take_three_bytes() {
+        for (index, preset) in aberration_presets().into_iter().enumerate() {
+            for amount in [0.0, 0.3, 0.77, 1.0] {
+                let mut sent = settings(index as u32);
+                sent.color.aberration = blend(preset, amount);
+                let wire = sent.to_wire();
+                assert_ne!(wire[ABERRATION], RAW_ABERRATION);
+                // Unused by a preset, the rest of the matrix's bytes are left
+                // zeroed.
+                assert!(wire[ABERRATION + 3..ABERRATION_ANGLE]
+                    .iter()
+                    .all(|byte| *byte == 0));
+                assert_round_trip(&sent, &VideoGlitchSettings::from_wire(&wire).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn values_outside_the_ranges_are_clamped() {
+        let mut sent = settings(1);
+        sent.intensity = 3.0;
+        sent.displacement.speed = -1.0;
+        sent.depth.far = 5000.0;
+        sent.displacement.antialias = 100;
+        sent.color.aberration_angle = -1.0;
+        let received = VideoGlitchSettings::from_wire(&sent.to_wire()).unwrap();
+        assert_eq!(received.intensity, 1.0);
+        assert_eq!(received.displacement.speed, 0.0);
+        assert_eq!(received.depth.far, DEPTH.1);
+        assert_eq!(received.displacement.antialias, 7);
+        assert_close(
+            "aberration_angle",
+            TURN.1 - 1.0,
+            received.color.aberration_angle,
+            5e-5,
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_tags() {
+        let wire = settings(2).to_wire();
+
+        let mut bad_noise = wire;
+        bad_noise[FLAGS] |= 0b11 << NOISE_SHIFT;
+        assert_eq!(VideoGlitchSettings::from_wire(&bad_noise), None);
+
+        let mut bad_response = wire;
+        bad_response[RESPONSE] = 4;
+        assert_eq!(VideoGlitchSettings::from_wire(&bad_response), None);
+
+        let mut bad_preset = wire;
+        bad_preset[ABERRATION] = aberration_presets().len() as u8;
+        assert_eq!(VideoGlitchSettings::from_wire(&bad_preset), None);
+    }
+
+    #[test]
+    fn deltas_round_trip() {
+        let baseline = settings(3).to_wire();
+        for step in 0..100 {
+            let sent = settings(step);
+            let delta = sent.to_wire_delta(&baseline);
+            let changed = sent
+                .to_wire()
+                .iter()
+                .zip(baseline)
+                .filter(|(byte, base)| **byte != *base)
+                .count();
+            assert_eq!(delta.len(), DELTA_MASK_SIZE + changed);
+            assert_eq!(
+                VideoGlitchSettings::from_wire_delta(&baseline, &delta),
+                VideoGlitchSettings::from_wire(&sent.to_wire())
+            );
+        }
+    }
+
+    #[test]
+    fn small_changes_make_small_deltas() {
+        let mut sent = settings(4);
+        let baseline = sent.to_wire();
+        let unchanged = sent.to_wire_delta(&baseline);
+        assert_eq!(unchanged.len(), 6);
+        assert_eq!(
+            VideoGlitchSettings::from_wire_delta(&baseline, &unchanged),
+            VideoGlitchSettings::from_wire(&baseline)
+        );
+
+        sent.intensity = (sent.intensity + 0.5).fract();
+        let delta = sent.to_wire_delta(&baseline);
+        assert_eq!(delta.len(), 7);
+        assert_round_trip(
+            &sent,
+            &VideoGlitchSettings::from_wire_delta(&baseline, &delta).unwrap(),
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_deltas() {
+        let baseline = settings(5).to_wire();
+        let delta = settings(6).to_wire_delta(&baseline);
+        // Truncated, in the mask and in the changed bytes.
+        assert_eq!(
+            VideoGlitchSettings::from_wire_delta(&baseline, &delta[..3]),
+            None
+        );
+        assert_eq!(
+            VideoGlitchSettings::from_wire_delta(&baseline, &delta[..delta.len() - 1]),
+            None
+        );
+        // Trailing bytes.
+        let mut long = delta.clone();
+        long.push(0);
+        assert_eq!(VideoGlitchSettings::from_wire_delta(&baseline, &long), None);
+    }
+}
@@ -0,0 +1,469 @@
+//! Tests that render, and so need a GPU. They are ignored by default, run them
+//! with `cargo test --test render -- --ignored`. Without a GPU, Mesa's
+//! llvmpipe works through `WGPU_BACKEND=gl`.
+//!
+//! The `golden_*` tests compare what the effect draws with the reference
+//! images in `examples/golden`, to catch shader changes that alter the
+//! output. After an intended change, replace the references with
+//! `GLITCH_BLESS=1 cargo test --test render golden -- --ignored`.
+use std::path::{Path, PathBuf};
+
+use bevy::{
+    app::PluginsState,
+    image::{CompressedImageFormats, ImageSampler, ImageType},
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        pipelined_rendering::PipelinedRenderingPlugin,
+        render_asset::RenderAssetUsages,
+        render_resource::{
+            Extent3d, PipelineCache, TextureDescriptor, TextureDimension, TextureFormat,
+            TextureUsages,
+        },
+        view::screenshot::{Screenshot, ScreenshotCaptured},
+        RenderApp,
+    },
+    window::ExitCondition,
+    winit::WinitPlugin,
+};
+use bevy_video_glitch::*;
+
+// An app rendering without a window, with its plugins finished.
+fn headless_app(plugin: VideoGlitchPlugin) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                ..default()
+            })
+            .disable::<WinitPlugin>()
+            // Keep the render app in reach, and each frame rendered by the
+            // end of its update.
+            .disable::<PipelinedRenderingPlugin>(),
+        plugin,
+    ));
+    while app.plugins_state() == PluginsState::Adding {
+        bevy::tasks::tick_global_task_pools_on_main_thread();
+    }
+    app.finish();
+    app.cleanup();
+    app
+}
+
+// An image of `size` for a camera to render into and to be read back.
+fn target(app: &mut App, size: UVec2) -> Handle<Image> {
+    let size = Extent3d {
+        width: size.x,
+        height: size.y,
+        ..default()
+    };
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: None,
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_SRC
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    image.resize(size);
+    app.world_mut().resource_mut::<Assets<Image>>().add(image)
+}
+
+// A 3D camera glitched with `settings`, rendering into an image.
+fn spawn_camera(app: &mut App, settings: VideoGlitchSettings) -> Entity {
+    let target = target(app, UVec2::splat(64));
+    app.world_mut()
+        .spawn((
+            Camera3d::default(),
+            Camera {
+                target: RenderTarget::Image(target),
+                ..default()
+            },
+            settings,
+        ))
+        .id()
+}
+
+// Update `app` until `done` or `frames` have passed, collecting the pipeline
+// errors sent meanwhile.
+fn update_until(
+    app: &mut App,
+    frames: usize,
+    done: impl Fn(&VideoGlitchPipelineStatus) -> bool,
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    for _ in 0..frames {
+        app.update();
+        errors.extend(
+            app.world_mut()
+                .resource_mut::<Events<VideoGlitchPipelineError>>()
+                .drain()
+                .map(|error| error.0),
+        );
+        if done(app.world().resource::<VideoGlitchPipelineStatus>()) {
+            break;
+        }
+    }
+    errors
+}
+
+#[test]
+#[ignore = "needs a GPU"]
+fn the_pipeline_becomes_ready() {
+    let mut app = headless_app(VideoGlitchPlugin::default());
+    spawn_camera(&mut app, VideoGlitchSettings::default());
+    let errors = update_until(&mut app, 120, VideoGlitchPipelineStatus::is_ready);
+    assert!(errors.is_empty(), "{errors:?}");
+    assert!(app
+        .world()
+        .resource::<VideoGlitchPipelineStatus>()
+        .is_ready());
+}
+
+// With `hot-reload` the shader comes from the asset server instead of the
+// internal handle.
+#[cfg(not(feature = "hot-reload"))]
+#[test]
+#[ignore = "needs a GPU"]
+fn a_broken_shader_fails_the_pipeline() {
+    let mut app = headless_app(VideoGlitchPlugin::default());
+    app.world_mut().resource_mut::<Assets<Shader>>().insert(
+        &VIDEO_GLITCH_SHADER_HANDLE,
+        Shader::from_wgsl("this is not wgsl", "broken.wgsl"),
+    );
+    spawn_camera(&mut app, VideoGlitchSettings::default());
+
+    let errors = update_until(&mut app, 120, |status| {
+        matches!(status, VideoGlitchPipelineStatus::Failed(_))
+    });
+    let VideoGlitchPipelineStatus::Failed(error) =
+        app.world().resource::<VideoGlitchPipelineStatus>().clone()
+    else {
+        panic!("the pipeline didn't fail");
+    };
+    assert_eq!(errors, [error]);
+}
+
+#[test]
+#[ignore = "needs a GPU"]
+fn unchanged_settings_are_written_once() {
+    let mut app = headless_app(VideoGlitchPlugin::default().with_push_constants(false));
+    let camera = spawn_camera(&mut app, VideoGlitchSettings::default());
+    update_until(&mut app, 120, VideoGlitchPipelineStatus::is_ready);
+    let writes = |app: &App| {
+        app.sub_app(RenderApp)
+            .world()
+            .resource::<VideoGlitchUniforms>()
+            .writes()
+    };
+
+    let before = writes(&app);
+    assert!(before > 0);
+    app.update();
+    app.update();
+    assert_eq!(writes(&app), before);
+
+    app.world_mut()
+        .get_mut::<VideoGlitchSettings>(camera)
+        .unwrap()
+        .intensity = 0.5;
+    app.update();
+    assert_eq!(writes(&app), before + 1);
+}
+
+#[test]
+#[ignore = "needs a GPU"]
+fn raised_limits_compile() {
+    let mut app = headless_app(
+        VideoGlitchPlugin::default()
+            .with_max_taps(256)
+            .with_max_antialias(4),
+    );
+    let mut settings = VideoGlitchSettings::default();
+    settings.displacement.antialias = 4;
+    let camera = spawn_camera(&mut app, settings);
+    app.world_mut()
+        .entity_mut(camera)
+        .insert(GlitchQuality::Custom { taps: 256 });
+    let errors = update_until(&mut app, 120, VideoGlitchPipelineStatus::is_ready);
+    assert!(errors.is_empty(), "{errors:?}");
+    assert!(app
+        .world()
+        .resource::<VideoGlitchPipelineStatus>()
+        .is_ready());
+}
+
+#[test]
+#[ignore = "needs a GPU"]
+fn the_world_leaves_bakes_alone() {
+    let mut app = headless_app(VideoGlitchPlugin::default());
+    // A volume and a hotspot around the temporary camera would glitch the
+    // bake, which has none of its own.
+    app.world_mut().spawn((
+        GlitchVolume {
+            shape: GlitchVolumeShape::Sphere(1000.0),
+            settings: VideoGlitchSettings {
+                intensity: 1.0,
+                ..default()
+            },
+            ..default()
+        },
+        Transform::default(),
+    ));
+    app.world_mut().spawn((
+        GlitchHotspot {
+            radius: GlitchHotspotRadius::World(1000.0),
+            ..default()
+        },
+        Transform::default(),
+    ));
+
+    let source = Image::new_fill(
+        Extent3d {
+            width: 64,
+            height: 64,
+            ..default()
+        },
+        TextureDimension::D2,
+        &[200, 40, 90, 255],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    let expected = source.data.clone();
+    let world = app.world_mut();
+    let source = world.resource_mut::<Assets<Image>>().add(source);
+    let baked = world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+        let mut commands = world.commands();
+        bake_video_glitch(
+            &mut commands,
+            &mut images,
+            source,
+            VideoGlitchSettings {
+                intensity: 0.0,
+                ..default()
+            },
+            1.0,
+        )
+    });
+    world.flush();
+
+    let mut done = false;
+    for _ in 0..600 {
+        app.update();
+        done |= app
+            .world_mut()
+            .resource_mut::<Events<VideoGlitchBaked>>()
+            .drain()
+            .any(|event| event.image == baked);
+        if done {
+            break;
+        }
+    }
+    assert!(done, "the bake didn't finish");
+    let images = app.world().resource::<Assets<Image>>();
+    let actual = &images.get(&baked).unwrap().data;
+    assert!(
+        actual
+            .iter()
+            .zip(&expected)
+            .all(|(actual, expected)| actual.abs_diff(*expected) <= 1),
+        "the bake was glitched"
+    );
+}
+
+// A width whose rows need no padding when read back.
+const GOLDEN_SIZE: UVec2 = UVec2::new(256, 144);
+// The effect's time in every golden case.
+const GOLDEN_TIME: f32 = 1.7;
+// How far apart a channel may be before a pixel counts as different, and the
+// fraction of pixels that may differ, which absorb rounding differences
+// between GPUs.
+const CHANNEL_TOLERANCE: u8 = 8;
+const PIXEL_TOLERANCE: f32 = 0.005;
+
+// Render a fixed scene of unlit stripes, which makes every tear and fringe
+// visible and doesn't depend on lighting, through a camera with `settings`,
+// and compare the result with the reference image `name`. With
+// `GLITCH_BLESS` set the result replaces the reference instead.
+fn golden(name: &str, hdr: bool, settings: VideoGlitchSettings, quality: Option<GlitchQuality>) {
+    let mut app = headless_app(VideoGlitchPlugin::default());
+    let world = app.world_mut();
+    let bar = world
+        .resource_mut::<Assets<Mesh>>()
+        .add(Cuboid::new(0.2, 4.0, 0.2));
+    for i in -8..=8 {
+        let material = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(StandardMaterial {
+                base_color: Color::hsl((i + 8) as f32 * 20.0, 0.8, 0.5),
+                unlit: true,
+                ..default()
+            });
+        world.spawn((
+            Mesh3d(bar.clone()),
+            MeshMaterial3d(material),
+            Transform::from_xyz(i as f32 * 0.4, 0.0, 0.0),
+        ));
+    }
+    let image = target(&mut app, GOLDEN_SIZE);
+    let mut camera = app.world_mut().spawn((
+        Camera3d::default(),
+        Camera {
+            hdr,
+            target: RenderTarget::Image(image.clone()),
+            clear_color: ClearColorConfig::Custom(Color::BLACK),
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+        settings,
+        GlitchTimeOverride(GOLDEN_TIME),
+    ));
+    if let Some(quality) = quality {
+        camera.insert(quality);
+    }
+
+    let errors = update_until(&mut app, 600, VideoGlitchPipelineStatus::is_ready);
+    assert!(errors.is_empty(), "{errors:?}");
+    // The scene's own pipelines may still be compiling.
+    for _ in 0..600 {
+        let pipeline_cache = app.sub_app(RenderApp).world().resource::<PipelineCache>();
+        if pipeline_cache.waiting_pipelines().next().is_none() {
+            break;
+        }
+        app.update();
+    }
+    #[derive(Resource)]
+    struct Captured(Image);
+    app.world_mut().spawn(Screenshot::image(image)).observe(
+        |captured: Trigger<ScreenshotCaptured>, mut commands: Commands| {
+            commands.insert_resource(Captured(captured.event().0.clone()));
+        },
+    );
+    for _ in 0..60 {
+        app.update();
+        if app.world().contains_resource::<Captured>() {
+            break;
+        }
+    }
+    let actual = app
+        .world_mut()
+        .remove_resource::<Captured>()
+        .expect("the screenshot was taken")
+        .0;
+
+    let path = golden_path(name, "png");
+    if std::env::var_os("GLITCH_BLESS").is_some() {
+        save(&actual, &path);
+        return;
+    }
+    if let Err(reason) = compare(&actual, &path) {
+        let actual_path = golden_path(name, "actual.png");
+        save(&actual, &actual_path);
+        panic!("{name}: {reason}, see {}", actual_path.display());
+    }
+}
+
+fn golden_path(name: &str, extension: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("examples/golden")
+        .join(format!("{name}.{extension}"))
+}
+
+fn save(image: &Image, path: &Path) {
+    let image = image
+        .clone()
+        .try_into_dynamic()
+        .expect("captured image converts");
+    image.to_rgba8().save(path).expect("reference is writable");
+}
+
+// Compare pixel by pixel, allowing for small differences between GPUs.
+fn compare(image: &Image, path: &Path) -> Result<(), String> {
+    let bytes = std::fs::read(path)
+        .map_err(|err| format!("no reference ({err}), run with GLITCH_BLESS=1 to create it"))?;
+    let reference = Image::from_buffer(
+        &bytes,
+        ImageType::Extension("png"),
+        CompressedImageFormats::NONE,
+        true,
+        ImageSampler::Default,
+        RenderAssetUsages::default(),
+    )
+    .map_err(|err| format!("unreadable reference: {err}"))?;
+    let actual = image
+        .clone()
+        .try_into_dynamic()
+        .map_err(|err| format!("unreadable result: {err}"))?
+        .to_rgba8();
+
+    if reference.size() != UVec2::new(actual.width(), actual.height()) {
+        return Err(format!(
+            "size {}x{} differs from the reference's {}",
+            actual.width(),
+            actual.height(),
+            reference.size()
+        ));
+    }
+    let different = actual
+        .as_raw()
+        .chunks_exact(4)
+        .zip(reference.data.chunks_exact(4))
+        .filter(|(a, b)| {
+            a.iter()
+                .zip(*b)
+                .any(|(a, b)| a.abs_diff(*b) > CHANNEL_TOLERANCE)
+        })
+        .count();
+    let fraction = different as f32 / (GOLDEN_SIZE.x * GOLDEN_SIZE.y) as f32;
+    if fraction > PIXEL_TOLERANCE {
+        return Err(format!("{:.2}% of the pixels differ", fraction * 100.0));
+    }
+    Ok(())
+}
+
+#[test]
+#[ignore = "needs a GPU"]
+fn golden_default() {
+    golden("default", false, VideoGlitchSettings::default(), None);
+}
+
+#[test]
+#[ignore = "needs a GPU"]
+fn golden_hdr() {
+    golden("hdr", true, VideoGlitchSettings::default(), None);
+}
+
+#[test]
+#[ignore = "needs a GPU"]
+fn golden_heavy() {
+    let heavy = VideoGlitchSettings {
+        intensity: 1.0,
+        displacement: DisplacementSettings {
+            amount: 0.4,
+            antialias: 4,
+            ..default()
+        },
+        color: ColorSettings {
+            aberration: ColorAberration::cvd_safe(ColorVisionDeficiency::Deuteranopia),
+            aberration_offset: 0.08,
+            ..default()
+        },
+        overlay: OverlaySettings {
+            interference: 0.6,
+            scanlines: 0.4,
+            color_space: GlitchColorSpace::Perceptual,
+        },
+        ..default()
+    };
+    golden("heavy", false, heavy, Some(GlitchQuality::High));
+}